@@ -0,0 +1,167 @@
+use crate::session::{SessionArgs, infer_sessions};
+use crate::{Scan, ScanArgs, finish_export, scan};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+
+#[derive(clap::Args, Debug)]
+pub struct AwArgs {
+    #[command(flatten)]
+    pub scan: ScanArgs,
+
+    /// ActivityWatch server URL
+    #[arg(long, default_value = "http://localhost:5600")]
+    pub url: String,
+
+    /// Bucket to write into [default: work-git_<hostname>]
+    #[arg(long)]
+    pub bucket: Option<String>,
+
+    #[command(flatten)]
+    pub sessions: SessionArgs,
+
+    /// Exit with status 1 if any repo couldn't be scanned
+    #[arg(long)]
+    pub strict: bool,
+}
+
+fn hostname() -> String {
+    let mut buf = [0u8; 256];
+    let rc = unsafe { libc::gethostname(buf.as_mut_ptr().cast(), buf.len()) };
+    if rc != 0 {
+        return "unknown".to_string();
+    }
+    let end = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+    String::from_utf8_lossy(&buf[..end]).into_owned()
+}
+
+fn iso_utc(ts: i64) -> String {
+    use chrono::{TimeZone, Utc};
+    Utc.timestamp_opt(ts, 0)
+        .single()
+        .map(|d| d.to_rfc3339())
+        .unwrap_or_else(|| ts.to_string())
+}
+
+fn http_err(url: &str, e: ureq::Error) -> String {
+    match e {
+        ureq::Error::Io(_) | ureq::Error::ConnectionFailed | ureq::Error::HostNotFound => {
+            format!("activitywatch: cannot reach {url} (is aw-server running?)")
+        }
+        e => format!("activitywatch: {e}"),
+    }
+}
+
+/// Pushes commits (zero-duration events) and inferred sessions into an
+/// ActivityWatch bucket. Every event carries a stable `data.key`, so running
+/// the sync again only sends what's new and refreshes sessions that grew.
+pub fn sync(aw: AwArgs) -> Result<(), String> {
+    let scan = scan(&aw.scan)?;
+    let sent = send(&aw, &scan);
+    finish_export(aw.strict, &scan, sent)
+}
+
+fn send(aw: &AwArgs, scan: &Scan) -> Result<(), String> {
+    let host = hostname();
+    let bucket = aw
+        .bucket
+        .clone()
+        .unwrap_or_else(|| format!("work-git_{host}"));
+    let api = format!("{}/api/0/buckets/{bucket}", aw.url.trim_end_matches('/'));
+
+    let rel = |p: &std::path::Path| scan.rel(p).display().to_string();
+
+    // Creating a bucket that already exists is a no-op on the server side.
+    ureq::post(&api)
+        .send_json(json!({
+            "client": "work",
+            "type": "app.git.activity",
+            "hostname": host,
+        }))
+        .map_err(|e| http_err(&aw.url, e))?;
+
+    let mut events = Vec::new();
+    for c in &scan.commits {
+        events.push(json!({
+            "timestamp": iso_utc(c.time),
+            "duration": 0,
+            "data": {
+                "key": format!("commit:{}", c.oid),
+                "kind": "commit",
+                "repo": rel(&c.repo),
+                "hash": c.oid.to_string(),
+                "summary": c.summary,
+                "insertions": c.insertions,
+                "deletions": c.deletions,
+            },
+        }));
+    }
+    for s in infer_sessions(&scan.commits, aw.sessions.gap_secs()) {
+        let repo = rel(&s.repo);
+        events.push(json!({
+            "timestamp": iso_utc(s.start),
+            "duration": s.end - s.start,
+            "data": {
+                "key": format!("session:{repo}:{}", s.start),
+                "kind": "session",
+                "repo": repo,
+                "commits": s.commits,
+                "insertions": s.insertions,
+                "deletions": s.deletions,
+            },
+        }));
+    }
+
+    // Look back one session lead so sessions that started before the window
+    // are still matched against what we sent last time.
+    let existing: Vec<Value> = ureq::get(format!("{api}/events"))
+        .query(
+            "start",
+            iso_utc(scan.since - crate::session::SESSION_LEAD_SECS),
+        )
+        .query("limit", "-1")
+        .call()
+        .map_err(|e| http_err(&aw.url, e))?
+        .body_mut()
+        .read_json()
+        .map_err(|e| format!("activitywatch: unexpected response: {e}"))?;
+
+    let mut known: HashMap<String, (Option<i64>, f64)> = HashMap::new();
+    for e in &existing {
+        if let Some(key) = e["data"]["key"].as_str() {
+            known.insert(
+                key.to_string(),
+                (e["id"].as_i64(), e["duration"].as_f64().unwrap_or(0.0)),
+            );
+        }
+    }
+
+    let mut fresh = Vec::new();
+    let mut updated = 0;
+    for e in events {
+        let key = e["data"]["key"].as_str().unwrap_or_default();
+        match known.get(key) {
+            Some((_, d)) if *d == e["duration"].as_f64().unwrap_or(0.0) => continue,
+            Some((Some(id), _)) => {
+                ureq::delete(format!("{api}/events/{id}"))
+                    .call()
+                    .map_err(|e| http_err(&aw.url, e))?;
+                updated += 1;
+            }
+            _ => {}
+        }
+        fresh.push(e);
+    }
+
+    if !fresh.is_empty() {
+        ureq::post(format!("{api}/events"))
+            .send_json(Value::Array(fresh.clone()))
+            .map_err(|e| http_err(&aw.url, e))?;
+    }
+
+    println!(
+        "Sent {} events to {bucket} ({} new, {updated} updated)",
+        fresh.len(),
+        fresh.len() - updated
+    );
+    Ok(())
+}
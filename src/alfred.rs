@@ -0,0 +1,100 @@
+use crate::forge::{commit_url, repo_web_url};
+use crate::render::Renderer;
+use crate::{CommitLine, Scan, format_time_local};
+use serde_json::json;
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+/// `--alfred`: an Alfred script filter result, one item per commit, whose
+/// `arg` is the commit's page on the forge (or the hash when the repo has no
+/// web remote) so the default action opens it and ⌘C copies the hash.
+pub struct Alfred;
+
+impl Renderer for Alfred {
+    fn render(&self, out: &mut dyn Write, scan: &Scan, commits: &[&CommitLine]) -> io::Result<()> {
+        let mut urls: HashMap<&PathBuf, Option<String>> = HashMap::new();
+
+        let items: Vec<_> = commits
+            .iter()
+            .map(|c| {
+                let repo = scan.rel(&c.repo).display().to_string();
+                let hash = c.oid.to_string();
+                let short = &c.short;
+                let url = urls
+                    .entry(&c.repo)
+                    // It has commits, so it opened fine for the scan.
+                    .or_insert_with(|| repo_web_url(&c.repo).ok().flatten())
+                    .as_ref()
+                    .map(|web| commit_url(web, &hash));
+
+                let mut item = json!({
+                    "uid": hash,
+                    "title": c.summary,
+                    "subtitle": format!(
+                        "{}  {repo}  {short}  {}f +{} -{}",
+                        format_time_local(c.time),
+                        c.files,
+                        c.insertions,
+                        c.deletions
+                    ),
+                    "arg": url.as_deref().unwrap_or(&hash),
+                    "match": format!("{} {repo} {short}", c.summary),
+                    "autocomplete": c.summary,
+                    "text": {
+                        "copy": hash,
+                        "largetype": match &c.body {
+                            Some(body) => format!("{}\n\n{body}", c.summary),
+                            None => c.summary.clone(),
+                        },
+                    },
+                });
+                if let Some(url) = url {
+                    item["quicklookurl"] = json!(url);
+                }
+                item
+            })
+            .collect();
+
+        writeln!(out, "{}", json!({ "items": items }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_commits_as_script_filter_items() {
+        let scan = Scan::stub(
+            &["/code"],
+            vec![CommitLine {
+                summary: "Fix login".to_string(),
+                body: Some("Retry on 401.".to_string()),
+                files: 1,
+                insertions: 3,
+                deletions: 1,
+                ..CommitLine::stub("/code/api", 1_772_452_800)
+            }],
+        );
+        let commits: Vec<&CommitLine> = scan.commits.iter().collect();
+        let mut out = Vec::new();
+        Alfred.render(&mut out, &scan, &commits).unwrap();
+
+        let v: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        let item = &v["items"][0];
+        let hash = "0".repeat(40);
+        assert_eq!(item["title"], "Fix login");
+        // No forge remote to open, so the action gets the hash.
+        assert_eq!(item["arg"], hash.as_str());
+        assert!(item.get("quicklookurl").is_none());
+        assert!(
+            item["subtitle"]
+                .as_str()
+                .unwrap()
+                .ends_with("  api  0000000  1f +3 -1")
+        );
+        assert_eq!(item["text"]["largetype"], "Fix login\n\nRetry on 401.");
+        assert_eq!(item["match"], "Fix login api 0000000");
+    }
+}
@@ -0,0 +1,146 @@
+use crate::{Args, scan};
+use clap::Parser;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use work::fixtures::FixtureRepo;
+
+#[derive(clap::Args, Debug)]
+pub struct BenchArgs {
+    /// Number of synthetic repos to generate
+    #[arg(long, value_name = "N")]
+    pub generate: usize,
+
+    /// Most commits in any one repo (each gets between 1 and this many)
+    #[arg(long, default_value = "200")]
+    pub commits: usize,
+
+    /// Generator seed; the same seed builds the same tree
+    #[arg(long, default_value = "1")]
+    pub seed: u64,
+
+    /// Number of timed scans
+    #[arg(long, default_value = "3")]
+    pub runs: usize,
+
+    /// Build the tree here and keep it, instead of in a temp dir that's removed afterwards
+    #[arg(long, value_name = "DIR")]
+    pub dir: Option<PathBuf>,
+}
+
+/// splitmix64: tiny, seedable, and the same on every platform.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `1..=max`, skewed towards small values by squaring, so
+    /// most repos are quiet and a few are busy, like a real `~/code`.
+    fn skewed(&mut self, max: usize) -> usize {
+        let max = max.max(1) as u64;
+        let r = self.next() % max;
+        ((r * r) / max + 1) as usize
+    }
+}
+
+/// Builds `n` repos, two levels deep, with commits spread over the last 30
+/// days and files of varying size. Returns the number of commits made.
+fn generate(root: &std::path::Path, args: &BenchArgs) -> Result<usize, String> {
+    let mut rng = Rng(args.seed);
+    let now = crate::now().timestamp();
+    let month = 30 * 24 * 60 * 60;
+    let mut total = 0;
+
+    for i in 0..args.generate {
+        let path = root
+            .join(format!("group{}", i % 10))
+            .join(format!("repo{i}"));
+        let repo = FixtureRepo::init(&path)
+            .map_err(|e| format!("work: cannot create '{}': {e}", path.display()))?;
+
+        let count = rng.skewed(args.commits);
+        let mut times: Vec<i64> = (0..count)
+            .map(|_| now - (rng.next() % month as u64) as i64)
+            .collect();
+        times.sort_unstable();
+
+        for (n, t) in times.into_iter().enumerate() {
+            let lines = rng.skewed(2000);
+            let contents: String = (0..lines)
+                .map(|l| format!("line {l} of commit {n}\n"))
+                .collect();
+            let file = format!("src/file{}.txt", rng.next() % 20);
+            repo.commit(t, &format!("Change {n} in repo {i}"), &[(&file, &contents)])
+                .map_err(|e| format!("work: commit in '{}': {e}", path.display()))?;
+        }
+        total += count;
+    }
+    Ok(total)
+}
+
+fn secs(d: Duration) -> String {
+    format!("{:.3}s", d.as_secs_f64())
+}
+
+/// Hidden `work bench`: generates a synthetic tree and times full scans of
+/// it, so releases can be compared on the same input.
+pub fn bench(args: BenchArgs) -> Result<(), String> {
+    let (root, keep) = match &args.dir {
+        Some(d) => (d.clone(), true),
+        None => (
+            std::env::temp_dir().join(format!("work-bench-{}", std::process::id())),
+            false,
+        ),
+    };
+    if root.exists() {
+        return Err(format!("work: '{}' already exists", root.display()));
+    }
+
+    let started = Instant::now();
+    let result = generate(&root, &args).and_then(|commits| {
+        println!(
+            "generated {} repos, {commits} commits in {} ({})",
+            args.generate,
+            secs(started.elapsed()),
+            root.display()
+        );
+
+        let root_s = root.display().to_string();
+        let scan_args = Args::parse_from([
+            "work", "--all", "--quiet", "--days", "31", "-L", "3", &root_s,
+        ])
+        .scan;
+        let mut times = Vec::new();
+        for run in 1..=args.runs.max(1) {
+            let started = Instant::now();
+            let scan = scan(&scan_args)?;
+            let took = started.elapsed();
+            println!(
+                "run {run}: {}  ({} repos, {} commits)",
+                secs(took),
+                scan.repos.len(),
+                scan.commits.len()
+            );
+            times.push(took);
+        }
+        times.sort();
+        println!(
+            "min {}  median {}  max {}",
+            secs(times[0]),
+            secs(times[times.len() / 2]),
+            secs(times[times.len() - 1])
+        );
+        Ok(())
+    });
+
+    if !keep {
+        let _ = fs::remove_dir_all(&root);
+    }
+    result
+}
@@ -0,0 +1,185 @@
+use crate::{CommitLine, Scan, ScanArgs, paint, report_warnings, scan};
+use std::collections::HashMap;
+
+#[derive(clap::Args, Debug)]
+pub struct ChurnArgs {
+    #[command(flatten)]
+    pub scan: ScanArgs,
+
+    /// Files listed per repo
+    #[arg(short = 'n', long, default_value = "10")]
+    pub top: usize,
+
+    /// Raw output for piping (tab-separated)
+    #[arg(short, long)]
+    pub raw: bool,
+}
+
+/// One file's share of a repo's commits in the window.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Hotspot {
+    path: String,
+    commits: usize,
+    insertions: usize,
+    deletions: usize,
+}
+
+/// Each repo's most-changed files, by commits touching them and then by
+/// lines, with the repos ordered by their busiest file.
+fn hotspots(scan: &Scan, commits: &[CommitLine], top: usize) -> Vec<(String, Vec<Hotspot>)> {
+    let mut by_repo: HashMap<String, HashMap<&str, Hotspot>> = HashMap::new();
+    for c in commits {
+        let files = by_repo.entry(scan.name(&c.repo)).or_default();
+        for f in &c.changes {
+            let h = files.entry(&f.path).or_default();
+            h.commits += 1;
+            h.insertions = h.insertions.saturating_add(f.insertions);
+            h.deletions = h.deletions.saturating_add(f.deletions);
+        }
+    }
+
+    let mut out: Vec<(String, Vec<Hotspot>)> = by_repo
+        .into_iter()
+        .map(|(repo, files)| {
+            let mut files: Vec<Hotspot> = files
+                .into_iter()
+                .map(|(path, h)| Hotspot {
+                    path: path.to_string(),
+                    ..h
+                })
+                .collect();
+            files.sort_by(|a, b| {
+                b.commits
+                    .cmp(&a.commits)
+                    .then_with(|| (b.insertions + b.deletions).cmp(&(a.insertions + a.deletions)))
+                    .then_with(|| a.path.cmp(&b.path))
+            });
+            files.truncate(top);
+            (repo, files)
+        })
+        .filter(|(_, files)| !files.is_empty())
+        .collect();
+    out.sort_by(|(ra, a), (rb, b)| b[0].commits.cmp(&a[0].commits).then_with(|| ra.cmp(rb)));
+    out
+}
+
+/// `work churn`: the files changed in the most commits in the window, per
+/// repo, with the lines added and removed across those commits.
+pub fn churn(args: ChurnArgs) -> Result<(), String> {
+    let scan_args = ScanArgs {
+        file_changes: true,
+        ..args.scan.clone()
+    };
+    let scan = scan(&scan_args)?;
+    let repos = hotspots(&scan, &scan.commits, args.top);
+    if repos.is_empty() {
+        report_warnings(&scan);
+        return Err(args.scan.no_commits());
+    }
+
+    if args.raw {
+        // repo\tpath\tcommits\tinsertions\tdeletions
+        for (repo, files) in &repos {
+            for h in files {
+                println!(
+                    "{repo}\t{}\t{}\t{}\t{}",
+                    h.path, h.commits, h.insertions, h.deletions
+                );
+            }
+        }
+    } else {
+        for (i, (repo, files)) in repos.iter().enumerate() {
+            if i > 0 {
+                println!();
+            }
+            println!("{}", paint("1", repo));
+            let commits_width = files[0].commits.to_string().len();
+            let ins_width = files
+                .iter()
+                .map(|h| h.insertions.to_string().len())
+                .max()
+                .unwrap_or(1);
+            let del_width = files
+                .iter()
+                .map(|h| h.deletions.to_string().len())
+                .max()
+                .unwrap_or(1);
+            for h in files {
+                println!(
+                    "  {:>commits_width$} commits  {} {}  {}",
+                    h.commits,
+                    paint(
+                        "32",
+                        format!("{:>w$}", format!("+{}", h.insertions), w = ins_width + 1)
+                    ),
+                    paint(
+                        "31",
+                        format!("{:>w$}", format!("-{}", h.deletions), w = del_width + 1)
+                    ),
+                    h.path
+                );
+            }
+        }
+        println!(
+            "\n{} commits in {} repos ({})",
+            scan.commits.len(),
+            repos.len(),
+            args.scan.window().label()
+        );
+    }
+    report_warnings(&scan);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FileChange;
+
+    fn touching(repo: &str, files: &[(&str, usize)]) -> CommitLine {
+        CommitLine {
+            changes: files
+                .iter()
+                .map(|(path, ins)| FileChange {
+                    path: path.to_string(),
+                    insertions: *ins,
+                    deletions: 1,
+                    mode_only: false,
+                })
+                .collect(),
+            ..CommitLine::stub(repo, 0)
+        }
+    }
+
+    #[test]
+    fn ranks_files_by_commits_then_lines() {
+        let scan = Scan::stub(&["/code"], Vec::new());
+        let commits = vec![
+            touching("/code/api", &[("src/routes.rs", 10), ("README.md", 1)]),
+            touching("/code/api", &[("src/routes.rs", 5), ("src/db.rs", 40)]),
+            touching("/code/api", &[("src/routes.rs", 1), ("src/db.rs", 2)]),
+            touching("/code/web", &[("app.ts", 3)]),
+        ];
+        let got = hotspots(&scan, &commits, 2);
+        assert_eq!(got.len(), 2);
+        assert_eq!(got[0].0, "api");
+        assert_eq!(
+            got[0].1,
+            [
+                Hotspot {
+                    path: "src/routes.rs".to_string(),
+                    commits: 3,
+                    insertions: 16,
+                    deletions: 3,
+                },
+                Hotspot {
+                    path: "src/db.rs".to_string(),
+                    commits: 2,
+                    insertions: 42,
+                    deletions: 2,
+                },
+            ]
+        );
+        assert_eq!(got[1].0, "web");
+    }
+}
@@ -0,0 +1,127 @@
+use crate::{Scan, ScanArgs, paint, report_warnings, scan_in};
+use std::collections::BTreeSet;
+use work::window::{self, Window};
+
+#[derive(clap::Args, Debug)]
+pub struct CompareArgs {
+    #[command(flatten)]
+    pub scan: ScanArgs,
+
+    /// The window to compare against: last-month, 30d, 2026-W12, 2026-03-01..2026-03-15, ...
+    #[arg(long, value_name = "WINDOW", value_parser = window::parse)]
+    pub against: Window,
+
+    /// Raw output for piping (tab-separated)
+    #[arg(short, long)]
+    pub raw: bool,
+}
+
+/// What one window adds up to.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Totals {
+    commits: usize,
+    insertions: usize,
+    deletions: usize,
+    /// Repos with at least one commit, by name.
+    repos: BTreeSet<String>,
+}
+
+fn totals(scan: &Scan) -> Totals {
+    let mut t = Totals::default();
+    for c in &scan.commits {
+        t.commits += 1;
+        t.insertions = t.insertions.saturating_add(c.insertions);
+        t.deletions = t.deletions.saturating_add(c.deletions);
+        t.repos.insert(scan.name(&c.repo));
+    }
+    t
+}
+
+/// `+12 (+40%)`, green when up and red when down. No percentage from zero.
+fn change(now: usize, before: usize) -> String {
+    let delta = now as i64 - before as i64;
+    let pct = match before {
+        0 => String::new(),
+        _ => format!(" ({:+.0}%)", delta as f64 * 100.0 / before as f64),
+    };
+    let text = format!("{delta:+}{pct}");
+    match delta.signum() {
+        1 => paint("32", text),
+        -1 => paint("31", text),
+        _ => paint("2", text),
+    }
+}
+
+/// `work compare`: commits, LoC and active repos in the window side by side
+/// with `--against`, and which repos were only active in one of them.
+pub fn compare(args: CompareArgs) -> Result<(), String> {
+    let current = args.scan.window();
+    let now_scan = scan_in(&args.scan, current, None)?;
+    // `--active-only` judges repos by the main window, and would drop
+    // ones that were only busy in the other.
+    let all_repos = ScanArgs {
+        active_only: false,
+        ..args.scan.clone()
+    };
+    let before_scan = scan_in(&all_repos, args.against, None)?;
+    let (now, before) = (totals(&now_scan), totals(&before_scan));
+
+    let rows = [
+        ("commits", now.commits, before.commits),
+        ("lines added", now.insertions, before.insertions),
+        ("lines removed", now.deletions, before.deletions),
+        ("active repos", now.repos.len(), before.repos.len()),
+    ];
+    let only_now: Vec<&str> = now
+        .repos
+        .difference(&before.repos)
+        .map(String::as_str)
+        .collect();
+    let only_before: Vec<&str> = before
+        .repos
+        .difference(&now.repos)
+        .map(String::as_str)
+        .collect();
+
+    if args.raw {
+        // metric\tcurrent\tagainst\tdelta
+        for (name, a, b) in rows {
+            println!("{name}\t{a}\t{b}\t{:+}", a as i64 - b as i64);
+        }
+    } else {
+        let (a_label, b_label) = (current.label(), args.against.label());
+        let a_width = a_label.len().max(8);
+        let b_width = b_label.len().max(8);
+        println!(
+            "{:<13}  {a_label:>a_width$}  {b_label:>b_width$}  change",
+            ""
+        );
+        for (name, a, b) in rows {
+            println!("{name:<13}  {a:>a_width$}  {b:>b_width$}  {}", change(a, b));
+        }
+        if !only_now.is_empty() {
+            println!("\nOnly active {a_label}: {}", only_now.join(", "));
+        }
+        if !only_before.is_empty() {
+            println!("\nOnly active {b_label}: {}", only_before.join(", "));
+        }
+    }
+
+    report_warnings(&now_scan);
+    report_warnings(&before_scan);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signs_changes() {
+        crate::COLOR.store(false, std::sync::atomic::Ordering::Relaxed);
+        assert_eq!(change(42, 30), "+12 (+40%)");
+        assert_eq!(change(10, 40), "-30 (-75%)");
+        assert_eq!(change(3, 0), "+3");
+        assert_eq!(change(0, 0), "+0");
+    }
+}
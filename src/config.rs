@@ -0,0 +1,601 @@
+use clap_complete::CompletionCandidate;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use work::window::{self, Window};
+
+/// Written by `work config init`. Every key is optional and documented here,
+/// so this doubles as the reference for the file's schema.
+const TEMPLATE: &str = r#"# work configuration
+#
+# String values may reference environment variables as ${NAME}, or
+# ${NAME:-fallback} to use a fallback when NAME is unset. Keep secrets like
+# tokens in the environment and point at them from here.
+
+# Directories to scan when no PATH is given on the command line.
+# A leading ~/ is expanded to your home directory.
+# paths = ["~/code", "${WORK_DIR:-~/work}"]
+
+# IANA timezone for windows and printed times, instead of the system's.
+# Handy when travelling: "today" and "this week" stay on home-office time.
+# tz = "Europe/Berlin"
+
+# Extra author patterns (globs on the author's name or email) for
+# --no-bots, on top of the built-in dependabot/renovate/github-actions list.
+# bots = ["*-ci@example.com", "Release Robot"]
+
+# Directories discovery skips under every scan root, in .gitignore syntax,
+# before the root's own .workignore file.
+# ignore = ["archive/", "vendor/*", "*.mirror"]
+
+# Keep a local log of scan sizes, times and cache hits for `work stats
+# --self`. Counts only, never paths or commits, and it never leaves this
+# machine (~/.local/state/work/usage.tsv).
+# record_usage = true
+
+[github]
+# Token for `work github`; used when neither --token nor $GITHUB_TOKEN is set.
+# token = "${GITHUB_TOKEN}"
+
+# API base URL, for GitHub Enterprise.
+# api_url = "https://api.github.com"
+
+[db]
+# SQLite file for `work db`.
+# path = "~/.local/share/work/history.db"
+
+[goal]
+# Daily goal for `work --today --goal-bar`: commits, lines changed (added
+# plus removed), or both.
+# commits = 5
+# lines = 300
+
+# Profiles, picked with --profile NAME on any command. Each may set its
+# own paths, identity (name and/or email to count as yours; a list of
+# emails for several), directories to ignore, repos to exclude and default
+# window, on top of the keys above.
+# [profile.work]
+# paths = ["~/work"]
+# email = "me@corp.io"
+# exclude_repo = ["forks/*"]
+# window = "week"
+#
+# [profile.oss]
+# paths = ["~/oss"]
+# email = "me@users.noreply.github.com"
+# window = "30d"
+
+# Named views, run with `work view <name>`. Each key is a command-line flag
+# (true for switches, a list to repeat one); `window` names a window flag,
+# `command` a subcommand and `paths` the directories to scan.
+# [view.team]
+# window = "last-week"
+# all = true
+# no_bots = true
+# group_by = "domain"
+#
+# [view.monthly]
+# command = "report"
+# window = "month"
+# format = "html"
+"#;
+
+#[derive(clap::Subcommand, Debug)]
+pub enum ConfigAction {
+    /// Write a commented default config file
+    Init {
+        /// Overwrite an existing config file
+        #[arg(long)]
+        force: bool,
+
+        /// Print the default config instead of writing it
+        #[arg(long)]
+        stdout: bool,
+    },
+
+    /// Print where the config file is read from
+    Path,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Config {
+    pub paths: Vec<PathBuf>,
+    pub tz: Option<chrono_tz::Tz>,
+    pub bots: Vec<glob::Pattern>,
+    /// Lines in `.workignore` syntax, applied under every scan root.
+    pub ignore: Vec<String>,
+    pub github_token: Option<String>,
+    pub github_api_url: Option<String>,
+    pub db_path: Option<PathBuf>,
+    pub goal: Goal,
+    pub record_usage: bool,
+    /// `[view.NAME]` tables, already turned into command-line arguments.
+    pub views: BTreeMap<String, Vec<String>>,
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+/// `[goal]`: what a good day looks like, for `--goal-bar`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Goal {
+    pub commits: Option<usize>,
+    /// Lines added plus lines removed.
+    pub lines: Option<usize>,
+}
+
+/// A `[profile.NAME]` table: settings for one side of your life, picked
+/// with `--profile NAME`.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Profile {
+    /// Replace the top-level `paths`.
+    pub paths: Vec<PathBuf>,
+    /// Replace your git identity; either one is enough. `email` may be a
+    /// list, for everything you've committed as.
+    pub name: Option<String>,
+    pub emails: Vec<String>,
+    /// Added to the top-level `ignore`.
+    pub ignore: Vec<String>,
+    pub exclude_repo: Vec<glob::Pattern>,
+    /// Used when no window flag is given.
+    pub window: Option<Window>,
+}
+
+fn home() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .filter(|h| !h.is_empty())
+        .map(PathBuf::from)
+}
+
+/// `$WORK_CONFIG`, else `$XDG_CONFIG_HOME/work/config.toml`, else
+/// `~/.config/work/config.toml`.
+pub fn path() -> Option<PathBuf> {
+    if let Some(p) = std::env::var_os("WORK_CONFIG").filter(|p| !p.is_empty()) {
+        return Some(PathBuf::from(p));
+    }
+    let dir = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|d| !d.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| home().map(|h| h.join(".config")))?;
+    Some(dir.join("work").join("config.toml"))
+}
+
+fn expand_home(s: &str) -> PathBuf {
+    match (s.strip_prefix("~/"), home()) {
+        (Some(rest), Some(h)) => h.join(rest),
+        _ => PathBuf::from(s),
+    }
+}
+
+/// Replaces `${NAME}` and `${NAME:-fallback}` with values from `env`. An
+/// unset variable without a fallback makes the whole value `None` when
+/// `optional`, and is an error naming the key it was in otherwise.
+fn interpolate(
+    value: &str,
+    key: &str,
+    optional: bool,
+    env: &dyn Fn(&str) -> Option<String>,
+) -> Result<Option<String>, String> {
+    let mut out = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after
+            .find('}')
+            .ok_or_else(|| format!("work: config: unclosed ${{ in {key}"))?;
+        let expr = &after[..end];
+        let (name, fallback) = match expr.split_once(":-") {
+            Some((n, f)) => (n, Some(f)),
+            None => (expr, None),
+        };
+        match (env(name).filter(|v| !v.is_empty()), fallback) {
+            (Some(v), _) => out.push_str(&v),
+            (None, Some(f)) => out.push_str(f),
+            (None, None) if optional => return Ok(None),
+            (None, None) => {
+                return Err(format!(
+                    "work: config: {key} uses ${{{name}}}, which is not set"
+                ));
+            }
+        }
+        rest = &after[end + 1..];
+    }
+    out.push_str(rest);
+    Ok(Some(out))
+}
+
+fn parse(text: &str, env: &dyn Fn(&str) -> Option<String>) -> Result<Config, String> {
+    let table: toml::Table = text.parse().map_err(|e| format!("work: config: {e}"))?;
+
+    let string = |key: &str, v: &toml::Value| -> Result<String, String> {
+        let s = v
+            .as_str()
+            .ok_or_else(|| format!("work: config: {key} must be a string"))?;
+        Ok(interpolate(s, key, false, env)?.unwrap_or_default())
+    };
+    // The `[github]` keys only matter to `work github`, which says when it
+    // has no token, so a secret that isn't exported (under cron, say)
+    // mustn't stop every other command from loading the config.
+    let optional = |key: &str, v: &toml::Value| -> Result<Option<String>, String> {
+        let s = v
+            .as_str()
+            .ok_or_else(|| format!("work: config: {key} must be a string"))?;
+        interpolate(s, key, true, env)
+    };
+    let section = |name: &str| -> Result<Option<&toml::Table>, String> {
+        match table.get(name) {
+            None => Ok(None),
+            Some(toml::Value::Table(t)) => Ok(Some(t)),
+            Some(_) => Err(format!("work: config: [{name}] must be a table")),
+        }
+    };
+
+    let mut cfg = Config::default();
+    for (key, value) in &table {
+        match key.as_str() {
+            "paths" => {
+                let list = value
+                    .as_array()
+                    .ok_or("work: config: paths must be a list of strings")?;
+                for p in list {
+                    cfg.paths.push(expand_home(&string("paths", p)?));
+                }
+            }
+            "tz" => {
+                cfg.tz = Some(
+                    crate::parse_tz(&string("tz", value)?)
+                        .map_err(|e| format!("work: config: tz: {e}"))?,
+                )
+            }
+            "bots" => {
+                let list = value
+                    .as_array()
+                    .ok_or("work: config: bots must be a list of strings")?;
+                for p in list {
+                    cfg.bots.push(
+                        crate::parse_glob(&string("bots", p)?)
+                            .map_err(|e| format!("work: config: bots: {e}"))?,
+                    );
+                }
+            }
+            "ignore" => {
+                let list = value
+                    .as_array()
+                    .ok_or("work: config: ignore must be a list of strings")?;
+                for p in list {
+                    cfg.ignore.push(string("ignore", p)?);
+                }
+            }
+            "record_usage" => {
+                cfg.record_usage = value
+                    .as_bool()
+                    .ok_or("work: config: record_usage must be true or false")?;
+            }
+            "github" | "db" | "goal" | "view" | "profile" => {}
+            other => return Err(format!("work: config: unknown key '{other}'")),
+        }
+    }
+    if let Some(gh) = section("github")? {
+        for (key, value) in gh {
+            let full = format!("github.{key}");
+            match key.as_str() {
+                "token" => cfg.github_token = optional(&full, value)?,
+                "api_url" => cfg.github_api_url = optional(&full, value)?,
+                _ => return Err(format!("work: config: unknown key '{full}'")),
+            }
+        }
+    }
+    if let Some(views) = section("view")? {
+        for (name, value) in views {
+            let toml::Value::Table(view) = value else {
+                return Err(format!("work: config: [view.{name}] must be a table"));
+            };
+            cfg.views
+                .insert(name.clone(), view_args(name, view, &string)?);
+        }
+    }
+    if let Some(profiles) = section("profile")? {
+        for (name, value) in profiles {
+            let toml::Value::Table(profile) = value else {
+                return Err(format!("work: config: [profile.{name}] must be a table"));
+            };
+            cfg.profiles
+                .insert(name.clone(), parse_profile(name, profile, &string)?);
+        }
+    }
+    if let Some(goal) = section("goal")? {
+        for (key, value) in goal {
+            let full = format!("goal.{key}");
+            let n = value
+                .as_integer()
+                .and_then(|n| usize::try_from(n).ok())
+                .filter(|&n| n > 0)
+                .ok_or_else(|| format!("work: config: {full} must be a positive number"))?;
+            match key.as_str() {
+                "commits" => cfg.goal.commits = Some(n),
+                "lines" => cfg.goal.lines = Some(n),
+                _ => return Err(format!("work: config: unknown key '{full}'")),
+            }
+        }
+    }
+    if let Some(db) = section("db")? {
+        for (key, value) in db {
+            let full = format!("db.{key}");
+            match key.as_str() {
+                "path" => cfg.db_path = Some(expand_home(&string(&full, value)?)),
+                _ => return Err(format!("work: config: unknown key '{full}'")),
+            }
+        }
+    }
+    Ok(cfg)
+}
+
+fn parse_profile(
+    name: &str,
+    table: &toml::Table,
+    string: &dyn Fn(&str, &toml::Value) -> Result<String, String>,
+) -> Result<Profile, String> {
+    let mut profile = Profile::default();
+    for (key, value) in table {
+        let full = format!("profile.{name}.{key}");
+        let list = |value: &toml::Value| -> Result<Vec<String>, String> {
+            value
+                .as_array()
+                .ok_or_else(|| format!("work: config: {full} must be a list of strings"))?
+                .iter()
+                .map(|v| string(&full, v))
+                .collect()
+        };
+        match key.as_str() {
+            "paths" => {
+                profile.paths = list(value)?.iter().map(|p| expand_home(p)).collect();
+            }
+            "name" => profile.name = Some(string(&full, value)?),
+            "email" if value.is_array() => profile.emails = list(value)?,
+            "email" => profile.emails = vec![string(&full, value)?],
+            "ignore" => profile.ignore = list(value)?,
+            "exclude_repo" => {
+                for p in list(value)? {
+                    profile.exclude_repo.push(
+                        crate::parse_glob(&p).map_err(|e| format!("work: config: {full}: {e}"))?,
+                    );
+                }
+            }
+            "window" => {
+                profile.window = Some(
+                    window::parse(&string(&full, value)?)
+                        .map_err(|e| format!("work: config: {full}: {e}"))?,
+                );
+            }
+            _ => return Err(format!("work: config: unknown key '{full}'")),
+        }
+    }
+    Ok(profile)
+}
+
+/// `[view.NAME]` as the arguments it stands for: the subcommand, then the
+/// flags in key order, then the paths.
+fn view_args(
+    name: &str,
+    view: &toml::Table,
+    string: &dyn Fn(&str, &toml::Value) -> Result<String, String>,
+) -> Result<Vec<String>, String> {
+    let mut args = Vec::new();
+    let mut paths = Vec::new();
+    for (key, value) in view {
+        let full = format!("view.{name}.{key}");
+        match key.as_str() {
+            "command" => args.insert(0, string(&full, value)?),
+            "window" => args.push(format!("--{}", string(&full, value)?)),
+            "paths" => {
+                let list = value
+                    .as_array()
+                    .ok_or_else(|| format!("work: config: {full} must be a list of strings"))?;
+                for p in list {
+                    paths.push(expand_home(&string(&full, p)?).display().to_string());
+                }
+            }
+            _ => {
+                let flag = format!("--{}", key.replace('_', "-"));
+                let values = match value {
+                    toml::Value::Array(list) => list.iter().collect(),
+                    v => vec![v],
+                };
+                for v in values {
+                    match v {
+                        toml::Value::Boolean(true) => args.push(flag.clone()),
+                        toml::Value::Boolean(false) => {}
+                        toml::Value::Integer(n) => args.extend([flag.clone(), n.to_string()]),
+                        v => args.extend([flag.clone(), string(&full, v)?]),
+                    }
+                }
+            }
+        }
+    }
+    args.extend(paths);
+    Ok(args)
+}
+
+/// Reads the config file, if there is one. A missing file is an empty config.
+pub fn load() -> Result<Config, String> {
+    let Some(path) = path() else {
+        return Ok(Config::default());
+    };
+    let text = match fs::read_to_string(&path) {
+        Ok(t) => t,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Config::default()),
+        Err(e) => return Err(format!("work: cannot read '{}': {e}", path.display())),
+    };
+    parse(&text, &|name| std::env::var(name).ok()).map_err(|e| format!("{e} ({})", path.display()))
+}
+
+/// The config file's view names, for `work view <TAB>`. A broken config
+/// file completes nothing rather than printing into the command line.
+pub fn view_names() -> Vec<CompletionCandidate> {
+    load()
+        .map(|cfg| {
+            cfg.views
+                .into_keys()
+                .map(CompletionCandidate::new)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The config file's profile names, for `--profile <TAB>`.
+pub fn profile_names() -> Vec<CompletionCandidate> {
+    load()
+        .map(|cfg| {
+            cfg.profiles
+                .into_keys()
+                .map(CompletionCandidate::new)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub fn config(action: ConfigAction) -> Result<(), String> {
+    let path = path().ok_or("work: cannot find a config directory (set WORK_CONFIG)")?;
+    match action {
+        ConfigAction::Path => println!("{}", path.display()),
+        ConfigAction::Init { stdout: true, .. } => print!("{TEMPLATE}"),
+        ConfigAction::Init { force, .. } => {
+            if path.exists() && !force {
+                return Err(format!(
+                    "work: '{}' already exists (use --force to overwrite)",
+                    path.display()
+                ));
+            }
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)
+                    .map_err(|e| format!("work: cannot create '{}': {e}", dir.display()))?;
+            }
+            fs::write(&path, TEMPLATE)
+                .map_err(|e| format!("work: cannot write '{}': {e}", path.display()))?;
+            println!("Wrote {}", path.display());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(name: &str) -> Option<String> {
+        (name == "TOKEN").then(|| "s3cret".to_string())
+    }
+
+    #[test]
+    fn parses_and_interpolates() {
+        assert_eq!(parse(TEMPLATE, &env).unwrap(), Config::default());
+
+        let cfg = parse(
+            r#"
+            paths = ["/code", "${MISSING:-/work}"]
+            tz = "America/Havana"
+            bots = ["*-ci@corp.io"]
+            ignore = ["archive/"]
+            [github]
+            token = "${TOKEN}"
+            "#,
+            &env,
+        )
+        .unwrap();
+        assert_eq!(
+            cfg.paths,
+            vec![PathBuf::from("/code"), PathBuf::from("/work")]
+        );
+        assert_eq!(cfg.github_token.as_deref(), Some("s3cret"));
+        assert_eq!(cfg.tz, Some(chrono_tz::America::Havana));
+        assert_eq!(cfg.bots, vec![glob::Pattern::new("*-ci@corp.io").unwrap()]);
+        assert_eq!(cfg.ignore, ["archive/"]);
+    }
+
+    #[test]
+    fn turns_views_into_arguments() {
+        let cfg = parse(
+            r#"
+            [view.monthly]
+            command = "report"
+            window = "month"
+            format = "html"
+            repo = ["apps/*", "libs/*"]
+            all = true
+            merges = false
+            limit = 10
+            paths = ["/code"]
+            "#,
+            &env,
+        )
+        .unwrap();
+        assert_eq!(
+            cfg.views["monthly"],
+            [
+                "report", "--all", "--format", "html", "--limit", "10", "--repo", "apps/*",
+                "--repo", "libs/*", "--month", "/code"
+            ]
+        );
+        assert!(parse("[view]\nteam = 1", &env).is_err());
+    }
+
+    #[test]
+    fn parses_profiles() {
+        let cfg = parse(
+            r#"
+            [profile.work]
+            paths = ["/work"]
+            email = ["me@corp.io", "me@old-laptop.local"]
+            exclude_repo = ["forks/*"]
+            window = "week"
+            "#,
+            &env,
+        )
+        .unwrap();
+        assert_eq!(
+            cfg.profiles["work"],
+            Profile {
+                paths: vec![PathBuf::from("/work")],
+                emails: vec!["me@corp.io".to_string(), "me@old-laptop.local".to_string()],
+                exclude_repo: vec![glob::Pattern::new("forks/*").unwrap()],
+                window: Some(Window::Week),
+                ..Profile::default()
+            }
+        );
+        assert!(parse("[profile.work]\nwindow = \"fortnight\"", &env).is_err());
+        assert!(parse("[profile.work]\nemial = \"x\"", &env).is_err());
+    }
+
+    #[test]
+    fn parses_goal() {
+        let cfg = parse("[goal]\ncommits = 5", &env).unwrap();
+        assert_eq!(
+            cfg.goal,
+            Goal {
+                commits: Some(5),
+                lines: None
+            }
+        );
+        assert!(parse("[goal]\nlines = 0", &env).is_err());
+    }
+
+    #[test]
+    fn parses_record_usage() {
+        assert!(parse("record_usage = true", &env).unwrap().record_usage);
+        assert!(parse("record_usage = \"yes\"", &env).is_err());
+    }
+
+    #[test]
+    fn rejects_unset_variables_outside_github() {
+        let err = parse("paths = [\"${MISSING}\"]", &env).unwrap_err();
+        assert!(err.contains("paths uses ${MISSING}"), "{err}");
+        // An unexported secret is just no token, for `work github` to report.
+        let cfg = parse("[github]\ntoken = \"${MISSING}\"", &env).unwrap();
+        assert_eq!(cfg.github_token, None);
+    }
+
+    #[test]
+    fn rejects_unknown_keys_and_bad_values() {
+        assert!(parse("depht = 3", &env).is_err());
+        assert!(parse("tz = \"Mars/Olympus\"", &env).is_err());
+    }
+}
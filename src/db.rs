@@ -0,0 +1,274 @@
+use crate::{
+    Scan, ScanArgs, finish_export, format_time_local, local_midnight, paint, raw_field, scan,
+};
+use rusqlite::{Connection, params};
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(clap::Subcommand, Debug)]
+pub enum DbAction {
+    /// Scan the window and upsert its commits into the database
+    // Boxed: the scan args dwarf `query`'s.
+    Sync(Box<DbSyncArgs>),
+
+    /// Search every commit ever synced, without touching the repos
+    Query(DbQueryArgs),
+}
+
+#[derive(clap::Args, Debug)]
+pub struct DbSyncArgs {
+    #[command(flatten)]
+    pub scan: ScanArgs,
+
+    /// Database file [default: ~/.local/share/work/history.db]
+    #[arg(long, value_name = "FILE")]
+    pub db: Option<PathBuf>,
+
+    /// Exit with status 1 if any repo couldn't be scanned
+    #[arg(long)]
+    pub strict: bool,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct DbQueryArgs {
+    /// Database file [default: ~/.local/share/work/history.db]
+    #[arg(long, value_name = "FILE")]
+    pub db: Option<PathBuf>,
+
+    /// Commits on or after this local date
+    #[arg(long, value_name = "YYYY-MM-DD")]
+    pub since: Option<chrono::NaiveDate>,
+
+    /// Commits on or before this local date
+    #[arg(long, value_name = "YYYY-MM-DD")]
+    pub until: Option<chrono::NaiveDate>,
+
+    /// Only repos whose name matches this glob
+    #[arg(long, value_name = "GLOB")]
+    pub repo: Option<String>,
+
+    /// Only commits whose message contains this text (case-insensitive)
+    #[arg(long, value_name = "TEXT")]
+    pub grep: Option<String>,
+
+    /// Max number of commits to print
+    #[arg(short, long, default_value = "50")]
+    pub limit: usize,
+
+    /// Raw output for piping (tab-separated)
+    #[arg(short, long)]
+    pub raw: bool,
+}
+
+/// `$XDG_DATA_HOME/work/history.db`, falling back to `~/.local/share`.
+fn default_path() -> Result<PathBuf, String> {
+    let data = match std::env::var_os("XDG_DATA_HOME").filter(|v| !v.is_empty()) {
+        Some(d) => PathBuf::from(d),
+        None => std::env::var_os("HOME")
+            .map(|h| PathBuf::from(h).join(".local/share"))
+            .ok_or("work: HOME is not set; pass --db")?,
+    };
+    Ok(data.join("work").join("history.db"))
+}
+
+fn open(path: Option<PathBuf>) -> Result<Connection, String> {
+    let path = match path {
+        Some(p) => p,
+        None => default_path()?,
+    };
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("work: cannot create '{}': {e}", dir.display()))?;
+    }
+    let conn = Connection::open(&path)
+        .map_err(|e| format!("work: cannot open '{}': {e}", path.display()))?;
+    init(&conn)?;
+    Ok(conn)
+}
+
+fn init(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS commits (
+            repo TEXT NOT NULL,
+            name TEXT NOT NULL,
+            oid TEXT NOT NULL,
+            time INTEGER NOT NULL,
+            summary TEXT NOT NULL,
+            body TEXT,
+            insertions INTEGER NOT NULL,
+            deletions INTEGER NOT NULL,
+            PRIMARY KEY (repo, oid)
+        );
+        CREATE INDEX IF NOT EXISTS commits_time ON commits (time);",
+    )
+    .map_err(db_err)
+}
+
+fn db_err(e: rusqlite::Error) -> String {
+    format!("work: database error: {e}")
+}
+
+/// Upserts the scanned commits. Keyed by repo path and OID, so re-syncing an
+/// overlapping window only refreshes what's already there.
+pub fn sync(args: DbSyncArgs) -> Result<(), String> {
+    let scan = scan(&args.scan)?;
+    let written = store(args.db, &scan);
+    finish_export(args.strict, &scan, written)
+}
+
+fn store(db: Option<PathBuf>, scan: &Scan) -> Result<(), String> {
+    let mut conn = open(db)?;
+
+    let tx = conn.transaction().map_err(db_err)?;
+    {
+        let mut stmt = tx
+            .prepare(
+                "INSERT INTO commits (repo, name, oid, time, summary, body, insertions, deletions)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+                 ON CONFLICT (repo, oid) DO UPDATE SET
+                    name = excluded.name,
+                    summary = excluded.summary,
+                    body = excluded.body,
+                    insertions = excluded.insertions,
+                    deletions = excluded.deletions",
+            )
+            .map_err(db_err)?;
+        for c in &scan.commits {
+            stmt.execute(params![
+                c.repo.to_string_lossy(),
+                scan.name(&c.repo),
+                c.oid.to_string(),
+                c.time,
+                c.summary,
+                c.body,
+                c.insertions as i64,
+                c.deletions as i64,
+            ])
+            .map_err(db_err)?;
+        }
+    }
+    tx.commit().map_err(db_err)?;
+
+    let total: i64 = conn
+        .query_row("SELECT COUNT(*) FROM commits", [], |r| r.get(0))
+        .map_err(db_err)?;
+    println!(
+        "Synced {} commits ({total} in the database)",
+        scan.commits.len()
+    );
+    Ok(())
+}
+
+struct Row {
+    name: String,
+    oid: String,
+    time: i64,
+    summary: String,
+    insertions: i64,
+    deletions: i64,
+}
+
+fn select(conn: &Connection, args: &DbQueryArgs) -> Result<Vec<Row>, String> {
+    let since = args.since.map(local_midnight);
+    let until = args.until.and_then(|d| d.succ_opt()).map(local_midnight);
+    let grep = args.grep.as_ref().map(|g| format!("%{g}%"));
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT name, oid, time, summary, insertions, deletions FROM commits
+             WHERE (?1 IS NULL OR time >= ?1)
+               AND (?2 IS NULL OR time < ?2)
+               AND (?3 IS NULL OR name GLOB ?3)
+               AND (?4 IS NULL OR summary LIKE ?4 OR body LIKE ?4)
+             ORDER BY time DESC
+             LIMIT ?5",
+        )
+        .map_err(db_err)?;
+    let rows = stmt
+        .query_map(
+            params![since, until, args.repo, grep, args.limit as i64],
+            |r| {
+                Ok(Row {
+                    name: r.get(0)?,
+                    oid: r.get(1)?,
+                    time: r.get(2)?,
+                    summary: r.get(3)?,
+                    insertions: r.get(4)?,
+                    deletions: r.get(5)?,
+                })
+            },
+        )
+        .map_err(db_err)?;
+    rows.collect::<Result<_, _>>().map_err(db_err)
+}
+
+pub fn query(args: DbQueryArgs) -> Result<(), String> {
+    let conn = open(args.db.clone())?;
+    let rows = select(&conn, &args)?;
+    if rows.is_empty() {
+        return Err("No matching commits in the database (try `work db sync`)".to_string());
+    }
+
+    let name_width = rows.iter().map(|r| r.name.len()).max().unwrap_or(0);
+    for r in &rows {
+        let t = format_time_local(r.time);
+        let short = &r.oid[..7.min(r.oid.len())];
+        if args.raw {
+            println!(
+                "{t}\t{}\t{short}\t+{}\t-{}\t{}",
+                r.name,
+                r.insertions,
+                r.deletions,
+                raw_field(&r.summary, false)
+            );
+        } else {
+            println!(
+                "{t}  {}  {}  {} {}  {}",
+                paint("1", format!("{:<name_width$}", r.name)),
+                paint("2", short),
+                paint("32", format!("+{}", r.insertions)),
+                paint("31", format!("-{}", r.deletions)),
+                r.summary
+            );
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn queries_by_repo_and_message() {
+        let conn = Connection::open_in_memory().unwrap();
+        init(&conn).unwrap();
+        for (repo, oid, time, summary) in [
+            ("api", "a1", 100, "Add login"),
+            ("api", "a2", 200, "Fix login redirect"),
+            ("ui", "b1", 300, "Login form"),
+        ] {
+            conn.execute(
+                "INSERT INTO commits VALUES (?1, ?1, ?2, ?3, ?4, NULL, 1, 0)",
+                params![repo, oid, time, summary],
+            )
+            .unwrap();
+        }
+
+        let args = DbQueryArgs {
+            db: None,
+            since: None,
+            until: None,
+            repo: Some("a*".to_string()),
+            grep: Some("LOGIN".to_string()),
+            limit: 50,
+            raw: false,
+        };
+        let got: Vec<String> = select(&conn, &args)
+            .unwrap()
+            .into_iter()
+            .map(|r| r.oid)
+            .collect();
+        assert_eq!(got, vec!["a2", "a1"]);
+    }
+}
@@ -0,0 +1,232 @@
+use crate::render::Renderer;
+use crate::{Args, CommitLine, Scan, paint};
+use std::collections::HashSet;
+use std::io::{self, Write};
+use std::path::Path;
+
+/// One email domain's share of the window.
+#[derive(Debug)]
+struct DomainRow {
+    domain: String,
+    org: bool,
+    commits: usize,
+    authors: usize,
+    repos: usize,
+    insertions: usize,
+    deletions: usize,
+}
+
+/// `jane@Example.com` -> `example.com`. Addresses without a domain (or
+/// without an `@` at all) are grouped together rather than dropped.
+fn domain_of(email: &str) -> String {
+    email
+        .rsplit_once('@')
+        .map(|(_, d)| d.trim().trim_end_matches('>').to_lowercase())
+        .filter(|d| !d.is_empty())
+        .unwrap_or_else(|| "(no domain)".to_string())
+}
+
+/// Busiest domain first.
+fn group(commits: &[&CommitLine], org: &[String]) -> Vec<DomainRow> {
+    struct Acc<'a> {
+        commits: usize,
+        authors: HashSet<String>,
+        repos: HashSet<&'a Path>,
+        insertions: usize,
+        deletions: usize,
+    }
+
+    let mut by_domain: Vec<(String, Acc)> = Vec::new();
+    for c in commits {
+        let domain = domain_of(&c.author_email);
+        let i = match by_domain.iter().position(|(d, _)| *d == domain) {
+            Some(i) => i,
+            None => {
+                by_domain.push((
+                    domain,
+                    Acc {
+                        commits: 0,
+                        authors: HashSet::new(),
+                        repos: HashSet::new(),
+                        insertions: 0,
+                        deletions: 0,
+                    },
+                ));
+                by_domain.len() - 1
+            }
+        };
+        let acc = &mut by_domain[i].1;
+        acc.commits += 1;
+        acc.authors.insert(c.author_email.to_lowercase());
+        acc.repos.insert(&c.repo);
+        acc.insertions = acc.insertions.saturating_add(c.insertions);
+        acc.deletions = acc.deletions.saturating_add(c.deletions);
+    }
+
+    let mut rows: Vec<DomainRow> = by_domain
+        .into_iter()
+        .map(|(domain, acc)| DomainRow {
+            org: org.iter().any(|o| o.eq_ignore_ascii_case(&domain)),
+            domain,
+            commits: acc.commits,
+            authors: acc.authors.len(),
+            repos: acc.repos.len(),
+            insertions: acc.insertions,
+            deletions: acc.deletions,
+        })
+        .collect();
+    rows.sort_by(|a, b| {
+        b.commits
+            .cmp(&a.commits)
+            .then_with(|| a.domain.cmp(&b.domain))
+    });
+    rows
+}
+
+/// `--group-by domain`: commits, authors and LoC per author email domain,
+/// then an org-vs-external total. The org is `--org-domain`, or the domain
+/// of your own `user.email`. Given every commit in the window.
+pub struct Domains<'a> {
+    pub args: &'a Args,
+}
+
+impl Renderer for Domains<'_> {
+    fn render(&self, out: &mut dyn Write, scan: &Scan, commits: &[&CommitLine]) -> io::Result<()> {
+        let args = self.args;
+        let org: Vec<String> = if args.org_domain.is_empty() {
+            scan.id.email.iter().map(|e| domain_of(e)).collect()
+        } else {
+            args.org_domain.iter().map(|d| d.to_lowercase()).collect()
+        };
+        let rows = group(commits, &org);
+
+        if args.raw {
+            // domain\torg|external\tcommits\tauthors\trepos\t+ins\t-del
+            for r in &rows {
+                writeln!(
+                    out,
+                    "{}\t{}\t{}\t{}\t{}\t+{}\t-{}",
+                    r.domain,
+                    if r.org { "org" } else { "external" },
+                    r.commits,
+                    r.authors,
+                    r.repos,
+                    r.insertions,
+                    r.deletions
+                )?;
+            }
+            return Ok(());
+        }
+
+        let width = rows.iter().map(|r| r.domain.len()).max().unwrap_or(0);
+        let commits_width = rows.first().map_or(1, |r| r.commits.to_string().len());
+        for r in &rows {
+            writeln!(
+                out,
+                "{}  {}  {:>commits_width$} commits  {:>4} authors  {:>4} repos  {} {}",
+                paint("1", format!("{:<width$}", r.domain)),
+                if r.org {
+                    paint("36", "org")
+                } else {
+                    "   ".to_string()
+                },
+                r.commits,
+                r.authors,
+                r.repos,
+                paint("32", format!("+{}", r.insertions)),
+                paint("31", format!("-{}", r.deletions)),
+            )?;
+        }
+
+        let total = |org: bool| {
+            rows.iter()
+                .filter(|r| r.org == org)
+                .fold((0, 0), |(c, a), r| (c + r.commits, a + r.authors))
+        };
+        let (org_commits, org_authors) = total(true);
+        let (ext_commits, ext_authors) = total(false);
+        writeln!(
+            out,
+            "\nOrg: {org_commits} commits by {org_authors} authors; external: {ext_commits} commits by {ext_authors} authors ({})",
+            args.scan.window().label()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    fn line(repo: &str, email: &str, insertions: usize) -> CommitLine {
+        CommitLine {
+            author_email: email.to_string(),
+            insertions,
+            ..CommitLine::stub(repo, 0)
+        }
+    }
+
+    #[test]
+    fn groups_by_domain_and_marks_the_org() {
+        let commits = [
+            line("api", "ana@corp.io", 10),
+            line("ui", "Ben@Corp.IO", 5),
+            line("api", "ana@corp.io", 1),
+            line("api", "dev@gmail.com", 7),
+            line("ui", "root", 2),
+        ];
+        let commits: Vec<&CommitLine> = commits.iter().collect();
+        let rows = group(&commits, &["corp.io".to_string()]);
+        let got: Vec<(&str, bool, usize, usize, usize, usize)> = rows
+            .iter()
+            .map(|r| {
+                (
+                    r.domain.as_str(),
+                    r.org,
+                    r.commits,
+                    r.authors,
+                    r.repos,
+                    r.insertions,
+                )
+            })
+            .collect();
+        assert_eq!(
+            got,
+            vec![
+                ("corp.io", true, 3, 2, 2, 16),
+                ("(no domain)", false, 1, 1, 1, 2),
+                ("gmail.com", false, 1, 1, 1, 7),
+            ]
+        );
+    }
+
+    #[test]
+    fn renders_raw_rows_per_domain() {
+        let scan = Scan::stub(
+            &["/code"],
+            vec![
+                line("/code/api", "ana@corp.io", 10),
+                line("/code/api", "dev@gmail.com", 7),
+            ],
+        );
+        let commits: Vec<&CommitLine> = scan.commits.iter().collect();
+        let args = Args::parse_from([
+            "work",
+            "--all",
+            "--group-by",
+            "domain",
+            "--raw",
+            "--org-domain",
+            "corp.io",
+            "x",
+        ]);
+        let mut out = Vec::new();
+        Domains { args: &args }
+            .render(&mut out, &scan, &commits)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "corp.io\torg\t1\t1\t1\t+10\t-0\ngmail.com\texternal\t1\t1\t1\t+7\t-0\n"
+        );
+    }
+}
@@ -0,0 +1,200 @@
+use crate::render::Renderer;
+use crate::{
+    Args, CommitLine, RefEventKind, Scan, ScanArgs, collect_ref_events, format_time_rfc3339,
+};
+use rayon::prelude::*;
+use serde_json::json;
+use std::io::{self, Write};
+use std::path::Path;
+
+/// A commit as an `--events` / `--jsonl` record.
+pub fn commit_json(c: &CommitLine, rel: &dyn Fn(&Path) -> String) -> serde_json::Value {
+    json!({
+        "type": "commit",
+        "time": format_time_rfc3339(c.time),
+        "timestamp": c.time,
+        "repo": rel(&c.repo),
+        "hash": c.oid.to_string(),
+        "short_hash": c.short,
+        "summary": c.summary,
+        "body": c.body,
+        "note": c.note,
+        "trailers": c
+            .trailers
+            .iter()
+            .map(|(k, v)| json!({ "key": k, "value": v }))
+            .collect::<Vec<_>>(),
+        "files": (!c.no_stats).then_some(c.files),
+        "insertions": (!c.no_stats).then_some(c.insertions),
+        "deletions": (!c.no_stats).then_some(c.deletions),
+        "lfs_files": (!c.no_stats).then_some(c.lfs_files),
+        "lfs_bytes": (!c.no_stats).then_some(c.lfs_bytes),
+        "mode_only": c.mode_only,
+        "also_in": c.also_in.iter().map(|p| rel(p)).collect::<Vec<_>>(),
+    })
+}
+
+/// A repo that couldn't be scanned, as an `--events` / `--jsonl` record.
+pub fn scan_error_json(scan: &Scan, repo: &Path, error: &str) -> serde_json::Value {
+    json!({ "type": "scan-error", "repo": scan.name(repo), "error": error })
+}
+
+/// `--jsonl`'s last record: the window, and the commits and lines it
+/// printed.
+pub fn summary_json(
+    args: &ScanArgs,
+    scan: &Scan,
+    commits: usize,
+    insertions: usize,
+    deletions: usize,
+) -> serde_json::Value {
+    json!({
+        "type": "summary",
+        "window": args.window().label(),
+        "since": format_time_rfc3339(scan.since),
+        "until": scan.until.map(format_time_rfc3339),
+        "repos": scan.repos.len(),
+        "failed": scan.failed.len(),
+        "commits": commits,
+        "insertions": insertions,
+        "deletions": deletions,
+    })
+}
+
+/// `--events`: every commit in the window, plus tags, stashes and new
+/// branches, as one JSON object per line, oldest first. Every record
+/// carries `type`, `time` (RFC 3339), `timestamp` (unix seconds) and
+/// `repo`. A stream consumer wants all of it, and an empty stream rather
+/// than an error, so it's given every commit rather than the `--limit`.
+pub struct Events<'a> {
+    pub args: &'a Args,
+}
+
+impl Renderer for Events<'_> {
+    fn render(&self, out: &mut dyn Write, scan: &Scan, commits: &[&CommitLine]) -> io::Result<()> {
+        let found: Vec<_> = scan
+            .repos
+            .par_iter()
+            .map(|r| {
+                collect_ref_events(r, scan.since, scan.until, &scan.id, &self.args.scan)
+                    .map_err(|e| (r, e))
+            })
+            .collect();
+        let mut ref_events = Vec::new();
+        let mut failed: Vec<(&Path, &str)> = scan
+            .failed
+            .iter()
+            .map(|(r, e)| (r.as_path(), e.as_str()))
+            .collect();
+        for f in &found {
+            match f {
+                Ok(events) => ref_events.extend(events),
+                // A repo that won't open has usually failed its scan already.
+                Err((r, e)) if !failed.iter().any(|(f, _)| f == r) => failed.push((r, e)),
+                Err(_) => {}
+            }
+        }
+        let rel = |p: &Path| scan.rel(p).display().to_string();
+
+        let mut records: Vec<(i64, serde_json::Value)> = Vec::new();
+        // `commits` is newest-first; reverse so same-second commits stay in order.
+        for c in commits.iter().rev() {
+            records.push((c.time, commit_json(c, &rel)));
+        }
+        for e in &ref_events {
+            let kind = match &e.kind {
+                RefEventKind::Tag { .. } => "tag",
+                RefEventKind::Stash { .. } => "stash",
+                RefEventKind::BranchCreated { .. } => "branch-created",
+            };
+            let mut v = json!({
+                "type": kind,
+                "time": format_time_rfc3339(e.time),
+                "timestamp": e.time,
+                "repo": rel(&e.repo),
+            });
+            let extra = match &e.kind {
+                RefEventKind::Tag {
+                    name,
+                    target,
+                    message,
+                } => json!({ "name": name, "target": target.to_string(), "message": message }),
+                RefEventKind::Stash { message } => json!({ "message": message }),
+                RefEventKind::BranchCreated { branch, from } => {
+                    json!({ "branch": branch, "from": from })
+                }
+            };
+            if let (Some(v), serde_json::Value::Object(extra)) = (v.as_object_mut(), extra) {
+                v.extend(extra);
+            }
+            records.push((e.time, v));
+        }
+
+        records.sort_by_key(|(t, _)| *t);
+        for (_, v) in records {
+            writeln!(out, "{v}")?;
+        }
+        // Not events in time, so they come last, after every dated record.
+        for (r, e) in failed {
+            writeln!(out, "{}", scan_error_json(scan, r, e))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[test]
+    fn writes_records_oldest_first_and_errors_last() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        for name in ["api", "web"] {
+            git2::Repository::init(root.join(name)).unwrap();
+        }
+        let line = |repo: &str, time: i64, summary: &str| CommitLine {
+            summary: summary.to_string(),
+            insertions: 2,
+            ..CommitLine::stub(root.join(repo).to_str().unwrap(), time)
+        };
+        let mut scan = Scan::stub(
+            &[root.to_str().unwrap()],
+            vec![
+                line("web", 1_772_456_400, "Add form"),
+                line("api", 1_772_452_800, "Fix login"),
+            ],
+        );
+        // Failed its scan, so it's reported once, not again for its refs.
+        scan.repos.push(root.join("broken"));
+        scan.failed
+            .push((root.join("broken"), "corrupt object".to_string()));
+        // Scanned, but gone by the time its refs are read.
+        scan.repos.push(root.join("gone"));
+        let commits: Vec<&CommitLine> = scan.commits.iter().collect();
+        let args = Args::parse_from(["work", "--events", "x"]);
+        let mut out = Vec::new();
+        Events { args: &args }
+            .render(&mut out, &scan, &commits)
+            .unwrap();
+
+        let records: Vec<serde_json::Value> = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(records.len(), 4);
+        assert_eq!(records[0]["summary"], "Fix login");
+        assert_eq!(records[0]["repo"], "api");
+        assert_eq!(records[0]["time"], format_time_rfc3339(1_772_452_800));
+        assert_eq!(records[0]["insertions"], 2);
+        assert_eq!(records[1]["summary"], "Add form");
+        assert_eq!(
+            records[2],
+            json!({ "type": "scan-error", "repo": "broken", "error": "corrupt object" })
+        );
+        assert_eq!(records[3]["type"], "scan-error");
+        assert_eq!(records[3]["repo"], "gone");
+    }
+}
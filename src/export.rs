@@ -0,0 +1,338 @@
+use crate::session::{SESSION_LEAD_SECS, Session, SessionArgs, infer_sessions};
+use crate::{CommitLine, Scan, ScanArgs, report_warnings, scan};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// How long a `--per-commit` event is. Git only knows when a commit was
+/// made, so each one is a short block ending at that moment.
+const COMMIT_EVENT_SECS: i64 = 15 * 60;
+
+#[derive(clap::Args, Debug)]
+pub struct ExportArgs {
+    #[command(flatten)]
+    pub scan: ScanArgs,
+
+    #[command(flatten)]
+    pub sessions: SessionArgs,
+
+    /// Write an iCalendar file (`-` for stdout)
+    #[arg(
+        long,
+        value_name = "FILE",
+        required_unless_present = "org",
+        conflicts_with = "org"
+    )]
+    pub ics: Option<PathBuf>,
+
+    /// Write an org-mode outline: a headline per repo, with its commits and a CLOCK line per session (`-` for stdout)
+    #[arg(long, value_name = "FILE")]
+    pub org: Option<PathBuf>,
+
+    /// With --ics: one event per commit instead of one per inferred work session
+    #[arg(long, conflicts_with = "org")]
+    pub per_commit: bool,
+}
+
+/// iCalendar's UTC date-time form (`20260304T091500Z`).
+fn ics_time(ts: i64) -> String {
+    use chrono::{TimeZone, Utc};
+    Utc.timestamp_opt(ts, 0)
+        .single()
+        .map(|d| d.format("%Y%m%dT%H%M%SZ").to_string())
+        .unwrap_or_else(|| ts.to_string())
+}
+
+/// Backslash-escapes a TEXT value (RFC 5545 3.3.11).
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for ch in s.chars() {
+        match ch {
+            '\\' | ';' | ',' => {
+                out.push('\\');
+                out.push(ch);
+            }
+            '\n' => out.push_str("\\n"),
+            '\r' => {}
+            _ => out.push(ch),
+        }
+    }
+    out
+}
+
+/// Appends one content line, folded at 75 octets (without splitting a
+/// character) with CRLF endings, as calendar apps insist on.
+fn push_line(out: &mut String, line: &str) {
+    let mut width = 0;
+    for ch in line.chars() {
+        if width + ch.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(ch);
+        width += ch.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+/// A calendar event, before it's written out.
+struct Event {
+    uid: String,
+    start: i64,
+    end: i64,
+    summary: String,
+    description: String,
+}
+
+/// One event per session: the repo and commit count as the title, and the
+/// session's commits, oldest first, as the description.
+fn session_events(scan: &Scan, sessions: &[Session]) -> Vec<Event> {
+    sessions
+        .iter()
+        .map(|s| {
+            let name = scan.name(&s.repo);
+            let mut commits: Vec<&CommitLine> = scan
+                .commits
+                .iter()
+                .filter(|c| {
+                    c.repo == s.repo && c.time >= s.start + SESSION_LEAD_SECS && c.time <= s.end
+                })
+                .collect();
+            commits.sort_by_key(|c| c.time);
+            let mut description = format!("+{} -{}\n", s.insertions, s.deletions);
+            for c in commits {
+                description += &format!("\n{} {}", c.short, c.summary);
+            }
+            Event {
+                // Stable across exports, so re-importing updates the event
+                // (a session that grew keeps its start) instead of doubling it.
+                uid: format!("{}-{}@work", s.start, name.replace('/', "-")),
+                start: s.start,
+                end: s.end,
+                summary: format!(
+                    "{name}: {} commit{}",
+                    s.commits,
+                    if s.commits == 1 { "" } else { "s" }
+                ),
+                description,
+            }
+        })
+        .collect()
+}
+
+fn commit_events(scan: &Scan) -> Vec<Event> {
+    let mut events: Vec<Event> = scan
+        .commits
+        .iter()
+        .map(|c| {
+            let mut description = format!(
+                "{} by {}, +{} -{}",
+                c.short, c.author_name, c.insertions, c.deletions
+            );
+            if let Some(body) = &c.body {
+                description += &format!("\n\n{body}");
+            }
+            Event {
+                uid: format!("{}@work", c.oid),
+                start: c.time - COMMIT_EVENT_SECS,
+                end: c.time,
+                summary: format!("{}: {}", scan.name(&c.repo), c.summary),
+                description,
+            }
+        })
+        .collect();
+    events.sort_by_key(|e| e.start);
+    events
+}
+
+fn calendar(events: &[Event], stamp: i64) -> String {
+    let mut out = String::new();
+    for line in [
+        "BEGIN:VCALENDAR",
+        "VERSION:2.0",
+        "PRODID:-//work//git activity//EN",
+        "CALSCALE:GREGORIAN",
+        "X-WR-CALNAME:work",
+    ] {
+        push_line(&mut out, line);
+    }
+    for e in events {
+        push_line(&mut out, "BEGIN:VEVENT");
+        push_line(&mut out, &format!("UID:{}", escape(&e.uid)));
+        push_line(&mut out, &format!("DTSTAMP:{}", ics_time(stamp)));
+        push_line(&mut out, &format!("DTSTART:{}", ics_time(e.start)));
+        push_line(&mut out, &format!("DTEND:{}", ics_time(e.end)));
+        push_line(&mut out, &format!("SUMMARY:{}", escape(&e.summary)));
+        push_line(&mut out, &format!("DESCRIPTION:{}", escape(&e.description)));
+        push_line(&mut out, "TRANSP:TRANSPARENT");
+        push_line(&mut out, "END:VEVENT");
+    }
+    push_line(&mut out, "END:VCALENDAR");
+    out
+}
+
+/// An org timestamp, inactive so it stays off the agenda:
+/// `[2026-03-04 Wed 09:00]`.
+fn org_time(ts: i64) -> String {
+    crate::zoned(ts)
+        .map(|d| d.format("[%Y-%m-%d %a %H:%M]").to_string())
+        .unwrap_or_else(|| format!("[{ts}]"))
+}
+
+/// An org-mode outline: a headline per repo, with a `CLOCK:` line per
+/// session in its `LOGBOOK` drawer (newest first, as org keeps them) so
+/// clock tables add the time up, and a sub-headline per commit, oldest
+/// first.
+fn org(scan: &Scan, sessions: &[Session], title: &str) -> String {
+    let mut by_repo: Vec<(String, &Path)> = scan
+        .commits
+        .iter()
+        .map(|c| (scan.name(&c.repo), c.repo.as_path()))
+        .collect();
+    by_repo.sort();
+    by_repo.dedup();
+
+    let mut out = format!("#+TITLE: {title}\n");
+    for (name, repo) in by_repo {
+        out += &format!("* {name}\n:LOGBOOK:\n");
+        for s in sessions.iter().rev().filter(|s| s.repo == repo) {
+            let mins = (s.end - s.start) / 60;
+            out += &format!(
+                "CLOCK: {}--{} => {:>2}:{:02}\n",
+                org_time(s.start),
+                org_time(s.end),
+                mins / 60,
+                mins % 60
+            );
+        }
+        out += ":END:\n";
+        let mut commits: Vec<&CommitLine> =
+            scan.commits.iter().filter(|c| c.repo == repo).collect();
+        commits.sort_by_key(|c| c.time);
+        for c in commits {
+            out += &format!(
+                "** {} {}  ={}= +{} -{}\n",
+                org_time(c.time),
+                c.summary,
+                c.short,
+                c.insertions,
+                c.deletions
+            );
+        }
+    }
+    out
+}
+
+/// Writes `text` to `path`, or stdout for `-`.
+fn write_out(path: &Path, text: &str, what: String, window: &str) -> Result<(), String> {
+    if path.as_os_str() == "-" {
+        print!("{text}");
+        return Ok(());
+    }
+    fs::write(path, text).map_err(|e| format!("work: cannot write {}: {e}", path.display()))?;
+    eprintln!("Wrote {what} to {} ({window})", path.display());
+    Ok(())
+}
+
+/// `work export`: the window's inferred work sessions as calendar events
+/// (`--ics`, or one per commit with `--per-commit`) for backfilling a
+/// calendar, or as an org-mode outline (`--org`) for a weekly review.
+pub fn export(args: ExportArgs) -> Result<(), String> {
+    let scan = scan(&args.scan)?;
+    if scan.commits.is_empty() {
+        report_warnings(&scan);
+        return Err(args.scan.no_commits());
+    }
+    let sessions = infer_sessions(&scan.commits, args.sessions.gap_secs());
+    let window = args.scan.window().label();
+
+    if let Some(path) = &args.org {
+        let repos = scan
+            .commits
+            .iter()
+            .map(|c| &c.repo)
+            .collect::<HashSet<_>>()
+            .len();
+        let text = org(&scan, &sessions, &format!("Commits, {window}"));
+        write_out(path, &text, format!("{repos} repos"), &window)?;
+    } else if let Some(path) = &args.ics {
+        let events = if args.per_commit {
+            commit_events(&scan)
+        } else {
+            session_events(&scan, &sessions)
+        };
+        let text = calendar(&events, crate::now().timestamp());
+        let what = if args.per_commit {
+            "commits"
+        } else {
+            "sessions"
+        };
+        write_out(path, &text, format!("{} {what}", events.len()), &window)?;
+    }
+    report_warnings(&scan);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[test]
+    fn writes_sessions_and_commits_as_events() {
+        let hour = 60 * 60;
+        let line = |time: i64, summary: &str| CommitLine {
+            summary: summary.to_string(),
+            insertions: 2,
+            ..CommitLine::stub("/code/acme/api", time)
+        };
+        let scan = Scan::stub(
+            &["/code"],
+            vec![
+                line(1_772_614_800 + hour, "Retry uploads; keep the queue, too"),
+                line(1_772_614_800, "Add upload queue"),
+            ],
+        );
+
+        let events = session_events(&scan, &infer_sessions(&scan.commits, 2 * hour));
+        let ics = calendar(&events, 0);
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ics.ends_with("END:VEVENT\r\nEND:VCALENDAR\r\n"));
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 1);
+        assert!(ics.contains("DTSTART:20260304T083000Z\r\nDTEND:20260304T100000Z\r\n"));
+        assert!(ics.contains("SUMMARY:acme/api: 2 commits\r\n"));
+        assert!(ics.contains("UID:1772613000-acme-api@work\r\n"));
+        // Escaped, and folded past 75 octets.
+        assert!(ics.contains("DESCRIPTION:+4 -0\\n\\n0000000 Add upload queue\\n0000000 Retry uploads\\; keep\r\n  the queue\\, too\r\n"));
+        assert!(ics.lines().all(|l| l.len() <= 75));
+
+        let ics = calendar(&commit_events(&scan), 0);
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 2);
+        assert!(ics.contains("DTSTART:20260304T084500Z\r\nDTEND:20260304T090000Z\r\n"));
+
+        // Times are local, so only the shape and the duration are fixed.
+        let outline = org(
+            &scan,
+            &infer_sessions(&scan.commits, 2 * hour),
+            "Commits, this week",
+        );
+        let lines: Vec<&str> = outline.lines().collect();
+        assert_eq!(
+            lines[..3],
+            ["#+TITLE: Commits, this week", "* acme/api", ":LOGBOOK:"]
+        );
+        assert!(lines[3].starts_with("CLOCK: [2026-03-0") && lines[3].ends_with("] =>  1:30"));
+        assert_eq!(lines[4], ":END:");
+        assert!(
+            lines[5].starts_with("** [2026-03-0")
+                && lines[5].ends_with("] Add upload queue  =0000000= +2 -0")
+        );
+        assert!(lines[6].ends_with("] Retry uploads; keep the queue, too  =0000000= +2 -0"));
+        assert_eq!(lines.len(), 7);
+        assert!(crate::Cli::try_parse_from(["work", "export", "a"]).is_err());
+        assert!(
+            crate::Cli::try_parse_from(["work", "export", "--org", "-", "--per-commit", "a"])
+                .is_err()
+        );
+    }
+}
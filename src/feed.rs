@@ -0,0 +1,171 @@
+use crate::{
+    CommitLine, Identity, ScanArgs, collect_commits, format_time_local, matches_identity, now,
+    paint, raw_field, report_warnings, scan,
+};
+use rayon::prelude::*;
+use std::collections::HashSet;
+use std::path::Path;
+
+#[derive(clap::Args, Debug)]
+pub struct FeedArgs {
+    #[command(flatten)]
+    pub scan: ScanArgs,
+
+    /// A repo counts as yours if you've committed to it in this many days
+    #[arg(long, value_name = "DAYS", default_value = "30")]
+    pub active_days: i64,
+
+    /// Max number of commits to print
+    #[arg(short, long, default_value = "50")]
+    pub limit: usize,
+
+    /// Raw output for piping (tab-separated)
+    #[arg(short, long)]
+    pub raw: bool,
+}
+
+fn is_mine(id: &Identity, c: &CommitLine) -> bool {
+    matches_identity(id, Some(&c.author_name), Some(&c.author_email))
+}
+
+/// Commits not by `id`, in repos where `id` has a commit too, or where
+/// `active_lately` says they committed before the window.
+fn others_in_my_repos<'a>(
+    commits: &'a [CommitLine],
+    id: &Identity,
+    active_lately: impl Fn(&Path) -> bool + Sync,
+) -> Vec<&'a CommitLine> {
+    let mut candidates: Vec<&Path> = commits
+        .iter()
+        .filter(|c| !is_mine(id, c))
+        .map(|c| c.repo.as_path())
+        .collect();
+    candidates.sort();
+    candidates.dedup();
+
+    // Your own commits in the window already prove you're active; only look
+    // further back for the repos where you have none.
+    let mut active: HashSet<&Path> = commits
+        .iter()
+        .filter(|c| is_mine(id, c))
+        .map(|c| c.repo.as_path())
+        .collect();
+    let lately: Vec<&Path> = candidates
+        .into_par_iter()
+        .filter(|r| !active.contains(r) && active_lately(r))
+        .collect();
+    active.extend(lately);
+
+    commits
+        .iter()
+        .filter(|c| active.contains(c.repo.as_path()) && !is_mine(id, c))
+        .collect()
+}
+
+/// `work feed`: everyone else's commits in the window, in the repos you've
+/// committed to lately, so teammates' changes to shared projects show up
+/// without a trip to the forge.
+pub fn feed(mut args: FeedArgs) -> Result<(), String> {
+    args.scan.all = true;
+    let scan = scan(&args.scan)?;
+    let id = &scan.id;
+    if id.name.is_none() && id.email.is_none() {
+        return Err("work: feed needs user.name or user.email to tell your commits apart".into());
+    }
+
+    let since = now().timestamp() - args.active_days.max(0) * 24 * 60 * 60;
+    let mine_only = ScanArgs {
+        all: false,
+        ..args.scan.clone()
+    };
+    let feed = others_in_my_repos(&scan.commits, id, |r| {
+        !collect_commits(r, since, None, id, &mine_only).0.is_empty()
+    });
+    let feed = &feed[..feed.len().min(args.limit)];
+    let printed = print_feed(&args, &scan, feed);
+    report_warnings(&scan);
+    printed
+}
+
+fn print_feed(args: &FeedArgs, scan: &crate::Scan, feed: &[&CommitLine]) -> Result<(), String> {
+    if feed.is_empty() {
+        return Err(format!(
+            "No commits by others in your repos in {}",
+            args.scan.window().describe()
+        ));
+    }
+    let repo_width = feed
+        .iter()
+        .map(|c| scan.rel(&c.repo).display().to_string().len())
+        .max()
+        .unwrap_or(0);
+    let author_width = feed
+        .iter()
+        .map(|c| c.author_name.chars().count())
+        .max()
+        .unwrap_or(0);
+    for c in feed {
+        let t = format_time_local(c.time);
+        let repo = scan.rel(&c.repo).display().to_string();
+        let short = &c.short;
+        if args.raw {
+            // time\trepo\thash\tauthor\temail\tsummary
+            println!(
+                "{t}\t{repo}\t{short}\t{}\t{}\t{}",
+                raw_field(&c.author_name, false),
+                c.author_email,
+                raw_field(&c.summary, false)
+            );
+        } else {
+            println!(
+                "{t}  {}  {}  {}  {}",
+                paint("1", format!("{repo:<repo_width$}")),
+                paint("2", short),
+                paint("35", format!("{:<author_width$}", c.author_name)),
+                c.summary
+            );
+        }
+    }
+    if !args.raw {
+        println!(
+            "\n{} commits by others ({})",
+            feed.len(),
+            args.scan.window().label()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn by(repo: &str, time: i64, email: &str) -> CommitLine {
+        CommitLine {
+            author_email: email.to_string(),
+            ..CommitLine::stub(repo, time)
+        }
+    }
+
+    #[test]
+    fn shows_others_in_repos_i_work_on() {
+        let me = Identity {
+            name: None,
+            email: Some("me@corp.io".to_string()),
+            other_emails: Vec::new(),
+        };
+        let commits = vec![
+            by("api", 50, "ana@corp.io"),
+            by("api", 40, "me@corp.io"),
+            by("ui", 30, "ben@corp.io"),
+            by("docs", 20, "cy@corp.io"),
+        ];
+        // Nothing of mine in `ui` this window, but there was last month.
+        let got = others_in_my_repos(&commits, &me, |r| r == Path::new("ui"));
+        let got: Vec<(i64, &str)> = got
+            .iter()
+            .map(|c| (c.time, c.author_email.as_str()))
+            .collect();
+        assert_eq!(got, [(50, "ana@corp.io"), (30, "ben@corp.io")]);
+    }
+}
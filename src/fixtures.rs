@@ -0,0 +1,64 @@
+//! Repos with fully pinned history: fixed author, committer and timestamps
+//! mean the same calls always produce the same commit hashes.
+
+use git2::{Oid, Repository, Signature, Time};
+use std::fs;
+use std::path::Path;
+
+pub const AUTHOR_NAME: &str = "Fixture Author";
+pub const AUTHOR_EMAIL: &str = "fixture@example.com";
+
+pub struct FixtureRepo {
+    repo: Repository,
+}
+
+impl FixtureRepo {
+    /// Creates `path` (and parents) and initializes a repo there whose
+    /// local `user.name` / `user.email` match the fixture author.
+    pub fn init(path: &Path) -> Result<Self, git2::Error> {
+        fs::create_dir_all(path).map_err(|e| git2::Error::from_str(&e.to_string()))?;
+        let repo = Repository::init(path)?;
+        let mut cfg = repo.config()?;
+        cfg.set_str("user.name", AUTHOR_NAME)?;
+        cfg.set_str("user.email", AUTHOR_EMAIL)?;
+        Ok(FixtureRepo { repo })
+    }
+
+    pub fn path(&self) -> &Path {
+        self.repo.workdir().unwrap_or_else(|| self.repo.path())
+    }
+
+    pub fn repo(&self) -> &Repository {
+        &self.repo
+    }
+
+    /// Writes `files` (path, contents) into the work tree and commits them on
+    /// top of `HEAD`, authored and committed at `time` (Unix seconds, UTC).
+    pub fn commit(
+        &self,
+        time: i64,
+        message: &str,
+        files: &[(&str, &str)],
+    ) -> Result<Oid, git2::Error> {
+        let mut index = self.repo.index()?;
+        for (rel, contents) in files {
+            let full = self.path().join(rel);
+            if let Some(dir) = full.parent() {
+                fs::create_dir_all(dir).map_err(|e| git2::Error::from_str(&e.to_string()))?;
+            }
+            fs::write(&full, contents).map_err(|e| git2::Error::from_str(&e.to_string()))?;
+            index.add_path(Path::new(rel))?;
+        }
+        index.write()?;
+        let tree = self.repo.find_tree(index.write_tree()?)?;
+
+        let sig = Signature::new(AUTHOR_NAME, AUTHOR_EMAIL, &Time::new(time, 0))?;
+        let parent = match self.repo.head() {
+            Ok(head) => Some(head.peel_to_commit()?),
+            Err(_) => None,
+        };
+        let parents: Vec<_> = parent.iter().collect();
+        self.repo
+            .commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+    }
+}
@@ -0,0 +1,107 @@
+use crate::open_repo;
+use git2::Repository;
+use std::path::Path;
+
+/// Turns a clone URL into the forge's web URL for the repo:
+/// `git@github.com:me/app.git` and `ssh://git@github.com/me/app` both
+/// become `https://github.com/me/app`. Local paths and `file://` remotes
+/// have no web page, so they give `None`.
+pub fn web_url(remote: &str) -> Option<String> {
+    let remote = remote.trim();
+
+    let (host, path) = if let Some(rest) = remote
+        .strip_prefix("https://")
+        .or_else(|| remote.strip_prefix("http://"))
+        .or_else(|| remote.strip_prefix("ssh://"))
+        .or_else(|| remote.strip_prefix("git://"))
+    {
+        let (authority, path) = rest.split_once('/')?;
+        let host = authority.rsplit('@').next()?;
+        // Drop ports: an SSH port says nothing about where the web UI lives.
+        let host = host.split(':').next()?;
+        (host, path)
+    } else if !remote.contains("://") {
+        // scp-like syntax: [user@]host:path
+        let (authority, path) = remote.split_once(':')?;
+        // Relative paths and Windows drive letters aren't hosts.
+        if authority.len() < 2 || authority.contains('/') {
+            return None;
+        }
+        let host = authority.rsplit('@').next()?;
+        (host, path)
+    } else {
+        return None;
+    };
+
+    let path = path.trim_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    if host.is_empty() || path.is_empty() {
+        return None;
+    }
+    Some(format!("https://{host}/{path}"))
+}
+
+/// Link to a single commit on the forge. GitLab and Bitbucket use their own
+/// routes; GitHub's `/commit/` works for Gitea, Forgejo and most others.
+pub fn commit_url(web: &str, hash: &str) -> String {
+    let host = web
+        .strip_prefix("https://")
+        .and_then(|r| r.split('/').next())
+        .unwrap_or("");
+    if host.contains("gitlab") {
+        format!("{web}/-/commit/{hash}")
+    } else if host.contains("bitbucket") {
+        format!("{web}/commits/{hash}")
+    } else {
+        format!("{web}/commit/{hash}")
+    }
+}
+
+/// Web URL of the repo's `origin`, or of its only remote if it isn't named
+/// `origin`. `Ok(None)` when it has no remote with a web page; an error
+/// when the repo won't open.
+pub fn repo_web_url(repo_path: &Path) -> Result<Option<String>, String> {
+    Ok(origin_web_url(&open_repo(repo_path)?))
+}
+
+fn origin_web_url(repo: &Repository) -> Option<String> {
+    let remote = match repo.find_remote("origin") {
+        Ok(r) => r,
+        Err(_) => {
+            let names = repo.remotes().ok()?;
+            if names.len() != 1 {
+                return None;
+            }
+            repo.find_remote(names.get(0)?).ok()?
+        }
+    };
+    web_url(remote.url()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derives_web_urls() {
+        let want = Some("https://github.com/me/app".to_string());
+        assert_eq!(web_url("git@github.com:me/app.git"), want);
+        assert_eq!(web_url("https://github.com/me/app.git"), want);
+        assert_eq!(web_url("https://token@github.com/me/app"), want);
+        assert_eq!(web_url("ssh://git@github.com:22/me/app.git"), want);
+        assert_eq!(web_url("/srv/git/app.git"), None);
+        assert_eq!(web_url("file:///srv/git/app.git"), None);
+    }
+
+    #[test]
+    fn builds_commit_urls_per_forge() {
+        assert_eq!(
+            commit_url("https://gitlab.com/me/app", "abc"),
+            "https://gitlab.com/me/app/-/commit/abc"
+        );
+        assert_eq!(
+            commit_url("https://github.com/me/app", "abc"),
+            "https://github.com/me/app/commit/abc"
+        );
+    }
+}
@@ -0,0 +1,325 @@
+use crate::{ScanArgs, forge, format_time_local, paint, report_warnings, scan};
+use serde_json::Value;
+use std::collections::HashMap;
+
+#[derive(clap::Args, Debug)]
+pub struct GithubArgs {
+    #[command(flatten)]
+    pub scan: ScanArgs,
+
+    /// GitHub token [default: $GITHUB_TOKEN]
+    #[arg(long, value_name = "TOKEN")]
+    pub token: Option<String>,
+
+    /// API base URL, for GitHub Enterprise [default: https://api.github.com]
+    #[arg(long, value_name = "URL")]
+    pub api_url: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PrAction {
+    Opened,
+    Merged,
+    Reviewed,
+}
+
+impl PrAction {
+    fn label(self) -> &'static str {
+        match self {
+            PrAction::Opened => "opened",
+            PrAction::Merged => "merged",
+            PrAction::Reviewed => "reviewed",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct PrEvent {
+    time: i64,
+    action: PrAction,
+    /// `owner/name` on GitHub.
+    repo: String,
+    number: u64,
+    title: String,
+    url: String,
+}
+
+/// One line of the merged timeline.
+enum Item<'a> {
+    Commit(&'a crate::CommitLine),
+    Pr(&'a PrEvent),
+}
+
+fn utc(ts: i64) -> String {
+    use chrono::{TimeZone, Utc};
+    Utc.timestamp_opt(ts, 0)
+        .single()
+        .map(|d| d.format("%Y-%m-%dT%H:%M:%SZ").to_string())
+        .unwrap_or_else(|| ts.to_string())
+}
+
+fn parse_time(v: &Value) -> Option<i64> {
+    chrono::DateTime::parse_from_rfc3339(v.as_str()?)
+        .ok()
+        .map(|d| d.timestamp())
+}
+
+/// A search-API date qualifier covering the window, e.g.
+/// `created:>=2026-03-01T00:00:00Z`.
+fn qualifier(field: &str, since: i64, until: Option<i64>) -> String {
+    match until {
+        Some(u) => format!("{field}:{}..{}", utc(since), utc(u)),
+        None => format!("{field}:>={}", utc(since)),
+    }
+}
+
+/// `owner/name` from a search hit's `repository_url`
+/// (`https://api.github.com/repos/owner/name`).
+fn repo_of(item: &Value) -> Option<String> {
+    let url = item["repository_url"].as_str()?;
+    let (_, rest) = url.split_once("/repos/")?;
+    Some(rest.to_string())
+}
+
+fn pr_event(item: &Value, action: PrAction, time: i64) -> Option<PrEvent> {
+    Some(PrEvent {
+        time,
+        action,
+        repo: repo_of(item)?,
+        number: item["number"].as_u64()?,
+        title: item["title"].as_str()?.to_string(),
+        url: item["html_url"].as_str()?.to_string(),
+    })
+}
+
+struct Client {
+    api: String,
+    auth: String,
+}
+
+impl Client {
+    fn get(&self, path: &str, query: &[(&str, &str)]) -> Result<Value, String> {
+        let mut req = ureq::get(format!("{}{path}", self.api))
+            .header("Authorization", &self.auth)
+            .header("Accept", "application/vnd.github+json")
+            .header("User-Agent", "work");
+        for (k, v) in query {
+            req = req.query(*k, *v);
+        }
+        req.call()
+            .map_err(|e| match e {
+                ureq::Error::StatusCode(401) => "github: the token was rejected (401)".to_string(),
+                e => format!("github: {path}: {e}"),
+            })?
+            .body_mut()
+            .read_json()
+            .map_err(|e| format!("github: unexpected response from {path}: {e}"))
+    }
+
+    /// Every hit for a search query. The search API stops at 1000 results,
+    /// which is far more PRs than anyone touches in a window.
+    fn search(&self, q: &str) -> Result<Vec<Value>, String> {
+        let mut items = Vec::new();
+        for page in 1..=10 {
+            let page = page.to_string();
+            let res = self.get(
+                "/search/issues",
+                &[("q", q), ("per_page", "100"), ("page", &page)],
+            )?;
+            let hits = res["items"].as_array().cloned().unwrap_or_default();
+            let done = hits.len() < 100;
+            items.extend(hits);
+            if done {
+                break;
+            }
+        }
+        Ok(items)
+    }
+}
+
+/// PRs the user opened, merged and reviewed within the window.
+fn fetch_prs(
+    gh: &Client,
+    login: &str,
+    since: i64,
+    until: Option<i64>,
+) -> Result<Vec<PrEvent>, String> {
+    let in_window = |t: i64| t >= since && until.is_none_or(|u| t < u);
+    let mut out = Vec::new();
+
+    let q = format!(
+        "is:pr author:{login} {}",
+        qualifier("created", since, until)
+    );
+    for item in gh.search(&q)? {
+        if let Some(t) = parse_time(&item["created_at"]) {
+            out.extend(pr_event(&item, PrAction::Opened, t));
+        }
+    }
+
+    let q = format!("is:pr author:{login} {}", qualifier("merged", since, until));
+    for item in gh.search(&q)? {
+        if let Some(t) = parse_time(&item["pull_request"]["merged_at"]) {
+            out.extend(pr_event(&item, PrAction::Merged, t));
+        }
+    }
+
+    // Search can only say a PR was touched in the window; the review's own
+    // timestamp comes from the PR's review list.
+    let q = format!(
+        "is:pr reviewed-by:{login} -author:{login} {}",
+        qualifier("updated", since, until)
+    );
+    for item in gh.search(&q)? {
+        let (Some(repo), Some(number)) = (repo_of(&item), item["number"].as_u64()) else {
+            continue;
+        };
+        let reviews = gh.get(
+            &format!("/repos/{repo}/pulls/{number}/reviews"),
+            &[("per_page", "100")],
+        )?;
+        let last = reviews
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|r| r["user"]["login"].as_str() == Some(login))
+            .filter_map(|r| parse_time(&r["submitted_at"]))
+            .filter(|&t| in_window(t))
+            .max();
+        if let Some(t) = last {
+            out.extend(pr_event(&item, PrAction::Reviewed, t));
+        }
+    }
+
+    out.retain(|e| in_window(e.time));
+    Ok(out)
+}
+
+/// Your commits plus the PRs you opened, merged and reviewed on GitHub, in
+/// one timeline. PRs in repos that are cloned under a scan root are shown
+/// under the local repo's name.
+pub fn github(args: GithubArgs) -> Result<(), String> {
+    let token =
+        args.token.clone().filter(|t| !t.is_empty()).ok_or(
+            "github: no token (pass --token, set GITHUB_TOKEN or github.token in the config)",
+        )?;
+    let gh = Client {
+        api: args
+            .api_url
+            .as_deref()
+            .unwrap_or("https://api.github.com")
+            .trim_end_matches('/')
+            .to_string(),
+        auth: format!("Bearer {token}"),
+    };
+
+    let scan = scan(&args.scan)?;
+    let me = gh.get("/user", &[])?;
+    let login = me["login"]
+        .as_str()
+        .ok_or("github: /user returned no login")?;
+    let mut prs = fetch_prs(&gh, login, scan.since, scan.until)?;
+    prs.sort_by_key(|p| std::cmp::Reverse(p.time));
+
+    // `owner/name` -> local repo name, via each clone's remote URL.
+    let local: HashMap<String, String> = scan
+        .repos
+        .iter()
+        .filter_map(|r| {
+            // One that won't open is in the scan's warnings already.
+            let web = forge::repo_web_url(r).ok().flatten()?;
+            let (_, path) = web.strip_prefix("https://")?.split_once('/')?;
+            Some((path.to_lowercase(), scan.name(r)))
+        })
+        .collect();
+    let repo_label = |p: &PrEvent| {
+        local
+            .get(&p.repo.to_lowercase())
+            .cloned()
+            .unwrap_or_else(|| p.repo.clone())
+    };
+
+    let mut items: Vec<(i64, Item)> = scan
+        .commits
+        .iter()
+        .map(|c| (c.time, Item::Commit(c)))
+        .chain(prs.iter().map(|p| (p.time, Item::Pr(p))))
+        .collect();
+    items.sort_by_key(|(t, _)| std::cmp::Reverse(*t));
+    if items.is_empty() {
+        return Err("No commits or pull requests in this window".to_string());
+    }
+
+    let width = items
+        .iter()
+        .map(|(_, i)| match i {
+            Item::Commit(c) => scan.name(&c.repo).len(),
+            Item::Pr(p) => repo_label(p).len(),
+        })
+        .max()
+        .unwrap_or(0);
+    for (t, item) in &items {
+        let t = format_time_local(*t);
+        match item {
+            Item::Commit(c) => {
+                println!(
+                    "{t}  {}  {}  {}",
+                    paint("1", format!("{:<width$}", scan.name(&c.repo))),
+                    paint("2", &c.short),
+                    c.summary
+                );
+            }
+            Item::Pr(p) => {
+                println!(
+                    "{t}  {}  {}  {}  {}",
+                    paint("1", format!("{:<width$}", repo_label(p))),
+                    paint("35", format!("#{} {}", p.number, p.action.label())),
+                    p.title,
+                    paint("2", &p.url)
+                );
+            }
+        }
+    }
+
+    let count = |a: PrAction| prs.iter().filter(|p| p.action == a).count();
+    println!(
+        "\n{} commits, {} PRs opened, {} merged, {} reviewed",
+        scan.commits.len(),
+        count(PrAction::Opened),
+        count(PrAction::Merged),
+        count(PrAction::Reviewed)
+    );
+    report_warnings(&scan);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn builds_queries_and_reads_search_hits() {
+        assert_eq!(
+            qualifier("created", 0, Some(86_400)),
+            "created:1970-01-01T00:00:00Z..1970-01-02T00:00:00Z"
+        );
+        assert_eq!(
+            qualifier("merged", 0, None),
+            "merged:>=1970-01-01T00:00:00Z"
+        );
+
+        let hit = json!({
+            "repository_url": "https://api.github.com/repos/me/app",
+            "number": 42,
+            "title": "Add login",
+            "html_url": "https://github.com/me/app/pull/42",
+            "created_at": "2026-03-02T10:00:00Z",
+        });
+        let t = parse_time(&hit["created_at"]).unwrap();
+        let ev = pr_event(&hit, PrAction::Opened, t).unwrap();
+        assert_eq!(ev.repo, "me/app");
+        assert_eq!(ev.number, 42);
+        assert_eq!(t, 1_772_445_600);
+    }
+}
@@ -0,0 +1,282 @@
+use crate::{CommitLine, format_time_local, resolve_window, zoned};
+use chrono::{Datelike, Days, NaiveDate};
+use eframe::egui::{self, Color32, Sense, Vec2};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use work::{Cancel, Event, RepoResult, ScanArgs, scan_report_with};
+
+#[derive(clap::Args, Debug)]
+pub struct GuiArgs {
+    #[command(flatten)]
+    pub scan: ScanArgs,
+}
+
+/// What the scan thread has found so far.
+#[derive(Default)]
+struct Shared {
+    /// Repos discovery found; `None` until it's done.
+    total: Option<usize>,
+    /// In the order they finished.
+    repos: Vec<RepoResult>,
+    /// Set when the scan is over: directories it couldn't read, or why it
+    /// failed altogether.
+    done: Option<Result<Vec<(PathBuf, String)>, String>>,
+}
+
+struct App {
+    shared: Arc<Mutex<Shared>>,
+    cancel: Cancel,
+    window: String,
+    /// First and last local day of the window, for the heatmap.
+    days: (NaiveDate, NaiveDate),
+    /// Repos unticked in the filter.
+    hidden: HashSet<String>,
+    search: String,
+}
+
+/// Commits per local day from `first` through `last`.
+fn day_counts(
+    commits: &[(&str, &CommitLine)],
+    first: NaiveDate,
+    last: NaiveDate,
+) -> Vec<(NaiveDate, usize)> {
+    let mut days: Vec<(NaiveDate, usize)> = first
+        .iter_days()
+        .take_while(|d| *d <= last)
+        .map(|d| (d, 0))
+        .collect();
+    for (_, c) in commits {
+        let Some(day) = zoned(c.time).map(|t| t.date_naive()) else {
+            continue;
+        };
+        if let Ok(i) = days.binary_search_by_key(&day, |(d, _)| *d) {
+            days[i].1 += 1;
+        }
+    }
+    days
+}
+
+/// A green from faint to full by `n` against the busiest day's `max`.
+fn shade(n: usize, max: usize) -> Color32 {
+    match n * 4 / max.max(1) {
+        _ if n == 0 => Color32::from_gray(60),
+        0 => Color32::from_rgb(14, 68, 41),
+        1 => Color32::from_rgb(0, 109, 50),
+        2 | 3 => Color32::from_rgb(38, 166, 65),
+        _ => Color32::from_rgb(57, 211, 83),
+    }
+}
+
+/// A column per week and a row per weekday, Monday on top, like a forge's
+/// contribution graph.
+fn heatmap(ui: &mut egui::Ui, days: &[(NaiveDate, usize)]) {
+    const CELL: f32 = 12.0;
+    const GAP: f32 = 2.0;
+    let Some((first, _)) = days.first() else {
+        return;
+    };
+    let offset = first.weekday().num_days_from_monday() as usize;
+    let weeks = (offset + days.len()).div_ceil(7);
+    let size = Vec2::new(weeks as f32 * (CELL + GAP), 7.0 * (CELL + GAP));
+    let (response, painter) = ui.allocate_painter(size, Sense::hover());
+    let max = days.iter().map(|(_, n)| *n).max().unwrap_or(0);
+    let mut hovered = None;
+    for (i, (day, n)) in days.iter().enumerate() {
+        let (week, weekday) = ((offset + i) / 7, (offset + i) % 7);
+        let min = response.rect.min + Vec2::new(week as f32, weekday as f32) * (CELL + GAP);
+        let cell = egui::Rect::from_min_size(min, Vec2::splat(CELL));
+        painter.rect_filled(cell, 2.0, shade(*n, max));
+        if response.hover_pos().is_some_and(|p| cell.contains(p)) {
+            hovered = Some((day, n));
+        }
+    }
+    if let Some((day, n)) = hovered {
+        response.on_hover_text(format!(
+            "{day}: {n} commit{}",
+            if *n == 1 { "" } else { "s" }
+        ));
+    }
+}
+
+impl eframe::App for App {
+    fn ui(&mut self, ui: &mut egui::Ui, _: &mut eframe::Frame) {
+        let shared = self.shared.lock().unwrap_or_else(|e| e.into_inner());
+        let mut repos: Vec<&RepoResult> = shared.repos.iter().collect();
+        repos.sort_by(|a, b| a.name.cmp(&b.name));
+
+        // Copies in other clones are shown once, like the log does.
+        let needle = self.search.to_lowercase();
+        let mut seen = HashSet::new();
+        let mut shown: Vec<(&str, &CommitLine)> = repos
+            .iter()
+            .filter(|r| !self.hidden.contains(&r.name))
+            .flat_map(|r| r.commits.iter().map(|c| (r.name.as_str(), c)))
+            .filter(|(_, c)| needle.is_empty() || c.summary.to_lowercase().contains(&needle))
+            .filter(|(_, c)| seen.insert(c.oid))
+            .collect();
+        shown.sort_by_key(|(_, c)| std::cmp::Reverse(c.time));
+
+        egui::Panel::top("status").show(ui, |ui| {
+            ui.horizontal(|ui| match &shared.done {
+                None => {
+                    ui.spinner();
+                    match shared.total {
+                        Some(total) => {
+                            ui.label(format!("Scanning repos: {}/{total}", shared.repos.len()))
+                        }
+                        None => ui.label("Finding repos…"),
+                    };
+                }
+                Some(Err(e)) => {
+                    ui.colored_label(Color32::LIGHT_RED, e);
+                }
+                Some(Ok(unreadable)) => {
+                    ui.label(format!(
+                        "{} commits in {} repos, {}",
+                        shown.len(),
+                        repos.len(),
+                        self.window
+                    ));
+                    if !unreadable.is_empty() {
+                        ui.weak(format!(
+                            "({} directories couldn't be read)",
+                            unreadable.len()
+                        ));
+                    }
+                }
+            });
+        });
+
+        egui::Panel::left("repos").show(ui, |ui| {
+            ui.add(egui::TextEdit::singleline(&mut self.search).hint_text("Filter summaries"));
+            ui.separator();
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for r in &repos {
+                    let mut on = !self.hidden.contains(&r.name);
+                    let label = format!("{} ({})", r.name, r.commits.len());
+                    if ui.checkbox(&mut on, label).changed() {
+                        match on {
+                            true => self.hidden.remove(&r.name),
+                            false => self.hidden.insert(r.name.clone()),
+                        };
+                    }
+                }
+            });
+        });
+
+        egui::CentralPanel::default().show(ui, |ui| {
+            heatmap(ui, &day_counts(&shown, self.days.0, self.days.1));
+            ui.separator();
+            let row = ui.text_style_height(&egui::TextStyle::Body) + ui.spacing().item_spacing.y;
+            egui::ScrollArea::vertical().show_rows(ui, row, shown.len(), |ui, range| {
+                for (repo, c) in &shown[range] {
+                    ui.horizontal(|ui| {
+                        ui.monospace(format_time_local(c.time));
+                        ui.strong(*repo);
+                        ui.weak(&c.short);
+                        ui.colored_label(
+                            Color32::from_rgb(57, 211, 83),
+                            format!("+{}", c.insertions),
+                        );
+                        ui.colored_label(Color32::LIGHT_RED, format!("-{}", c.deletions));
+                        ui.label(&c.summary);
+                    });
+                }
+            });
+        });
+    }
+}
+
+impl Drop for App {
+    /// Closing the window stops a scan that's still going.
+    fn drop(&mut self) {
+        self.cancel.cancel();
+    }
+}
+
+/// `work gui`: the window's commits in a desktop window, filled in as each
+/// repo is scanned: a feed, a heatmap of commits per day and a repo
+/// filter.
+pub fn gui(args: GuiArgs) -> Result<(), String> {
+    let window = args.scan.window();
+    let (since, until) = resolve_window(window)?;
+    let local_day = |ts: i64| zoned(ts).map(|t| t.date_naive());
+    let last = until.map_or_else(|| local_day(crate::now().timestamp()), |u| local_day(u - 1));
+    let days = match (local_day(since), last) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return Err("work: the window's dates are out of range".to_string()),
+    };
+    // A heatmap of decades is no use; show the last year of a long window.
+    let days = (days.0.max(days.1 - Days::new(365)), days.1);
+
+    let shared = Arc::new(Mutex::new(Shared::default()));
+    let cancel = Cancel::default();
+    let app = App {
+        shared: shared.clone(),
+        cancel: cancel.clone(),
+        window: window.label(),
+        days,
+        hidden: HashSet::new(),
+        search: String::new(),
+    };
+    let options = eframe::NativeOptions {
+        viewport: egui::ViewportBuilder::default()
+            .with_title("work")
+            .with_inner_size([1000.0, 700.0]),
+        ..Default::default()
+    };
+    eframe::run_native(
+        "work",
+        options,
+        Box::new(move |cc| {
+            let ctx = cc.egui_ctx.clone();
+            std::thread::spawn(move || {
+                let lock = || shared.lock().unwrap_or_else(|e| e.into_inner());
+                let hook = |e: Event| {
+                    match e {
+                        Event::Found(plan) => lock().total = Some(plan.repos.len()),
+                        Event::Finished(r) => lock().repos.push(r.clone()),
+                        Event::Started { .. } => return,
+                    }
+                    ctx.request_repaint();
+                };
+                let report = scan_report_with(&args.scan, &cancel, &hook);
+                lock().done = Some(report.map(|r| r.errors));
+                ctx.request_repaint();
+            });
+            Ok(Box::new(app))
+        }),
+    )
+    .map_err(|e| format!("work: cannot open a window: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::local_midnight;
+
+    #[test]
+    fn counts_commits_per_local_day() {
+        let day = 24 * 60 * 60;
+        let first = NaiveDate::from_ymd_opt(2026, 3, 2).unwrap();
+        let last = NaiveDate::from_ymd_opt(2026, 3, 5).unwrap();
+        // Local noon, so every zone (even UTC+14) puts it on the 2nd.
+        let monday = local_midnight(first) + day / 2;
+        let commits = [
+            CommitLine::stub("/code/api", monday),
+            CommitLine::stub("/code/api", monday + 60),
+            CommitLine::stub("/code/web", monday + 2 * day),
+            // Outside the days asked for.
+            CommitLine::stub("/code/web", monday + 9 * day),
+        ];
+        let commits: Vec<(&str, &CommitLine)> = commits.iter().map(|c| ("api", c)).collect();
+        let counts: Vec<usize> = day_counts(&commits, first, last)
+            .iter()
+            .map(|(_, n)| *n)
+            .collect();
+        assert_eq!(counts, [2, 0, 1, 0]);
+        assert_eq!(shade(0, 5), Color32::from_gray(60));
+        assert_ne!(shade(5, 5), shade(1, 5));
+    }
+}
@@ -0,0 +1,175 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Read from each scan root.
+pub const FILE_NAME: &str = ".workignore";
+
+/// One gitignore-style line.
+#[derive(Debug)]
+struct Rule {
+    pattern: glob::Pattern,
+    /// `!pattern`: re-includes what an earlier rule excluded.
+    negated: bool,
+    /// No `/` in the pattern, so it matches a directory's name at any depth.
+    name_only: bool,
+}
+
+/// Directories discovery leaves alone under one scan root: `ignore` from
+/// the config file, then the root's `.workignore`, in gitignore syntax.
+#[derive(Debug, Default)]
+pub struct Ignore {
+    root: PathBuf,
+    rules: Vec<Rule>,
+}
+
+fn parse_line(line: &str) -> Result<Option<Rule>, String> {
+    let line = line.trim_end();
+    if line.is_empty() || line.starts_with('#') {
+        return Ok(None);
+    }
+    let (negated, line) = match line.strip_prefix('!') {
+        Some(rest) => (true, rest),
+        None => (false, line.strip_prefix('\\').unwrap_or(line)),
+    };
+    // Discovery only ever looks at directories, so a trailing `/` changes
+    // nothing.
+    let line = line.trim_end_matches('/');
+    // `**/name` is just `name`: a directory called that at any depth.
+    let line = match line.strip_prefix("**/") {
+        Some(rest) if !rest.contains('/') => rest,
+        _ => line,
+    };
+    let name_only = !line.contains('/');
+    let line = line.strip_prefix('/').unwrap_or(line);
+    let pattern = glob::Pattern::new(line).map_err(|e| format!("invalid pattern '{line}': {e}"))?;
+    Ok(Some(Rule {
+        pattern,
+        negated,
+        name_only,
+    }))
+}
+
+impl Ignore {
+    pub fn load(root: &Path, config: &[String]) -> Result<Self, String> {
+        let mut rules = Vec::new();
+        for line in config {
+            rules.extend(parse_line(line).map_err(|e| format!("work: config: ignore: {e}"))?);
+        }
+        let file = root.join(FILE_NAME);
+        match fs::read_to_string(&file) {
+            Ok(text) => {
+                for (n, line) in text.lines().enumerate() {
+                    rules.extend(
+                        parse_line(line)
+                            .map_err(|e| format!("work: {}:{}: {e}", file.display(), n + 1))?,
+                    );
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(format!("work: cannot read '{}': {e}", file.display())),
+        }
+        Ok(Ignore {
+            root: root.to_path_buf(),
+            rules,
+        })
+    }
+
+    /// Name patterns from git's global excludes file (`core.excludesFile`,
+    /// or `$XDG_CONFIG_HOME/git/ignore`), so discovery skips what git never
+    /// tracks anyway: `node_modules`, build output, editor state. A file
+    /// that can't be read, or a line that doesn't parse, means no rules
+    /// rather than an error: it's git's file, not ours.
+    pub fn global() -> Self {
+        let path = git2::Config::open_default()
+            .and_then(|c| c.get_path("core.excludesfile"))
+            .ok()
+            .or_else(|| {
+                let config = match std::env::var_os("XDG_CONFIG_HOME").filter(|v| !v.is_empty()) {
+                    Some(d) => PathBuf::from(d),
+                    None => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+                };
+                Some(config.join("git").join("ignore"))
+            });
+        let text = path
+            .and_then(|p| fs::read_to_string(p).ok())
+            .unwrap_or_default();
+        Self::global_from(&text)
+    }
+
+    /// Patterns with a `/` are anchored to each repo's top, which means
+    /// nothing above the repos, so only the bare names (and `**/name`) are
+    /// kept.
+    fn global_from(text: &str) -> Self {
+        Ignore {
+            root: PathBuf::new(),
+            rules: text
+                .lines()
+                .filter_map(|l| parse_line(l).ok().flatten())
+                .filter(|r| r.name_only)
+                .collect(),
+        }
+    }
+
+    /// Whether `dir` (somewhere under the root) is ignored. As in git, the
+    /// last matching rule decides.
+    pub fn is_ignored(&self, dir: &Path) -> bool {
+        let Ok(rel) = dir.strip_prefix(&self.root) else {
+            return false;
+        };
+        let rel = rel.to_string_lossy();
+        let name = dir
+            .file_name()
+            .map(|n| n.to_string_lossy())
+            .unwrap_or_default();
+        let opts = glob::MatchOptions {
+            require_literal_separator: true,
+            ..Default::default()
+        };
+        self.rules
+            .iter()
+            .rev()
+            .find(|r| {
+                let subject = if r.name_only { &name } else { &rel };
+                r.pattern.matches_with(subject, opts)
+            })
+            .is_some_and(|r| !r.negated)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn follows_gitignore_rules() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        fs::write(
+            root.join(FILE_NAME),
+            "# old stuff\narchive/\n/mirrors/*\n!mirrors/keep\nvendor-*\n",
+        )
+        .unwrap();
+        let ignore = Ignore::load(root, &["**/tmp".to_string()]).unwrap();
+
+        assert!(ignore.is_ignored(&root.join("archive")));
+        assert!(ignore.is_ignored(&root.join("apps/archive")));
+        assert!(ignore.is_ignored(&root.join("mirrors/linux")));
+        assert!(!ignore.is_ignored(&root.join("mirrors/keep")));
+        assert!(!ignore.is_ignored(&root.join("apps/mirrors/linux")));
+        assert!(ignore.is_ignored(&root.join("libs/vendor-zlib")));
+        assert!(ignore.is_ignored(&root.join("apps/web/tmp")));
+        assert!(!ignore.is_ignored(&root.join("apps/web")));
+
+        let global =
+            Ignore::global_from("node_modules/\n/build\n*.swp\n[oops\n**/.idea/\n**/out/tmp\n");
+        assert!(global.is_ignored(&root.join("apps/web/node_modules")));
+        assert!(global.is_ignored(&root.join("apps/web/.idea")));
+        assert!(global.is_ignored(&root.join(".idea")));
+        assert!(!global.is_ignored(&root.join("apps/out/tmp")));
+        assert!(!global.is_ignored(&root.join("build")));
+
+        fs::write(root.join(FILE_NAME), "[oops\n").unwrap();
+        let err = Ignore::load(root, &[]).unwrap_err();
+        assert!(err.contains(".workignore:1"), "{err}");
+    }
+}
@@ -0,0 +1,244 @@
+use crate::render::Renderer;
+use crate::{Args, CommitLine, Scan, paint};
+use std::collections::{HashMap, HashSet};
+use std::io::{self, Write};
+use std::path::Path;
+use whatlang::Lang;
+
+/// How sure whatlang has to be. Its own `is_reliable` turns down most
+/// one-line English summaries; below this, guesses on short messages are
+/// mostly noise ("Update dependencies" comes out Dutch).
+const MIN_CONFIDENCE: f64 = 0.5;
+
+/// The language a commit message is written in, from its summary and body.
+/// `None` when it's too short or mixed to tell, which is common: "fix
+/// typo" could be half a dozen languages.
+pub fn detect(summary: &str, body: Option<&str>) -> Option<Lang> {
+    let text = match body {
+        Some(body) => format!("{summary}\n{body}"),
+        None => summary.to_string(),
+    };
+    whatlang::detect(&text)
+        .filter(|info| info.confidence() >= MIN_CONFIDENCE)
+        .map(|info| info.lang())
+}
+
+/// `--lang` / `--exclude-lang`: an ISO 639-3 code (`eng`, `spa`, `cmn`) or
+/// an English name (`english`, `spanish`).
+pub fn parse_lang(s: &str) -> Result<Lang, String> {
+    let s = s.trim().to_lowercase();
+    Lang::from_code(s.as_str())
+        .or_else(|| {
+            Lang::all()
+                .iter()
+                .copied()
+                .find(|l| l.eng_name().eq_ignore_ascii_case(&s))
+        })
+        .ok_or_else(|| {
+            format!(
+                "unknown language '{s}' (try an ISO 639-3 code like eng, or a name like english)"
+            )
+        })
+}
+
+/// Whether a commit in `lang` passes the filters. Commits whose language
+/// couldn't be told pass both: dropping every short summary from `--lang
+/// eng` would drop most English ones.
+pub fn wanted(lang: Option<Lang>, only: &[Lang], exclude: &[Lang]) -> bool {
+    let Some(lang) = lang else {
+        return true;
+    };
+    (only.is_empty() || only.contains(&lang)) && !exclude.contains(&lang)
+}
+
+/// One language's share of the window.
+#[derive(Debug)]
+struct LanguageRow {
+    lang: Option<Lang>,
+    commits: usize,
+    authors: usize,
+    repos: usize,
+    insertions: usize,
+    deletions: usize,
+}
+
+/// Busiest language first, with undetected messages last.
+fn group(commits: &[&CommitLine]) -> Vec<LanguageRow> {
+    #[derive(Default)]
+    struct Acc<'a> {
+        commits: usize,
+        authors: HashSet<String>,
+        repos: HashSet<&'a Path>,
+        insertions: usize,
+        deletions: usize,
+    }
+
+    let mut by_lang: HashMap<Option<Lang>, Acc> = HashMap::new();
+    for c in commits {
+        let acc = by_lang.entry(c.lang).or_default();
+        acc.commits += 1;
+        acc.authors.insert(c.author_email.to_lowercase());
+        acc.repos.insert(&c.repo);
+        acc.insertions = acc.insertions.saturating_add(c.insertions);
+        acc.deletions = acc.deletions.saturating_add(c.deletions);
+    }
+
+    let mut rows: Vec<LanguageRow> = by_lang
+        .into_iter()
+        .map(|(lang, acc)| LanguageRow {
+            lang,
+            commits: acc.commits,
+            authors: acc.authors.len(),
+            repos: acc.repos.len(),
+            insertions: acc.insertions,
+            deletions: acc.deletions,
+        })
+        .collect();
+    rows.sort_by(|a, b| {
+        a.lang
+            .is_none()
+            .cmp(&b.lang.is_none())
+            .then_with(|| b.commits.cmp(&a.commits))
+            .then_with(|| a.lang.map(|l| l.code()).cmp(&b.lang.map(|l| l.code())))
+    });
+    rows
+}
+
+fn name(lang: Option<Lang>) -> &'static str {
+    lang.map_or("(undetected)", Lang::eng_name)
+}
+
+/// `--group-by language`: commits, authors and LoC per detected commit
+/// message language. Given every commit in the window.
+pub struct Languages<'a> {
+    pub args: &'a Args,
+}
+
+impl Renderer for Languages<'_> {
+    fn render(&self, out: &mut dyn Write, _: &Scan, commits: &[&CommitLine]) -> io::Result<()> {
+        let rows = group(commits);
+
+        if self.args.raw {
+            // code\tname\tcommits\tauthors\trepos\t+ins\t-del
+            for r in &rows {
+                writeln!(
+                    out,
+                    "{}\t{}\t{}\t{}\t{}\t+{}\t-{}",
+                    r.lang.map_or("-", |l| l.code()),
+                    name(r.lang),
+                    r.commits,
+                    r.authors,
+                    r.repos,
+                    r.insertions,
+                    r.deletions
+                )?;
+            }
+            return Ok(());
+        }
+
+        let width = rows.iter().map(|r| name(r.lang).len()).max().unwrap_or(0);
+        let commits_width = rows
+            .iter()
+            .map(|r| r.commits.to_string().len())
+            .max()
+            .unwrap_or(1);
+        for r in &rows {
+            writeln!(
+                out,
+                "{}  {:>commits_width$} commits  {:>4} authors  {:>4} repos  {} {}",
+                paint("1", format!("{:<width$}", name(r.lang))),
+                r.commits,
+                r.authors,
+                r.repos,
+                paint("32", format!("+{}", r.insertions)),
+                paint("31", format!("-{}", r.deletions)),
+            )?;
+        }
+        let detected: usize = rows
+            .iter()
+            .filter(|r| r.lang.is_some())
+            .map(|r| r.commits)
+            .sum();
+        writeln!(
+            out,
+            "\n{detected} of {} commit messages detected ({})",
+            commits.len(),
+            self.args.scan.window().label()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[test]
+    fn detects_filters_and_groups_by_language() {
+        assert_eq!(
+            detect(
+                "Corrige el error de inicio de sesión cuando expira la sesión",
+                None
+            ),
+            Some(Lang::Spa)
+        );
+        assert_eq!(
+            detect(
+                "Fix login redirect",
+                Some("The session expired before the redirect was sent.")
+            ),
+            Some(Lang::Eng)
+        );
+        assert_eq!(detect("fix typo", None), None);
+
+        assert_eq!(parse_lang("ENG"), Ok(Lang::Eng));
+        assert_eq!(parse_lang("spanish"), Ok(Lang::Spa));
+        assert!(parse_lang("klingon").is_err());
+
+        assert!(wanted(Some(Lang::Eng), &[Lang::Eng], &[]));
+        assert!(!wanted(Some(Lang::Spa), &[Lang::Eng], &[]));
+        assert!(!wanted(Some(Lang::Spa), &[], &[Lang::Spa]));
+        assert!(wanted(None, &[Lang::Eng], &[Lang::Spa]));
+
+        let line = |lang, email: &str| CommitLine {
+            lang,
+            author_email: email.to_string(),
+            ..CommitLine::stub("api", 0)
+        };
+        let scan = Scan::stub(
+            &["/code"],
+            vec![
+                line(None, "a@x.io"),
+                line(Some(Lang::Spa), "b@x.io"),
+                line(Some(Lang::Eng), "a@x.io"),
+                line(Some(Lang::Eng), "c@x.io"),
+            ],
+        );
+        let commits: Vec<&CommitLine> = scan.commits.iter().collect();
+        let rows = group(&commits);
+        let got: Vec<(Option<Lang>, usize, usize)> = rows
+            .iter()
+            .map(|r| (r.lang, r.commits, r.authors))
+            .collect();
+        assert_eq!(
+            got,
+            [
+                (Some(Lang::Eng), 2, 2),
+                (Some(Lang::Spa), 1, 1),
+                (None, 1, 1)
+            ]
+        );
+
+        let args = Args::parse_from(["work", "--all", "--group-by", "language", "--raw", "x"]);
+        let mut out = Vec::new();
+        Languages { args: &args }
+            .render(&mut out, &scan, &commits)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "eng\tEnglish\t2\t2\t1\t+0\t-0\n\
+             spa\tSpanish\t1\t1\t1\t+0\t-0\n\
+             -\t(undetected)\t1\t1\t1\t+0\t-0\n"
+        );
+    }
+}
@@ -0,0 +1,4933 @@
+//! `work`: your recent commits across many git repos. The whole CLI lives
+//! here, and `main.rs` only calls [`main`], so other front ends can scan
+//! with the same flags and get the same results: [`ScanArgs::parse_from`]
+//! takes the flags, [`scan_report`] returns what each repo had. Also time
+//! window arithmetic, and helpers for building repos with reproducible
+//! history, so golden-file tests (ours and packagers') get the same hashes
+//! everywhere.
+
+// Modules name the library the way outside code does.
+extern crate self as work;
+
+mod activitywatch;
+mod alfred;
+mod bench;
+mod churn;
+mod compare;
+mod config;
+mod db;
+mod domains;
+mod events;
+mod export;
+mod feed;
+pub mod fixtures;
+mod forge;
+mod github;
+#[cfg(feature = "gui")]
+mod gui;
+mod ignore;
+mod language;
+mod maintain;
+mod menubar;
+mod open;
+mod plain;
+mod pretty;
+mod progress;
+mod punchcard;
+mod raw;
+mod releases;
+mod render;
+mod report;
+mod repos;
+mod scanreport;
+mod seen;
+mod session;
+mod show;
+mod signing;
+mod statcache;
+mod ticket;
+mod timewarrior;
+mod usage;
+mod watch;
+mod waybar;
+mod whoami;
+pub mod window;
+
+pub use render::Renderer;
+pub use scanreport::{
+    Cancel, Event, EventHook, Outcome, Plan, RepoResult, ScanReport, Timings, scan_report,
+    scan_report_with,
+};
+
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
+use clap_complete::ArgValueCandidates;
+use git2::{Config, Oid, Repository};
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write as _;
+use std::fs;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, Once, OnceLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use unicode_width::UnicodeWidthChar;
+use window::Window;
+
+#[derive(Parser, Debug)]
+#[command(
+    about = "Show your recent commits across many git repos",
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Cmd>,
+
+    /// Timezone for windows and printed times, e.g. Europe/Berlin [default: `tz` from the config, else the system's]
+    #[arg(long, global = true, value_name = "ZONE", value_parser = parse_tz)]
+    tz: Option<chrono_tz::Tz>,
+
+    /// Use the paths, identity, excludes and window of `[profile.NAME]` in the config file
+    #[arg(long, global = true, value_name = "NAME", add = ArgValueCandidates::new(config::profile_names))]
+    profile: Option<String>,
+
+    #[command(flatten)]
+    args: Args,
+}
+
+#[derive(Subcommand, Debug)]
+enum Cmd {
+    /// Push your git activity into another tool
+    Sync {
+        #[command(subcommand)]
+        target: SyncTarget,
+    },
+
+    /// Keep a local SQLite history of your commits and query it
+    Db {
+        #[command(subcommand)]
+        action: db::DbAction,
+    },
+
+    /// Write a shareable report of the window's commits
+    Report(report::ReportArgs),
+
+    /// Your commits plus the pull requests you opened, merged and reviewed on GitHub
+    Github(github::GithubArgs),
+
+    /// Plugin output for xbar, SwiftBar and Argos menu bar widgets (default: today)
+    Menubar(menubar::MenubarArgs),
+
+    /// JSON for a Waybar custom module (default: today)
+    Waybar(waybar::WaybarArgs),
+
+    /// Run `git maintenance` in repos whose object stores slow scans down
+    Maintain(maintain::MaintainArgs),
+
+    /// List the repos found, with branch, last commit, ahead/behind and dirty state
+    Repos(repos::ReposArgs),
+
+    /// Others' commits in the repos you've been committing to lately
+    Feed(feed::FeedArgs),
+
+    /// Commits, lines and active repos in this window against another one
+    Compare(compare::CompareArgs),
+
+    /// Annotated tags made in the window, with the commits since the last one
+    Releases(releases::ReleasesArgs),
+
+    /// Commits in the window on a day-of-week × hour-of-day grid
+    Punchcard(punchcard::PunchcardArgs),
+
+    /// The files changed in the most commits in the window, per repo
+    Churn(churn::ChurnArgs),
+
+    /// Write the window's work sessions or commits to a file for another app (iCalendar)
+    Export(export::ExportArgs),
+
+    /// Keep the log (today's, by default) on screen, redrawn as commits land
+    Watch(watch::WatchArgs),
+
+    /// How you use work, from the opt-in local usage log, and what to tune
+    Stats(usage::StatsArgs),
+
+    /// Open a commit on its forge (GitHub, GitLab, ...) in the browser
+    Open(open::OpenArgs),
+
+    /// Print a commit found in any discovered repo: metadata, message, files and optionally the patch
+    Show(show::ShowArgs),
+
+    /// Browse the window's commits in a desktop window: a feed, a heatmap and a repo filter
+    #[cfg(feature = "gui")]
+    Gui(gui::GuiArgs),
+
+    /// Run a named view from the config file; without a name, list them
+    View {
+        #[arg(add = ArgValueCandidates::new(config::view_names))]
+        name: Option<String>,
+
+        /// More arguments, added after the view's own
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+
+    /// Create or locate the config file
+    Config {
+        #[command(subcommand)]
+        action: config::ConfigAction,
+    },
+
+    /// Time scans of a generated tree of synthetic repos
+    #[command(hide = true)]
+    Bench(bench::BenchArgs),
+
+    /// Print a shell completion script (e.g. `source <(work completions zsh)`)
+    Completions {
+        #[arg(value_enum)]
+        shell: clap_complete::Shell,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum SyncTarget {
+    /// Send commits and inferred work sessions to a local ActivityWatch server
+    Activitywatch(activitywatch::AwArgs),
+
+    /// Backfill timewarrior with inferred work sessions (`timew track`)
+    Timewarrior(timewarrior::TimewArgs),
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum DateKind {
+    /// When the change was first written (survives rebases and cherry-picks)
+    Author,
+    /// When the commit was last rewritten (git log's default order)
+    Committer,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum Column {
+    /// Commit time
+    Time,
+    /// Repo, relative to its scan root
+    Repo,
+    /// Short commit hash
+    Hash,
+    /// Files changed, lines added and removed
+    Stats,
+    /// First line of the message
+    Summary,
+    /// Author name
+    Author,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum SortKey {
+    /// Newest first
+    Time,
+    /// By repo, newest first within each
+    Repo,
+    /// Biggest first, by lines added plus lines removed
+    Size,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum GroupBy {
+    /// Author email domain, split into your organization and everyone else
+    Domain,
+    /// The language commit messages are written in, as detected
+    Language,
+}
+
+/// What to scan and which commits count. Shared by every subcommand.
+#[derive(clap::Args, Clone, Debug)]
+pub struct ScanArgs {
+    /// Directories to scan [default: `paths` from the config file]
+    #[arg(value_name = "PATH")]
+    paths: Vec<PathBuf>,
+
+    /// Max depth to search for repos
+    #[arg(short = 'L', default_value = "3")]
+    depth: usize,
+
+    /// How many days back to look [default: 7]
+    #[arg(long, group = "window")]
+    days: Option<i64>,
+
+    /// Shortcut for "commits since local midnight"
+    #[arg(long, group = "window")]
+    today: bool,
+
+    /// Shortcut for "commits from the previous local day only", for standups
+    #[arg(long, group = "window")]
+    yesterday: bool,
+
+    /// Shortcut for "commits since Monday 00:00 local time"
+    #[arg(long, visible_alias = "this-week", group = "window")]
+    week: bool,
+
+    /// Shortcut for "commits from the previous Monday-to-Sunday week only"
+    #[arg(long, group = "window")]
+    last_week: bool,
+
+    /// Commits from one ISO week, e.g. 2026-W12
+    #[arg(long, group = "window", value_name = "YYYY-Www", value_parser = window::parse_iso_week)]
+    iso_week: Option<chrono::NaiveDate>,
+
+    /// Shortcut for "commits since the start of the local calendar month"
+    #[arg(long, group = "window")]
+    month: bool,
+
+    /// Shortcut for "commits from the previous calendar month only"
+    #[arg(long, group = "window")]
+    last_month: bool,
+
+    /// Shortcut for "commits since the start of the calendar quarter"
+    #[arg(long, group = "window")]
+    quarter: bool,
+
+    /// Shortcut for "commits from the previous calendar quarter only"
+    #[arg(long, group = "window")]
+    last_quarter: bool,
+
+    /// Commits from this one local date
+    #[arg(long, group = "window", value_name = "YYYY-MM-DD")]
+    on: Option<chrono::NaiveDate>,
+
+    /// Commits on or after this local date
+    #[arg(long, group = "window", value_name = "YYYY-MM-DD")]
+    since: Option<chrono::NaiveDate>,
+
+    /// With --since: commits on or before this local date
+    #[arg(long, requires = "since", value_name = "YYYY-MM-DD")]
+    until: Option<chrono::NaiveDate>,
+
+    /// Fetch from remotes before scanning (slower)
+    #[arg(long)]
+    remote: bool,
+
+    /// Give up on a repo (fetch and history walk) after this many seconds
+    #[arg(long, value_name = "SECS", value_parser = clap::value_parser!(u64).range(1..))]
+    repo_timeout: Option<u64>,
+
+    /// Don't filter to your author identity
+    #[arg(long)]
+    all: bool,
+
+    /// Someone else's commits instead of yours: part of a name or email, a glob, or /regex/ (repeatable)
+    #[arg(long = "author", value_name = "PATTERN", value_parser = parse_author_pattern)]
+    authors: Vec<AuthorPattern>,
+
+    /// Include merge commits
+    #[arg(long)]
+    merges: bool,
+
+    /// Follow only the first parent of merges, so each merge stands for the
+    /// branch it brought in; implies --merges
+    #[arg(long)]
+    first_parent: bool,
+
+    /// Which commit timestamp drives the window, sorting and display
+    #[arg(long, value_enum, default_value = "committer")]
+    date: DateKind,
+
+    /// Hash length to show; longer where the repo has another object with that prefix, like git
+    #[arg(long, value_name = "N", default_value = "7", value_parser = clap::value_parser!(u8).range(4..=40))]
+    abbrev: u8,
+
+    /// Show full 40-character hashes
+    #[arg(long, conflicts_with = "abbrev")]
+    full_hash: bool,
+
+    /// Leave matching paths out of LoC stats (repeatable, pathspec glob)
+    #[arg(long = "stat-exclude", value_name = "GLOB")]
+    stat_exclude: Vec<String>,
+
+    /// Don't apply the built-in lockfile excludes to LoC stats
+    #[arg(long)]
+    no_default_stat_excludes: bool,
+
+    /// Leave files whose only change is their mode or symlink target out of LoC stats
+    #[arg(long)]
+    exclude_mode_changes: bool,
+
+    /// Don't count lines whose only change is whitespace (like `git diff -w`), so formatter runs don't dominate LoC stats
+    #[arg(short = 'w', long)]
+    ignore_whitespace: bool,
+
+    /// Only commits with at least N lines changed (insertions plus deletions), to hide typo fixes
+    #[arg(long, value_name = "N")]
+    min_changes: Option<usize>,
+
+    /// Only commits with at most N lines changed (insertions plus deletions)
+    #[arg(long, value_name = "N")]
+    max_changes: Option<usize>,
+
+    /// Only commits touching matching paths, with LoC stats limited to them (repeatable pathspec)
+    #[arg(long = "path", value_name = "PATHSPEC")]
+    path_filter: Vec<String>,
+
+    /// Don't look inside directories whose name starts with a dot while finding repos
+    #[arg(long)]
+    skip_hidden: bool,
+
+    /// Look inside directories your global gitignore (core.excludesFile) matches by name while finding repos
+    #[arg(long)]
+    no_global_ignore: bool,
+
+    /// Only scan repos whose path (relative to its root) matches (repeatable glob)
+    #[arg(long = "repo", value_name = "GLOB", value_parser = parse_glob)]
+    repo_globs: Vec<glob::Pattern>,
+
+    /// Skip repos whose path (relative to its root) matches (repeatable glob)
+    #[arg(long = "exclude-repo", value_name = "GLOB", value_parser = parse_glob)]
+    exclude_repo_globs: Vec<glob::Pattern>,
+
+    /// Also scan initialized submodules, named parent/submodule
+    #[arg(long)]
+    submodules: bool,
+
+    /// Only commits whose diff changes how often STRING appears, like `git log -S`
+    #[arg(long, value_name = "STRING")]
+    find_code: Option<String>,
+
+    /// Only commits with this trailer (Co-authored-by, Fixes, ...), its value containing VALUE
+    /// if given; repeatable, all must match
+    #[arg(long = "trailer", value_name = "KEY[=VALUE]", value_parser = parse_trailer_filter)]
+    trailer_filters: Vec<TrailerFilter>,
+
+    /// Only commits whose message is in this language: ISO 639-3 code or English name (repeatable)
+    #[arg(long = "lang", value_name = "LANG", value_parser = language::parse_lang)]
+    langs: Vec<whatlang::Lang>,
+
+    /// Leave out commits whose message is in this language (repeatable)
+    #[arg(long = "exclude-lang", value_name = "LANG", value_parser = language::parse_lang)]
+    exclude_langs: Vec<whatlang::Lang>,
+
+    /// Skip repos whose git files haven't changed since the window started
+    #[arg(long, conflicts_with = "remote")]
+    active_only: bool,
+
+    /// Skip commits by bots: dependabot, renovate, github-actions and the like
+    #[arg(long)]
+    no_bots: bool,
+
+    /// Extra author patterns for --no-bots, from `bots` in the config file.
+    #[arg(skip)]
+    bots: Vec<glob::Pattern>,
+
+    /// `.workignore` lines from `ignore` in the config file, for every root.
+    #[arg(skip)]
+    ignore: Vec<String>,
+
+    /// Who counts as you, from `--profile`, instead of git's `user.*`.
+    /// Boxed so every subcommand's args don't grow by two strings.
+    #[arg(skip)]
+    identity: Option<Box<Identity>>,
+
+    /// The `--profile`'s window, for when no window flag is given.
+    #[arg(skip)]
+    default_window: Option<Window>,
+
+    /// Don't reuse or save diff stats from earlier runs
+    #[arg(long)]
+    no_stat_cache: bool,
+
+    /// In partial clones, let git fetch the objects diff stats need (a round trip per commit)
+    #[arg(long)]
+    allow_lazy_fetch: bool,
+
+    /// Keep each commit's per-file changes, for `work churn`.
+    #[arg(skip)]
+    file_changes: bool,
+
+    /// Detect each commit message's language even without `--lang`.
+    #[arg(skip)]
+    detect_language: bool,
+
+    /// Verify each commit's signature, for `--show-signatures`.
+    #[arg(skip)]
+    signatures: bool,
+
+    /// Stops the scan early; see `scan_report_with`.
+    #[arg(skip)]
+    cancel: Cancel,
+
+    /// `record_usage` from the config file: log each scan for `work stats --self`.
+    #[arg(skip)]
+    record_usage: bool,
+
+    /// Take diff stats from the cache and diff nothing new, for views that
+    /// show no sizes and run every few seconds (the waybar module). A
+    /// `--min-changes` / `--max-changes` still diffs, as it needs sizes.
+    #[arg(skip)]
+    cached_stats_only: bool,
+
+    /// Where diff stats are cached, set at startup unless --no-stat-cache
+    /// or --deterministic. Unset in tests, which never touch the cache.
+    #[arg(skip)]
+    stat_cache: Option<PathBuf>,
+
+    /// Show a commit once per clone/worktree instead of once overall
+    #[arg(long)]
+    no_dedupe: bool,
+
+    /// Collapse cherry-picks: keep only the earliest commit per patch-id
+    #[arg(long)]
+    dedupe_patches: bool,
+
+    /// Choose which of the discovered repos to scan in fzf (TAB to multi-select)
+    #[arg(long)]
+    pick: bool,
+
+    /// Open repos owned by other users without a `safe.directory` entry (for containers)
+    #[arg(long)]
+    trust_all: bool,
+
+    /// Don't show scan progress on stderr
+    #[arg(short, long)]
+    quiet: bool,
+
+    /// Reproducible output for golden-file tests: clock from $WORK_NOW, UTC, no colors, no config
+    #[arg(long)]
+    deterministic: bool,
+}
+
+impl ScanArgs {
+    /// The scan flags as `work` takes them on the command line, e.g.
+    /// `["~/code", "--days", "30", "--all"]`. The config file isn't read.
+    pub fn parse_from<I, T>(flags: I) -> Result<Self, String>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<std::ffi::OsString> + Clone,
+    {
+        #[derive(Parser)]
+        struct Flags {
+            #[command(flatten)]
+            scan: ScanArgs,
+        }
+        let argv = std::iter::once("work".into()).chain(flags.into_iter().map(Into::into));
+        Flags::try_parse_from(argv)
+            .map(|f| f.scan)
+            .map_err(|e| e.to_string())
+    }
+
+    fn days(&self) -> i64 {
+        self.days.unwrap_or(7)
+    }
+
+    /// Whether a commit changing `lines` lines passes `--min-changes` and
+    /// `--max-changes`.
+    fn wants_size(&self, lines: usize) -> bool {
+        self.min_changes.is_none_or(|min| lines >= min)
+            && self.max_changes.is_none_or(|max| lines <= max)
+    }
+
+    /// Whether any window flag was given, so views with their own natural
+    /// window (like the menubar's "today") know when to step aside.
+    fn has_window(&self) -> bool {
+        self.days.is_some()
+            || self.today
+            || self.yesterday
+            || self.week
+            || self.last_week
+            || self.iso_week.is_some()
+            || self.month
+            || self.last_month
+            || self.quarter
+            || self.last_quarter
+            || self.on.is_some()
+            || self.since.is_some()
+    }
+
+    /// Today, unless a window flag was given: the natural window of the
+    /// views that sit in a bar or a terminal all day.
+    fn today_by_default(&mut self) {
+        if !self.has_window() {
+            self.today = true;
+        }
+    }
+
+    fn window(&self) -> Window {
+        if self.today {
+            Window::Today
+        } else if self.yesterday {
+            Window::Yesterday
+        } else if self.week {
+            Window::Week
+        } else if self.last_week {
+            Window::LastWeek
+        } else if let Some(monday) = self.iso_week {
+            Window::IsoWeek(monday)
+        } else if self.month {
+            Window::Month
+        } else if self.last_month {
+            Window::LastMonth
+        } else if self.quarter {
+            Window::Quarter
+        } else if self.last_quarter {
+            Window::LastQuarter
+        } else if let Some(day) = self.on {
+            Window::Range(day, day.succ_opt().unwrap_or(day))
+        } else if let Some(from) = self.since {
+            match self.until {
+                Some(to) => Window::Range(from, to.succ_opt().unwrap_or(to)),
+                None => Window::Since(from),
+            }
+        } else if let (None, Some(w)) = (self.days, self.default_window) {
+            w
+        } else {
+            Window::Days(self.days())
+        }
+    }
+
+    /// Whether a commit by this author is in: `--author` if given, else
+    /// everyone with `--all`, else just you.
+    fn wants_author(&self, id: &Identity, name: Option<&str>, email: Option<&str>) -> bool {
+        if !self.authors.is_empty() {
+            return self
+                .authors
+                .iter()
+                .any(|p| p.matches(name) || p.matches(email));
+        }
+        self.all || matches_identity(id, name, email)
+    }
+
+    /// "No commits found ..." for an empty window, naming whose commits
+    /// were looked for.
+    fn no_commits(&self) -> String {
+        let window = self.window().describe();
+        if !self.authors.is_empty() {
+            let who: Vec<&str> = self.authors.iter().map(AuthorPattern::as_str).collect();
+            format!("No commits found by {} in {window}", who.join(" or "))
+        } else if self.all {
+            format!("No commits found in {window}")
+        } else {
+            format!("No commits found for your identity in {window} (try --all)")
+        }
+    }
+
+    /// Whose commits count as yours: the profile's identity, else git's.
+    fn identity(&self) -> Identity {
+        self.identity
+            .as_deref()
+            .cloned()
+            .unwrap_or_else(default_identity)
+    }
+}
+
+/// The default view: a commit log across every repo.
+#[derive(Parser, Debug)]
+struct Args {
+    #[command(flatten)]
+    scan: ScanArgs,
+
+    /// Max number of commits to print (across all repos)
+    #[arg(short, long, default_value = "50")]
+    limit: usize,
+
+    /// Raw output for piping (tab-separated)
+    #[arg(short, long)]
+    raw: bool,
+
+    /// With --raw: separate fields with NUL instead of tab, like git's -z
+    #[arg(short = 'z', long = "null", requires = "raw")]
+    null: bool,
+
+    /// Chronological NDJSON event stream (commits, tags, stashes, new branches)
+    #[arg(long, conflicts_with = "raw")]
+    events: bool,
+
+    /// Alfred script filter JSON, with links to each commit on its forge
+    #[arg(long, conflicts_with_all = ["raw", "events"])]
+    alfred: bool,
+
+    /// Show the whole commit message under each commit, not just the summary
+    #[arg(long, conflicts_with = "raw")]
+    full_message: bool,
+
+    /// Show each commit's git notes (`refs/notes/commits`, or `core.notesRef`) under it
+    #[arg(long, conflicts_with = "raw")]
+    notes: bool,
+
+    /// Mark each commit's signature: ✓ good, ✗ bad or unverifiable, - unsigned
+    #[arg(long, conflicts_with_all = ["events", "alfred", "stream", "jsonl", "group_by", "whoami"])]
+    show_signatures: bool,
+
+    /// Print each repo's commits as soon as it's scanned (grouped by repo, not time-sorted)
+    #[arg(long, conflicts_with_all = ["events", "alfred", "dedupe_patches"])]
+    stream: bool,
+
+    /// One JSON object per commit as each repo is scanned, then a summary record
+    #[arg(long, conflicts_with_all = ["raw", "events", "alfred", "stream", "full_message", "new_only", "dedupe_patches"])]
+    jsonl: bool,
+
+    /// After scanning, list repos whose object stores could use `work maintain`
+    #[arg(long)]
+    check_maintenance: bool,
+
+    /// Exit with status 1 if any repo couldn't be scanned
+    #[arg(long)]
+    strict: bool,
+
+    /// Describe the effective query (roots, repos, window, filters) on stderr first
+    #[arg(long)]
+    explain: bool,
+
+    /// Summarize the window's commits per author email domain or per message language, instead of listing them
+    #[arg(long, value_enum, value_name = "KEY", requires = "all", conflicts_with_all = ["events", "alfred", "stream", "jsonl"])]
+    group_by: Option<GroupBy>,
+
+    /// Summarize commits per ticket named in their summaries (PROJ-123, #42), or per match of REGEX (its first group, if any): `--by-ticket='\[(\w+-\d+)\]'`
+    #[arg(
+        long,
+        value_name = "REGEX",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = ticket::DEFAULT_PATTERN,
+        value_parser = ticket::parse_pattern,
+        conflicts_with_all = ["events", "alfred", "stream", "jsonl", "group_by", "whoami", "show_signatures"]
+    )]
+    by_ticket: Option<regex::Regex>,
+
+    /// Email domain that counts as your organization for --group-by domain (repeatable) [default: your user.email's]
+    #[arg(long, value_name = "DOMAIN")]
+    org_domain: Vec<String>,
+
+    /// Which columns to print, in order, in both pretty and raw output
+    #[arg(
+        long,
+        value_enum,
+        value_delimiter = ',',
+        default_value = "time,repo,hash,stats,summary",
+        conflicts_with_all = ["events", "alfred", "stream", "jsonl"]
+    )]
+    columns: Vec<Column>,
+
+    /// Order of the commits; --limit keeps the first ones in this order
+    #[arg(long, value_enum, default_value = "time", conflicts_with_all = ["events", "stream", "jsonl"])]
+    sort: SortKey,
+
+    /// Reverse the --sort order
+    #[arg(long, conflicts_with_all = ["events", "stream", "jsonl"])]
+    reverse: bool,
+
+    /// Screen-reader friendly: one labeled line per commit, no colors or padding
+    #[arg(long, conflicts_with_all = ["raw", "events", "alfred", "stream", "jsonl", "wrap", "group_by"])]
+    plain: bool,
+
+    /// On a terminal too narrow for a commit's line, put its summary on a second, indented line
+    #[arg(long, conflicts_with_all = ["raw", "events", "alfred", "stream", "jsonl"])]
+    wrap: bool,
+
+    /// Fit lines into N columns instead of the terminal's width, cutting long summaries short with …
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u16).range(20..), conflicts_with_all = ["raw", "events", "alfred", "jsonl", "plain"])]
+    max_width: Option<u16>,
+
+    /// Print summaries in full even when lines run past the terminal's width
+    #[arg(long, conflicts_with_all = ["max_width", "raw", "events", "alfred", "jsonl", "plain"])]
+    no_truncate: bool,
+
+    /// With --all: mark and color your own commits, and give your share in the footer
+    #[arg(long, requires = "all", conflicts_with_all = ["raw", "events", "alfred", "stream", "jsonl", "group_by", "by_ticket"])]
+    highlight_me: bool,
+
+    /// List the author identities in the window that look like yours, instead of commits
+    #[arg(long, conflicts_with_all = ["events", "alfred", "stream", "jsonl", "group_by", "new_only"])]
+    whoami: bool,
+
+    /// Only show commits that earlier --new-only runs (of this view) haven't shown
+    #[arg(long, conflicts_with = "stream")]
+    new_only: bool,
+
+    /// With --today: a progress bar toward the daily goal from `[goal]` in the config file
+    #[arg(long, requires = "today", conflicts_with_all = ["raw", "events", "alfred", "stream", "jsonl", "plain", "group_by", "whoami"])]
+    goal_bar: bool,
+
+    /// `[goal]` from the config file.
+    #[arg(skip)]
+    goal: config::Goal,
+
+    /// The `work view` this came from; `--new-only` keeps state per view.
+    #[arg(skip)]
+    view: Option<String>,
+}
+
+impl Args {
+    /// What the scan has to gather for this view's flags.
+    fn prepare_scan(&mut self) {
+        self.scan.detect_language = self.group_by == Some(GroupBy::Language);
+        self.scan.signatures = self.show_signatures;
+    }
+}
+
+/// Lockfiles that routinely produce five-figure diffs nobody wrote by hand.
+const DEFAULT_STAT_EXCLUDES: &[&str] = &[
+    "package-lock.json",
+    "npm-shrinkwrap.json",
+    "yarn.lock",
+    "pnpm-lock.yaml",
+    "bun.lockb",
+    "Cargo.lock",
+    "Gemfile.lock",
+    "composer.lock",
+    "poetry.lock",
+    "uv.lock",
+    "Pipfile.lock",
+    "go.sum",
+    "flake.lock",
+];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Identity {
+    pub name: Option<String>,
+    pub email: Option<String>,
+    /// More addresses that are also you: a profile's `email` list past
+    /// the first.
+    pub other_emails: Vec<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct CommitLine {
+    pub repo: PathBuf,
+    pub time: i64,
+    pub oid: Oid,
+    /// The hash as displayed: `--abbrev` characters, or more if that
+    /// prefix is ambiguous in the repo, or all of it with `--full-hash`.
+    pub short: String,
+    pub author_name: String,
+    pub author_email: String,
+    pub summary: String,
+    /// Everything after the summary paragraph (trailers included), if any.
+    pub body: Option<String>,
+    /// The commit's note in the default notes ref, if it has one.
+    pub note: Option<String>,
+    /// `Key: value` trailers at the end of the message, in order.
+    pub trailers: Vec<(String, String)>,
+    /// Files touched, not counting stat excludes and generated files.
+    pub files: usize,
+    pub insertions: usize,
+    pub deletions: usize,
+    /// LFS pointer files changed, and by how many bytes their objects grew
+    /// (see `DiffStats`).
+    pub lfs_files: usize,
+    pub lfs_bytes: i64,
+    /// No diff stats: they need objects a partial clone doesn't have, or
+    /// `cached_stats_only` kept the scan from diffing. The counts are 0.
+    pub no_stats: bool,
+    /// Every file it touched only changed mode or symlink target (see
+    /// `DiffStats`), counted before `--exclude-mode-changes` drops them.
+    pub mode_only: bool,
+    /// Per-file lines; only kept for `work churn` (`ScanArgs::file_changes`).
+    pub changes: Vec<FileChange>,
+    /// The message's language, when asked for and whatlang could tell.
+    pub lang: Option<whatlang::Lang>,
+    /// Only checked for `--show-signatures`.
+    pub signature: Option<signing::Signature>,
+    /// Other clones/worktrees the same commit was found in (see `dedupe`).
+    pub also_in: Vec<PathBuf>,
+    /// Only computed for `--dedupe-patches`.
+    pub patch_id: Option<Oid>,
+}
+
+#[cfg(test)]
+impl CommitLine {
+    /// A commit with every optional field empty, for module tests.
+    fn stub(repo: &str, time: i64) -> Self {
+        CommitLine {
+            repo: PathBuf::from(repo),
+            time,
+            oid: Oid::zero(),
+            short: "0000000".to_string(),
+            author_name: String::new(),
+            author_email: String::new(),
+            summary: String::new(),
+            body: None,
+            note: None,
+            trailers: Vec::new(),
+            files: 0,
+            insertions: 0,
+            deletions: 0,
+            lfs_files: 0,
+            lfs_bytes: 0,
+            no_stats: false,
+            mode_only: false,
+            changes: Vec::new(),
+            lang: None,
+            signature: None,
+            also_in: Vec::new(),
+            patch_id: None,
+        }
+    }
+}
+
+#[cfg(test)]
+impl Scan {
+    /// A scan of `roots` that found `commits` (newest first) in the repos
+    /// they name, and nothing else, for module tests.
+    fn stub(roots: &[&str], commits: Vec<CommitLine>) -> Self {
+        let mut repos: Vec<PathBuf> = commits.iter().map(|c| c.repo.clone()).collect();
+        repos.sort();
+        repos.dedup();
+        Scan {
+            roots: roots.iter().map(PathBuf::from).collect(),
+            repos,
+            id: Identity {
+                name: None,
+                email: None,
+                other_emails: Vec::new(),
+            },
+            since: 0,
+            until: None,
+            commits,
+            retried: Vec::new(),
+            failed: Vec::new(),
+            unreadable: Vec::new(),
+            unborn: Vec::new(),
+        }
+    }
+}
+
+/// A size change in decimal units, as the forges show LFS sizes:
+/// `+1.2 MB`, `-340 KB`, `+12 B`.
+fn format_bytes(delta: i64) -> String {
+    let sign = if delta < 0 { '-' } else { '+' };
+    let n = delta.unsigned_abs() as f64;
+    match n {
+        n if n >= 1e9 => format!("{sign}{:.1} GB", n / 1e9),
+        n if n >= 1e6 => format!("{sign}{:.1} MB", n / 1e6),
+        n if n >= 1e3 => format!("{sign}{:.0} KB", n / 1e3),
+        n => format!("{sign}{n} B"),
+    }
+}
+
+impl CommitLine {
+    /// `LFS: 2 files, +1.2 MB`, for commits that changed LFS pointers.
+    fn lfs_summary(&self) -> Option<String> {
+        (self.lfs_files > 0).then(|| {
+            format!(
+                "LFS: {} file{}, {}",
+                self.lfs_files,
+                if self.lfs_files == 1 { "" } else { "s" },
+                format_bytes(self.lfs_bytes)
+            )
+        })
+    }
+
+    /// `3f`, `+120`, `-45`, or `?f`, `+?`, `-?` when the stats are unavailable.
+    fn stat_cells(&self) -> [String; 3] {
+        if self.no_stats {
+            return ["?f".into(), "+?".into(), "-?".into()];
+        }
+        [
+            format!("{}f", self.files),
+            format!("+{}", self.insertions),
+            format!("-{}", self.deletions),
+        ]
+    }
+}
+
+/// Non-commit activity recovered from tags and reflogs for `--events`.
+#[derive(Clone, Debug)]
+enum RefEventKind {
+    Tag {
+        name: String,
+        target: Oid,
+        message: String,
+    },
+    Stash {
+        message: String,
+    },
+    BranchCreated {
+        branch: String,
+        from: String,
+    },
+}
+
+#[derive(Clone, Debug)]
+struct RefEvent {
+    repo: PathBuf,
+    time: i64,
+    kind: RefEventKind,
+}
+
+/// Why discovery passed over a directory, for `work repos discover --dry-run`.
+#[derive(Debug, PartialEq, Eq)]
+enum Skip {
+    /// Below `-L`; repos further down are never seen.
+    Depth(usize),
+    /// Symlinked directories aren't followed, so nothing is found twice.
+    Symlink,
+    Unreadable(String),
+    /// Matched by the root's `.workignore` or `ignore` in the config file.
+    Ignored,
+    /// Matched by a name in git's global excludes file.
+    GitIgnored,
+    /// `--skip-hidden`: a directory whose name starts with a dot.
+    Hidden,
+    /// A repo left out by `--repo` / `--exclude-repo`.
+    Excluded,
+    /// `--active-only`: nothing in its git dir changed in the window.
+    Dormant,
+}
+
+impl std::fmt::Display for Skip {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Skip::Depth(max) => write!(f, "deeper than -L {max}"),
+            Skip::Symlink => f.write_str("symlink"),
+            Skip::Unreadable(e) => write!(f, "unreadable: {e}"),
+            Skip::Ignored => f.write_str("ignored by .workignore or the config file"),
+            Skip::GitIgnored => f.write_str("ignored by your global gitignore"),
+            Skip::Hidden => f.write_str("hidden (--skip-hidden)"),
+            Skip::Excluded => f.write_str("excluded by --repo/--exclude-repo"),
+            Skip::Dormant => f.write_str("no git activity in the window (--active-only)"),
+        }
+    }
+}
+
+/// Called with every directory discovery doesn't descend into, and why.
+type SkipHook<'a> = &'a mut dyn FnMut(&Path, Skip);
+
+/// How discovery walks each root.
+struct Walk<'a> {
+    max_depth: usize,
+    ignore: &'a ignore::Ignore,
+    /// Names from the global gitignore, unless `--no-global-ignore`.
+    global: &'a ignore::Ignore,
+    skip_hidden: bool,
+}
+
+/// What the walk's tasks found, gathered from every thread.
+#[derive(Default)]
+struct Found {
+    repos: Mutex<Vec<PathBuf>>,
+    skipped: Mutex<Vec<(PathBuf, Skip)>>,
+}
+
+impl Found {
+    fn skip(&self, dir: &Path, why: Skip) {
+        let mut skipped = self.skipped.lock().unwrap_or_else(|e| e.into_inner());
+        skipped.push((dir.to_path_buf(), why));
+    }
+}
+
+fn find_repos(base: &Path, walk: &Walk, skip: SkipHook) -> Vec<PathBuf> {
+    let found = Found::default();
+    // A task per directory: wide trees are listed in parallel, and a task
+    // only queues its subdirectories and returns, so however deep `-L`
+    // goes, nothing recurses on our stack.
+    rayon::scope(|s| visit(s, walk, &found, base.to_path_buf(), 0));
+
+    let mut skipped = found
+        .skipped
+        .into_inner()
+        .unwrap_or_else(|e| e.into_inner());
+    skipped.sort_by(|a, b| a.0.cmp(&b.0));
+    for (dir, why) in skipped {
+        skip(&dir, why);
+    }
+    let mut repos = found.repos.into_inner().unwrap_or_else(|e| e.into_inner());
+    repos.sort();
+    repos
+}
+
+fn visit<'s>(
+    s: &rayon::Scope<'s>,
+    walk: &'s Walk<'s>,
+    found: &'s Found,
+    dir: PathBuf,
+    depth: usize,
+) {
+    if depth > 0 {
+        if walk.ignore.is_ignored(&dir) {
+            return found.skip(&dir, Skip::Ignored);
+        }
+        if walk.global.is_ignored(&dir) {
+            return found.skip(&dir, Skip::GitIgnored);
+        }
+        let hidden = dir
+            .file_name()
+            .is_some_and(|n| n.as_encoded_bytes().starts_with(b"."));
+        if walk.skip_hidden && hidden {
+            return found.skip(&dir, Skip::Hidden);
+        }
+    }
+    if depth > walk.max_depth {
+        return found.skip(&dir, Skip::Depth(walk.max_depth));
+    }
+    match dir.join(".git").try_exists() {
+        Ok(true) => {
+            found
+                .repos
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .push(dir);
+            return;
+        }
+        Ok(false) => {}
+        Err(e) => return found.skip(&dir, Skip::Unreadable(e.to_string())),
+    }
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) => return found.skip(&dir, Skip::Unreadable(e.to_string())),
+    };
+    for entry in entries {
+        // An entry that can't be read mid-listing (a file system error,
+        // a name too long for the platform) takes its subtree with it.
+        let (path, kind) = match entry.and_then(|e| Ok((e.path(), e.file_type()?))) {
+            Ok(found) => found,
+            Err(e) => {
+                found.skip(&dir, Skip::Unreadable(e.to_string()));
+                continue;
+            }
+        };
+        if kind.is_dir() {
+            s.spawn(move |s| visit(s, walk, found, path, depth + 1));
+        } else if kind.is_symlink() && path.is_dir() {
+            found.skip(&path, Skip::Symlink);
+        }
+    }
+}
+
+/// Work trees of `repo`'s initialized submodules, and of theirs in turn.
+/// Uninitialized ones have nothing checked out to scan and are skipped.
+/// One that's checked out but won't open (another user's, say) is kept,
+/// so its scan fails with the reason instead of it going missing.
+fn submodules(repo: &Path) -> Vec<PathBuf> {
+    // A parent that won't open is scanned itself, and fails there.
+    let Ok(parent) = open_repo(repo) else {
+        return Vec::new();
+    };
+    let Ok(subs) = parent.submodules() else {
+        return Vec::new();
+    };
+    let mut out = Vec::new();
+    for sm in subs {
+        let dir = repo.join(sm.path());
+        if !dir.join(".git").exists() {
+            continue;
+        }
+        out.extend(submodules(&dir));
+        out.push(dir);
+    }
+    out
+}
+
+fn default_identity() -> Identity {
+    let cfg = Config::open_default().ok();
+    let name = cfg.as_ref().and_then(|c| c.get_string("user.name").ok());
+    let email = cfg.as_ref().and_then(|c| c.get_string("user.email").ok());
+    Identity {
+        name,
+        email,
+        other_emails: Vec::new(),
+    }
+}
+
+/// The identity a repo commits under when its own config changes it: a
+/// local `user.email` (a work identity in work clones), or one set by an
+/// `includeIf` for the repo's path. `None` when it's the same as `id`.
+fn repo_identity(repo: &Repository, id: &Identity) -> Option<Identity> {
+    let cfg = repo.config().and_then(|mut c| c.snapshot()).ok()?;
+    let outside = Config::open_default().and_then(|mut c| c.snapshot()).ok()?;
+    own_identity(&cfg, &outside, id)
+}
+
+/// The `user.*` values a repo's config sets that the config outside any
+/// repo doesn't: what its local level or an `includeIf` for its path
+/// changes. Inherited values are left out, so with a `--profile` identity
+/// a repo without its own doesn't bring the global git identity back in.
+fn own_identity(cfg: &Config, outside: &Config, id: &Identity) -> Option<Identity> {
+    let own = |key: &str| {
+        let value = cfg.get_string(key).ok()?;
+        (outside.get_string(key).ok().as_ref() != Some(&value)).then_some(value)
+    };
+    let (name, email) = (own("user.name"), own("user.email"));
+    let same = |mine: &Option<String>, theirs: &Option<String>| mine.is_none() || mine == theirs;
+    if same(&name, &id.name) && same(&email, &id.email) {
+        return None;
+    }
+    Some(Identity {
+        name,
+        email,
+        other_emails: Vec::new(),
+    })
+}
+
+/// Start of the error for a repo that hit `--repo-timeout`. Those aren't
+/// retried: a hung mount would just hang again.
+const TIMED_OUT: &str = "timed out";
+
+/// Start of the error for a repo whose scan was stopped by `Cancel`. Not
+/// retried either.
+const CANCELLED: &str = "cancelled";
+
+/// Start of the "error" for a repo with nothing committed yet. It isn't a
+/// failure, so it's listed apart from them and never retried.
+const UNBORN: &str = "no commits yet";
+
+/// Set by `--trust-all`: skip git's check that repos belong to this user.
+static TRUST_ALL: AtomicBool = AtomicBool::new(false);
+
+/// Turns `--trust-all` on for the rest of the process. Both `main` and
+/// `scan_report_in` call it, so library callers get it too; only the first
+/// call does anything.
+fn trust_all() {
+    static ONCE: Once = Once::new();
+    ONCE.call_once(|| {
+        TRUST_ALL.store(true, Ordering::Relaxed);
+        // SAFETY: libgit2 keeps this in a plain global that every open
+        // reads. It's written once, under `Once`, and only ever to turn the
+        // check off, so an open racing it just sees one value or the other.
+        unsafe {
+            let _ = git2::opts::set_verify_owner_validation(false);
+        }
+    });
+}
+
+/// A `git` invocation that honors `--trust-all` like libgit2 does.
+fn git_command() -> Command {
+    let mut cmd = Command::new("git");
+    if TRUST_ALL.load(Ordering::Relaxed) {
+        cmd.args(["-c", "safe.directory=*"]);
+    }
+    cmd
+}
+
+/// `Repository::open`, but a repo owned by another user (which git refuses
+/// to touch) gets an error that says how to allow it.
+fn open_repo(path: &Path) -> Result<Repository, String> {
+    Repository::open(path).map_err(|e| match e.code() {
+        git2::ErrorCode::Owner => format!(
+            "dubious ownership: owned by another user (trust it with `git config --global --add safe.directory {}`, or pass --trust-all)",
+            path.display()
+        ),
+        _ => e.message().to_string(),
+    })
+}
+
+fn fetch_repo(path: &Path, deadline: Option<Instant>) -> Result<(), String> {
+    // Keep it simple and compatible with whatever auth the user already has.
+    let mut child = git_command()
+        .args(["fetch", "--quiet", "--prune"])
+        .current_dir(path)
+        .spawn()
+        .map_err(|e| format!("cannot run git fetch: {e}"))?;
+    let status = loop {
+        if let Some(status) = child
+            .try_wait()
+            .map_err(|e| format!("git fetch failed: {e}"))?
+        {
+            break status;
+        }
+        if deadline.is_some_and(|d| Instant::now() >= d) {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(format!("{TIMED_OUT} during git fetch"));
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    };
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("git fetch failed ({status})"))
+    }
+}
+
+fn matches_identity(id: &Identity, author_name: Option<&str>, author_email: Option<&str>) -> bool {
+    if id.name.is_none() && id.email.is_none() {
+        // No configured identity; don't accidentally filter everything out.
+        return true;
+    }
+
+    if let Some(got) = author_email
+        && id
+            .email
+            .iter()
+            .chain(&id.other_emails)
+            .any(|want| want.eq_ignore_ascii_case(got))
+    {
+        return true;
+    }
+
+    if let (Some(want), Some(got)) = (id.name.as_deref(), author_name)
+        && want == got
+    {
+        return true;
+    }
+
+    false
+}
+
+/// An `--author` pattern, matched case-insensitively against the author's
+/// name and email. Like `git log --author`, plain text matches anywhere in
+/// either; a glob has to match one of them whole.
+#[derive(Clone, Debug)]
+enum AuthorPattern {
+    Text(String),
+    Glob(glob::Pattern),
+    Regex(regex::Regex),
+}
+
+fn parse_author_pattern(s: &str) -> Result<AuthorPattern, String> {
+    if let Some(re) = s.strip_prefix('/').and_then(|r| r.strip_suffix('/')) {
+        return regex::RegexBuilder::new(re)
+            .case_insensitive(true)
+            .build()
+            .map(AuthorPattern::Regex)
+            .map_err(|e| format!("invalid regex '{re}': {e}"));
+    }
+    if s.contains(['*', '?', '[']) {
+        return parse_glob(s).map(AuthorPattern::Glob);
+    }
+    Ok(AuthorPattern::Text(s.to_lowercase()))
+}
+
+impl AuthorPattern {
+    fn matches(&self, field: Option<&str>) -> bool {
+        let Some(field) = field else {
+            return false;
+        };
+        match self {
+            AuthorPattern::Text(t) => field.to_lowercase().contains(t.as_str()),
+            AuthorPattern::Glob(g) => g.matches_with(
+                field,
+                glob::MatchOptions {
+                    case_sensitive: false,
+                    ..Default::default()
+                },
+            ),
+            AuthorPattern::Regex(r) => r.is_match(field),
+        }
+    }
+
+    fn as_str(&self) -> &str {
+        match self {
+            AuthorPattern::Text(t) => t,
+            AuthorPattern::Glob(g) => g.as_str(),
+            AuthorPattern::Regex(r) => r.as_str(),
+        }
+    }
+}
+
+/// `--no-bots` author patterns, matched case-insensitively against the
+/// author's name and email. `[[]bot[]]` is a literal `[bot]`, which GitHub
+/// puts on every app account.
+const BOTS: &[&str] = &[
+    "*[[]bot[]]*",
+    "dependabot*",
+    "renovate*",
+    "github-actions*",
+    "action@github.com",
+    "greenkeeper*",
+    "snyk-bot*",
+    "pre-commit-ci*",
+    "mergify*",
+    "semantic-release-bot*",
+    "allcontributors*",
+    "imgbot*",
+    "depfu*",
+];
+
+fn is_bot(args: &ScanArgs, author_name: Option<&str>, author_email: Option<&str>) -> bool {
+    static BUILTIN: OnceLock<Vec<glob::Pattern>> = OnceLock::new();
+    let builtin = BUILTIN.get_or_init(|| BOTS.iter().filter_map(|p| parse_glob(p).ok()).collect());
+    let opts = glob::MatchOptions {
+        case_sensitive: false,
+        ..Default::default()
+    };
+    let fields = [author_name, author_email];
+    builtin
+        .iter()
+        .chain(&args.bots)
+        .any(|p| fields.iter().flatten().any(|s| p.matches_with(s, opts)))
+}
+
+/// Turns `--stat-exclude` globs (plus the lockfile defaults) into libgit2
+/// pathspecs. libgit2 takes the first matching spec, so the negated
+/// excludes go first and then `--path`, or a catch-all `*`. Like `.gitignore`, a glob
+/// without a slash matches the basename at any depth.
+fn stat_pathspecs(args: &ScanArgs) -> Vec<String> {
+    let defaults = if args.no_default_stat_excludes {
+        &[][..]
+    } else {
+        DEFAULT_STAT_EXCLUDES
+    };
+
+    let globs = defaults
+        .iter()
+        .copied()
+        .chain(args.stat_exclude.iter().map(String::as_str));
+
+    let mut specs = Vec::new();
+    for glob in globs {
+        let glob = glob.trim_start_matches('/');
+        if glob.is_empty() {
+            continue;
+        }
+        specs.push(format!("!{glob}"));
+        if !glob.contains('/') {
+            specs.push(format!("!*/{glob}"));
+        }
+    }
+
+    if !args.path_filter.is_empty() {
+        specs.extend(args.path_filter.iter().cloned());
+    } else if !specs.is_empty() {
+        specs.push("*".to_string());
+    }
+    specs
+}
+
+/// `--path`: whether the commit changes anything matching `specs`, against
+/// its first parent.
+fn touches(repo: &Repository, commit: &git2::Commit, specs: &[String]) -> bool {
+    let Ok(tree) = commit.tree() else {
+        return false;
+    };
+    let parent_tree = commit.parent(0).ok().and_then(|p| p.tree().ok());
+    let mut opts = git2::DiffOptions::new();
+    for spec in specs {
+        opts.pathspec(spec);
+    }
+    repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), Some(&mut opts))
+        .is_ok_and(|d| d.deltas().len() > 0)
+}
+
+fn is_generated(repo: &Repository, path: &Path) -> bool {
+    matches!(
+        repo.get_attr(
+            path,
+            "linguist-generated",
+            git2::AttrCheckFlags::FILE_THEN_INDEX
+        )
+        .map(git2::AttrValue::from_string),
+        Ok(git2::AttrValue::True)
+    )
+}
+
+/// A blobless or treeless clone (`git clone --filter=...`): objects it
+/// doesn't have are fetched by git on demand, which libgit2 can't do.
+/// Older gits mark one with `extensions.partialClone`, newer ones with
+/// `remote.<name>.promisor`.
+fn is_partial_clone(repo: &Repository) -> bool {
+    let Ok(cfg) = repo.config() else {
+        return false;
+    };
+    if cfg
+        .get_string("extensions.partialclone")
+        .is_ok_and(|r| !r.is_empty())
+    {
+        return true;
+    }
+    let Ok(mut promisors) = cfg.entries(Some(r"remote\..*\.promisor")) else {
+        return false;
+    };
+    let mut found = false;
+    while let Some(Ok(entry)) = promisors.next() {
+        found |= entry.value().is_some_and(|v| v == "true");
+    }
+    found
+}
+
+/// Whether every tree and blob `diff_stats` reads is already here. In a
+/// partial clone it only runs when they are, so nothing is fetched behind
+/// the user's back.
+fn objects_present(repo: &Repository, commit: &git2::Commit, pathspecs: &[String]) -> bool {
+    let present = || -> Option<bool> {
+        let tree = commit.tree().ok()?;
+        let parent = match commit.parent(0) {
+            Ok(p) => Some(p.tree().ok()?),
+            Err(_) => None,
+        };
+        let mut opts = git2::DiffOptions::new();
+        for spec in pathspecs {
+            opts.pathspec(spec);
+        }
+        let diff = repo
+            .diff_tree_to_tree(parent.as_ref(), Some(&tree), Some(&mut opts))
+            .ok()?;
+        let odb = repo.odb().ok()?;
+        let here = |f: git2::DiffFile| f.id().is_zero() || odb.exists(f.id());
+        Some(
+            diff.deltas()
+                .all(|d| here(d.old_file()) && here(d.new_file())),
+        )
+    };
+    present().unwrap_or(false)
+}
+
+/// `--allow-lazy-fetch`: `git diff --numstat` against the first parent
+/// fetches whatever objects the partial clone is missing, after which
+/// `diff_stats` has everything it reads.
+fn fetch_objects(repo: &Repository, commit: &git2::Commit, pathspecs: &[String]) -> bool {
+    let dir = repo.workdir().unwrap_or(repo.path());
+    let mut cmd = git_command();
+    cmd.current_dir(dir)
+        .args(["diff", "--numstat", "--no-renames", "--no-color"]);
+    match commit.parent_id(0) {
+        Ok(parent) => cmd.arg(parent.to_string()),
+        // The empty tree, for a root commit.
+        Err(_) => cmd.arg("4b825dc642cb6eb9a060e54bf8d69288fbee4904"),
+    };
+    cmd.arg(commit.id().to_string()).arg("--");
+    // libgit2's `!glob` is git's `:(exclude)glob`.
+    cmd.args(pathspecs.iter().map(|p| match p.strip_prefix('!') {
+        Some(glob) => format!(":(exclude){glob}"),
+        None => p.clone(),
+    }));
+    cmd.stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|s| s.success())
+}
+
+/// One file in a commit, for `work churn`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileChange {
+    pub path: String,
+    pub insertions: usize,
+    pub deletions: usize,
+    /// Only the file's mode or symlink target changed.
+    pub mode_only: bool,
+}
+
+/// What one commit changed, as `diff_stats` counts it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct DiffStats {
+    files: usize,
+    insertions: usize,
+    deletions: usize,
+    /// Files that are Git LFS pointers on either side. Their lines aren't
+    /// counted: a pointer always changes by the same 2-3 lines.
+    lfs_files: usize,
+    /// Change in the size of the LFS objects those pointers refer to.
+    lfs_bytes: i64,
+    /// Files whose only change is their mode (`chmod +x`, or bits flapping
+    /// between checkouts with `core.fileMode` off) or, for symlinks, their
+    /// target, and the lines of those: included in the counts above.
+    mode_files: usize,
+    mode_insertions: usize,
+    mode_deletions: usize,
+}
+
+impl DiffStats {
+    /// Every file the commit touched is a mode or symlink change.
+    fn mode_only(&self) -> bool {
+        self.files > 0 && self.mode_files == self.files
+    }
+
+    /// `--exclude-mode-changes`: the counts without those files.
+    fn without_mode_changes(self) -> Self {
+        DiffStats {
+            files: self.files - self.mode_files,
+            insertions: self.insertions - self.mode_insertions,
+            deletions: self.deletions - self.mode_deletions,
+            mode_files: 0,
+            mode_insertions: 0,
+            mode_deletions: 0,
+            ..self
+        }
+    }
+}
+
+/// The object size a Git LFS pointer file records, if the blob is one.
+/// Pointers are tiny, so anything over 1 KB isn't read at all.
+fn lfs_pointer_size(odb: &git2::Odb, id: Oid) -> Option<i64> {
+    if id.is_zero() || odb.read_header(id).ok()?.0 > 1024 {
+        return None;
+    }
+    let obj = odb.read(id).ok()?;
+    let text = std::str::from_utf8(obj.data()).ok()?;
+    if !text.starts_with("version https://git-lfs.github.com/spec/") {
+        return None;
+    }
+    text.lines()
+        .find_map(|l| l.strip_prefix("size ")?.trim().parse().ok())
+}
+
+/// Files changed, lines added and lines removed, against the first parent,
+/// so a merge counts everything its branch brought in. With `changes`,
+/// also each counted file's own lines. With `ignore_whitespace`, a file
+/// whose changes are all whitespace still counts, with no lines.
+fn diff_stats(
+    repo: &Repository,
+    commit: &git2::Commit,
+    pathspecs: &[String],
+    ignore_whitespace: bool,
+    mut changes: Option<&mut Vec<FileChange>>,
+) -> DiffStats {
+    let mut stats = DiffStats::default();
+    let commit_tree = match commit.tree() {
+        Ok(t) => t,
+        Err(_) => return stats,
+    };
+
+    let parent_tree = if commit.parent_count() >= 1 {
+        commit.parent(0).ok().and_then(|p| p.tree().ok())
+    } else {
+        None
+    };
+
+    let mut opts = git2::DiffOptions::new();
+    for spec in pathspecs {
+        opts.pathspec(spec);
+    }
+    opts.ignore_whitespace(ignore_whitespace);
+
+    let diff =
+        match repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit_tree), Some(&mut opts)) {
+            Ok(d) => d,
+            Err(_) => return stats,
+        };
+    let odb = repo.odb().ok();
+
+    // Tally per file so anything marked `linguist-generated` in
+    // .gitattributes can be left out, same as the forges do.
+    for (idx, delta) in diff.deltas().enumerate() {
+        let path = delta.new_file().path().or_else(|| delta.old_file().path());
+        if path.is_some_and(|p| is_generated(repo, p)) {
+            continue;
+        }
+        stats.files += 1;
+        let (old, new) = (delta.old_file(), delta.new_file());
+        let mode_only = delta.status() == git2::Delta::Modified
+            && ((old.mode() != new.mode() && old.id() == new.id())
+                || (old.mode() == git2::FileMode::Link && new.mode() == git2::FileMode::Link));
+        let mut lines = (0, 0);
+        let lfs = odb.as_ref().and_then(|odb| {
+            let old = lfs_pointer_size(odb, delta.old_file().id());
+            let new = lfs_pointer_size(odb, delta.new_file().id());
+            (old.is_some() || new.is_some()).then(|| new.unwrap_or(0) - old.unwrap_or(0))
+        });
+        if let Some(bytes) = lfs {
+            stats.lfs_files += 1;
+            stats.lfs_bytes += bytes;
+        } else if let Ok(Some(patch)) = git2::Patch::from_diff(&diff, idx)
+            && let Ok((_, ins, del)) = patch.line_stats()
+        {
+            lines = (ins, del);
+            stats.insertions += ins;
+            stats.deletions += del;
+        }
+        if mode_only {
+            stats.mode_files += 1;
+            stats.mode_insertions += lines.0;
+            stats.mode_deletions += lines.1;
+        }
+        if let (Some(changes), Some(path)) = (changes.as_deref_mut(), path) {
+            changes.push(FileChange {
+                path: path.to_string_lossy().into_owned(),
+                insertions: lines.0,
+                deletions: lines.1,
+                mode_only,
+            });
+        }
+    }
+
+    stats
+}
+
+/// `git patch-id` equivalent for the commit's change against its first
+/// parent. Empty changes get no id, or every empty commit would collapse
+/// into one.
+fn patch_id(repo: &Repository, commit: &git2::Commit) -> Option<Oid> {
+    let tree = commit.tree().ok()?;
+    let parent_tree = commit.parent(0).ok().and_then(|p| p.tree().ok());
+    let diff = repo
+        .diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)
+        .ok()?;
+    if diff.deltas().len() == 0 {
+        return None;
+    }
+    diff.patchid(None).ok()
+}
+
+/// `git log -S` for one commit: whether some file has a different number
+/// of `needle`s after it than before, against the first parent. Binary
+/// files are skipped.
+fn pickaxe(repo: &Repository, commit: &git2::Commit, needle: &str) -> bool {
+    let Ok(tree) = commit.tree() else {
+        return false;
+    };
+    let parent_tree = commit.parent(0).ok().and_then(|p| p.tree().ok());
+    let Ok(diff) = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None) else {
+        return false;
+    };
+    let count = |file: git2::DiffFile| {
+        if file.id().is_zero() {
+            return 0;
+        }
+        match repo.find_blob(file.id()) {
+            Ok(blob) if !blob.is_binary() => String::from_utf8_lossy(blob.content())
+                .matches(needle)
+                .count(),
+            _ => 0,
+        }
+    };
+    diff.deltas()
+        .any(|d| count(d.old_file()) != count(d.new_file()))
+}
+
+/// The shortest prefix of `oid`, at least `len` characters, that names no
+/// other object in the repo. Git does the same, so the result always works
+/// with `git show`.
+fn abbreviate(repo: &Repository, oid: Oid, len: usize) -> String {
+    let hex = oid.to_string();
+    let Ok(odb) = repo.odb() else {
+        return hex[..len].to_string();
+    };
+    for n in len..hex.len() {
+        match Oid::from_str(&hex[..n]).and_then(|prefix| odb.exists_prefix(prefix, n)) {
+            Err(e) if e.code() == git2::ErrorCode::Ambiguous => continue,
+            _ => return hex[..n].to_string(),
+        }
+    }
+    hex
+}
+
+/// Walks one repo's history for the window. A walk that breaks partway
+/// (a corrupt or missing object) keeps the commits it got before the break,
+/// alongside the error.
+fn collect_commits(
+    repo_path: &Path,
+    since: i64,
+    until: Option<i64>,
+    id: &Identity,
+    args: &ScanArgs,
+) -> (Vec<CommitLine>, Option<String>) {
+    let mut out = Vec::new();
+    let err = walk_commits(repo_path, since, until, id, args, &mut out).err();
+    if args.signatures {
+        signing::verify(repo_path, &mut out);
+    }
+    (out, err)
+}
+
+fn walk_commits(
+    repo_path: &Path,
+    since: i64,
+    until: Option<i64>,
+    id: &Identity,
+    args: &ScanArgs,
+    out: &mut Vec<CommitLine>,
+) -> Result<(), String> {
+    let repo = open_repo(repo_path)?;
+
+    // A detached HEAD is walked like a branch; only an unborn one (a fresh
+    // `git init`, or HEAD naming a branch that doesn't exist) has no history.
+    let head = match repo.head() {
+        Ok(h) => h,
+        Err(e)
+            if matches!(
+                e.code(),
+                git2::ErrorCode::UnbornBranch | git2::ErrorCode::NotFound
+            ) =>
+        {
+            let branch = repo.find_reference("HEAD").ok().and_then(|h| {
+                h.symbolic_target()
+                    .map(|t| t.trim_start_matches("refs/heads/").to_string())
+            });
+            return Err(match branch {
+                Some(b) => format!("{UNBORN} (HEAD is an unborn `{b}`)"),
+                None => UNBORN.to_string(),
+            });
+        }
+        Err(e) => return Err(e.message().to_string()),
+    };
+
+    let oid = match head.target() {
+        Some(oid) => oid,
+        None => return Ok(()),
+    };
+
+    let mut walk = repo.revwalk().map_err(|e| e.message().to_string())?;
+    walk.push(oid).map_err(|e| e.message().to_string())?;
+    let _ = walk.set_sorting(git2::Sort::TIME);
+    if args.first_parent {
+        walk.simplify_first_parent()
+            .map_err(|e| e.message().to_string())?;
+    }
+
+    // Commits made under the repo's own identity are yours too.
+    let local_id = repo_identity(&repo, id);
+    let pathspecs = stat_pathspecs(args);
+    let partial = is_partial_clone(&repo);
+    let mut cache = args
+        .stat_cache
+        .as_deref()
+        .map(|dir| statcache::StatCache::open(dir, &repo, &pathspecs, args.ignore_whitespace));
+    let abbrev = if args.full_hash {
+        40
+    } else {
+        usize::from(args.abbrev)
+    };
+    // Most repos have no notes; skip the per-commit lookup for them.
+    let notes_ref = repo
+        .note_default_ref()
+        .ok()
+        .filter(|r| repo.find_reference(r).is_ok());
+    for item in walk {
+        if args.cancel.is_cancelled() {
+            return Err(CANCELLED.to_string());
+        }
+        let item = item.map_err(|e| format!("history walk stopped: {}", e.message()))?;
+        let commit = repo
+            .find_commit(item)
+            .map_err(|e| format!("cannot read commit {item}: {}", e.message()))?;
+
+        // The walk is in committer order, and an author date is almost never
+        // later than its committer date, so stopping on the committer date
+        // is safe either way.
+        let committed = commit.time().seconds();
+        if committed < since {
+            break;
+        }
+        let t = match args.date {
+            DateKind::Committer => committed,
+            DateKind::Author => commit.author().when().seconds(),
+        };
+        // Implausible dates are never in a window, rather than in every
+        // open-ended one (2106) or silently before all of them (1901).
+        if t < since || !window::is_plausible(t) {
+            continue;
+        }
+
+        if let Some(until) = until
+            && t >= until
+        {
+            continue;
+        }
+
+        if !(args.merges || args.first_parent) && commit.parent_count() > 1 {
+            continue;
+        }
+
+        let author = commit.author();
+        if !args.wants_author(id, author.name(), author.email())
+            && !local_id
+                .as_ref()
+                .is_some_and(|local| args.wants_author(local, author.name(), author.email()))
+        {
+            continue;
+        }
+
+        if args.no_bots {
+            let author = commit.author();
+            if is_bot(args, author.name(), author.email()) {
+                continue;
+            }
+        }
+
+        if !args.path_filter.is_empty() && !touches(&repo, &commit, &args.path_filter) {
+            continue;
+        }
+        if let Some(needle) = &args.find_code
+            && !pickaxe(&repo, &commit, needle)
+        {
+            continue;
+        }
+        let trailers = trailers(commit.message().unwrap_or(""));
+        if !has_trailers(&trailers, &args.trailer_filters) {
+            continue;
+        }
+        let lang =
+            if args.detect_language || !args.langs.is_empty() || !args.exclude_langs.is_empty() {
+                language::detect(commit.summary().unwrap_or(""), commit.body())
+            } else {
+                None
+            };
+        if !language::wanted(lang, &args.langs, &args.exclude_langs) {
+            continue;
+        }
+
+        // The cache only has totals, so `work churn` always diffs.
+        let mut changes = Vec::new();
+        let cached = match args.file_changes {
+            true => None,
+            false => cache.as_mut().and_then(|c| c.get(commit.id())),
+        };
+        let stats = cached.or_else(|| {
+            if args.cached_stats_only && args.min_changes.is_none() && args.max_changes.is_none() {
+                return None;
+            }
+            let complete = !partial
+                || objects_present(&repo, &commit, &pathspecs)
+                || (args.allow_lazy_fetch && fetch_objects(&repo, &commit, &pathspecs));
+            let wanted = args.file_changes.then_some(&mut changes);
+            let stats = complete
+                .then(|| diff_stats(&repo, &commit, &pathspecs, args.ignore_whitespace, wanted));
+            if let (Some(stats), Some(c)) = (stats, cache.as_mut()) {
+                c.insert(commit.id(), stats);
+            }
+            stats
+        });
+        let stats_or_zero = stats.unwrap_or_default();
+        let mode_only = stats_or_zero.mode_only();
+        let DiffStats {
+            files,
+            insertions,
+            deletions,
+            lfs_files,
+            lfs_bytes,
+            ..
+        } = if args.exclude_mode_changes {
+            changes.retain(|f| !f.mode_only);
+            stats_or_zero.without_mode_changes()
+        } else {
+            stats_or_zero
+        };
+        // Commits without stats (objects missing from a partial clone)
+        // have no size to judge, so they stay, marked as such.
+        if stats.is_some() && !args.wants_size(insertions.saturating_add(deletions)) {
+            continue;
+        }
+
+        let summary = commit
+            .summary()
+            .unwrap_or("(no message)")
+            .trim()
+            .to_string();
+
+        let body = commit
+            .body()
+            .map(|b| b.trim_end().to_string())
+            .filter(|b| !b.is_empty());
+
+        let note = notes_ref
+            .as_deref()
+            .and_then(|r| repo.find_note(Some(r), commit.id()).ok())
+            .and_then(|n| n.message().map(|m| m.trim_end().to_string()))
+            .filter(|n| !n.is_empty());
+
+        let author = commit.author();
+        out.push(CommitLine {
+            repo: repo_path.to_path_buf(),
+            time: t,
+            oid: commit.id(),
+            short: abbreviate(&repo, commit.id(), abbrev),
+            author_name: author.name().unwrap_or_default().to_string(),
+            author_email: author.email().unwrap_or_default().to_string(),
+            summary,
+            body,
+            note,
+            trailers,
+            files,
+            insertions,
+            deletions,
+            lfs_files,
+            lfs_bytes,
+            no_stats: stats.is_none(),
+            mode_only,
+            changes,
+            lang,
+            signature: None,
+            also_in: Vec::new(),
+            patch_id: if args.dedupe_patches {
+                patch_id(&repo, &commit)
+            } else {
+                None
+            },
+        });
+    }
+
+    Ok(())
+}
+
+/// One attempt at a repo: fetch (with `--remote`), then walk. A failed fetch
+/// still yields the local commits, but is reported so the repo gets retried.
+fn scan_repo(
+    repo_path: &Path,
+    since: i64,
+    until: Option<i64>,
+    id: &Identity,
+    args: &ScanArgs,
+) -> (Vec<CommitLine>, Option<String>) {
+    let deadline = args
+        .repo_timeout
+        .map(|secs| Instant::now() + Duration::from_secs(secs));
+    let fetched = if args.remote {
+        fetch_repo(repo_path, deadline)
+    } else {
+        Ok(())
+    };
+    let (commits, err) = match deadline {
+        None => collect_commits(repo_path, since, until, id, args),
+        Some(deadline) => collect_commits_until(deadline, repo_path, since, until, id, args),
+    };
+    (commits, err.or(fetched.err()))
+}
+
+/// `collect_commits` on its own thread, abandoned if it's still running at
+/// `deadline`. libgit2 can't be interrupted mid-read, so a walk stuck on a
+/// dead network mount is left behind rather than stopped; it goes away
+/// with the process.
+fn collect_commits_until(
+    deadline: Instant,
+    repo_path: &Path,
+    since: i64,
+    until: Option<i64>,
+    id: &Identity,
+    args: &ScanArgs,
+) -> (Vec<CommitLine>, Option<String>) {
+    let secs = args.repo_timeout.unwrap_or_default();
+    let (tx, rx) = std::sync::mpsc::channel();
+    let (path, id, args) = (repo_path.to_path_buf(), id.clone(), args.clone());
+    std::thread::spawn(move || {
+        let _ = tx.send(collect_commits(&path, since, until, &id, &args));
+    });
+    let left = deadline.saturating_duration_since(Instant::now());
+    rx.recv_timeout(left)
+        .unwrap_or_else(|_| (Vec::new(), Some(format!("{TIMED_OUT} after {secs}s"))))
+}
+
+fn collect_ref_events(
+    repo_path: &Path,
+    since: i64,
+    until: Option<i64>,
+    id: &Identity,
+    args: &ScanArgs,
+) -> Result<Vec<RefEvent>, String> {
+    let repo = open_repo(repo_path)?;
+
+    let in_window = |t: i64| window::is_plausible(t) && t >= since && until.is_none_or(|u| t < u);
+    let local_id = repo_identity(&repo, id);
+    let is_mine = |sig: &git2::Signature| {
+        (args.wants_author(id, sig.name(), sig.email())
+            || local_id
+                .as_ref()
+                .is_some_and(|l| args.wants_author(l, sig.name(), sig.email())))
+            && !(args.no_bots && is_bot(args, sig.name(), sig.email()))
+    };
+
+    let mut out = Vec::new();
+    let mut push = |time: i64, kind: RefEventKind| {
+        out.push(RefEvent {
+            repo: repo_path.to_path_buf(),
+            time,
+            kind,
+        });
+    };
+
+    // Only annotated tags record when they were made.
+    let mut tag_oids = Vec::new();
+    let _ = repo.tag_foreach(|oid, _| {
+        tag_oids.push(oid);
+        true
+    });
+    for oid in tag_oids {
+        let Ok(tag) = repo.find_tag(oid) else {
+            continue;
+        };
+        let Some(tagger) = tag.tagger() else {
+            continue;
+        };
+        let t = tagger.when().seconds();
+        if !in_window(t) || !is_mine(&tagger) {
+            continue;
+        }
+        push(
+            t,
+            RefEventKind::Tag {
+                name: tag.name().unwrap_or("(invalid utf-8)").to_string(),
+                target: tag.target_id(),
+                message: tag
+                    .message()
+                    .unwrap_or("")
+                    .lines()
+                    .next()
+                    .unwrap_or("")
+                    .trim()
+                    .to_string(),
+            },
+        );
+    }
+
+    if let Ok(reflog) = repo.reflog("refs/stash") {
+        for entry in reflog.iter() {
+            let sig = entry.committer();
+            let t = sig.when().seconds();
+            if !in_window(t) || !is_mine(&sig) {
+                continue;
+            }
+            push(
+                t,
+                RefEventKind::Stash {
+                    message: entry.message().unwrap_or("").to_string(),
+                },
+            );
+        }
+    }
+
+    // A branch's oldest reflog entry says where it was created from, as long
+    // as the reflog hasn't been expired.
+    if let Ok(branches) = repo.branches(Some(git2::BranchType::Local)) {
+        for (branch, _) in branches.flatten() {
+            let Some(refname) = branch.get().name() else {
+                continue;
+            };
+            let Ok(reflog) = repo.reflog(refname) else {
+                continue;
+            };
+            let Some(first) = reflog.iter().next_back() else {
+                continue;
+            };
+            let Some(from) = first
+                .message()
+                .and_then(|m| m.strip_prefix("branch: Created from "))
+            else {
+                continue;
+            };
+            let sig = first.committer();
+            let t = sig.when().seconds();
+            if !in_window(t) || !is_mine(&sig) {
+                continue;
+            }
+            push(
+                t,
+                RefEventKind::BranchCreated {
+                    branch: refname.trim_start_matches("refs/heads/").to_string(),
+                    from: from.to_string(),
+                },
+            );
+        }
+    }
+
+    Ok(out)
+}
+
+/// Printed in place of a timestamp outside `window::PLAUSIBLE`.
+const INVALID_DATE: &str = "(invalid date)";
+
+fn format_time_rfc3339(ts: i64) -> String {
+    zoned(ts)
+        .map(|d| d.to_rfc3339())
+        .unwrap_or_else(|| INVALID_DATE.to_string())
+}
+
+/// `2026-03-02 14:30`, or `(invalid date)` padded to the same width so
+/// columns still line up.
+fn format_time_local(ts: i64) -> String {
+    zoned(ts)
+        .map(|d| d.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|| format!("{INVALID_DATE:<16}"))
+}
+
+/// Just the `14:30` of `format_time_local`, for views whose window is a
+/// single day and so implies the date.
+fn format_clock_local(ts: i64) -> String {
+    zoned(ts)
+        .map(|d| d.format("%H:%M").to_string())
+        .unwrap_or_else(|| INVALID_DATE.to_string())
+}
+
+/// A `--trailer` filter. Keys compare case-insensitively, as git does;
+/// values by substring, so `Co-authored-by=me@corp.io` matches
+/// `Me <me@corp.io>`.
+#[derive(Clone, Debug)]
+struct TrailerFilter {
+    key: String,
+    value: Option<String>,
+}
+
+fn parse_trailer_filter(s: &str) -> Result<TrailerFilter, String> {
+    let (key, value) = match s.split_once('=') {
+        Some((k, v)) => (k, Some(v.trim().to_lowercase())),
+        None => (s, None),
+    };
+    let key = key.trim().trim_end_matches(':').to_lowercase();
+    if key.is_empty() {
+        return Err(format!(
+            "'{s}' has no trailer key (e.g. Co-authored-by=you@corp.io)"
+        ));
+    }
+    Ok(TrailerFilter { key, value })
+}
+
+/// The message's trailers, via libgit2's parser (the same rules as
+/// `git interpret-trailers`).
+fn trailers(message: &str) -> Vec<(String, String)> {
+    git2::message_trailers_strs(message)
+        .map(|t| {
+            t.iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn has_trailers(trailers: &[(String, String)], filters: &[TrailerFilter]) -> bool {
+    filters.iter().all(|f| {
+        trailers.iter().any(|(k, v)| {
+            k.eq_ignore_ascii_case(&f.key)
+                && f.value
+                    .as_ref()
+                    .is_none_or(|want| v.to_lowercase().contains(want))
+        })
+    })
+}
+
+fn parse_glob(s: &str) -> Result<glob::Pattern, String> {
+    glob::Pattern::new(s).map_err(|e| format!("invalid glob '{s}': {e}"))
+}
+
+/// `--repo` / `--exclude-repo`: a repo is scanned if it matches any `--repo`
+/// glob (or none were given) and no `--exclude-repo` glob.
+fn repo_selected(args: &ScanArgs, name: &str) -> bool {
+    (args.repo_globs.is_empty() || args.repo_globs.iter().any(|g| g.matches(name)))
+        && !args.exclude_repo_globs.iter().any(|g| g.matches(name))
+}
+
+fn parse_tz(s: &str) -> Result<chrono_tz::Tz, String> {
+    s.parse()
+        .map_err(|_| format!("unknown timezone '{s}' (expected an IANA name like Europe/Berlin)"))
+}
+
+/// `--tz`: the zone windows and printed times use instead of the system's.
+static ZONE: OnceLock<chrono_tz::Tz> = OnceLock::new();
+
+/// `ts` on the wall clock of the `--tz` zone (or the system's), if it's a
+/// plausible commit time at all.
+fn zoned(ts: i64) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+    use chrono::TimeZone;
+    if !window::is_plausible(ts) {
+        return None;
+    }
+    match ZONE.get() {
+        Some(tz) => tz.timestamp_opt(ts, 0).single().map(|d| d.fixed_offset()),
+        None => chrono::Local
+            .timestamp_opt(ts, 0)
+            .single()
+            .map(|d| d.fixed_offset()),
+    }
+}
+
+fn resolve_window(w: Window) -> Result<(i64, Option<i64>), String> {
+    match ZONE.get() {
+        Some(tz) => w.resolve(now(), tz),
+        None => w.resolve(now(), &chrono::Local),
+    }
+}
+
+fn local_midnight(date: chrono::NaiveDate) -> i64 {
+    match ZONE.get() {
+        Some(tz) => window::midnight(tz, date),
+        None => window::midnight(&chrono::Local, date),
+    }
+}
+
+/// Whether pretty output uses ANSI colors.
+static COLOR: AtomicBool = AtomicBool::new(true);
+
+/// The clock `--deterministic` pins to `$WORK_NOW`.
+static FIXED_NOW: OnceLock<chrono::DateTime<chrono::FixedOffset>> = OnceLock::new();
+
+fn now() -> chrono::DateTime<chrono::Utc> {
+    FIXED_NOW
+        .get()
+        .map(|t| t.to_utc())
+        .unwrap_or_else(chrono::Utc::now)
+}
+
+/// Columns on screen: `$COLUMNS` if set, else the terminal stdout is on.
+/// `None` when output goes to a file or pipe.
+fn terminal_width() -> Option<usize> {
+    if let Some(cols) = std::env::var("COLUMNS").ok().and_then(|c| c.parse().ok()) {
+        return Some(cols);
+    }
+    // SAFETY: TIOCGWINSZ only writes a `winsize` into the struct it's given.
+    let mut ws: libc::winsize = unsafe { std::mem::zeroed() };
+    let ok = unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut ws) } == 0;
+    (ok && ws.ws_col > 0).then_some(ws.ws_col as usize)
+}
+
+/// Columns `s` takes up on screen, not counting `paint`'s escapes. Wide
+/// characters (CJK, most emoji) take two.
+fn visible_width(s: &str) -> usize {
+    let mut width = 0;
+    let mut in_escape = false;
+    for ch in s.chars() {
+        match (in_escape, ch) {
+            (false, '\x1b') => in_escape = true,
+            (true, 'm') => in_escape = false,
+            (true, _) => {}
+            (false, _) => width += ch.width().unwrap_or(0),
+        }
+    }
+    width
+}
+
+/// `s` padded with spaces to `width` columns on screen; `{:<width$}`
+/// counts chars, which leaves wide names short.
+fn pad_visible(s: &str, width: usize) -> String {
+    format!("{s}{}", " ".repeat(width.saturating_sub(visible_width(s))))
+}
+
+/// `s` cut to at most `width` columns on screen, ending in `…` if anything
+/// was dropped. Escapes are kept, and colors reset after the cut so they
+/// don't bleed into the next line. A wide character that would straddle
+/// the edge is dropped whole.
+fn truncate_visible(s: &str, width: usize) -> String {
+    if visible_width(s) <= width {
+        return s.to_string();
+    }
+    let room = width.saturating_sub(1);
+    let (mut out, mut used) = (String::new(), 0);
+    let (mut in_escape, mut painted) = (false, false);
+    for ch in s.chars() {
+        match (in_escape, ch) {
+            (false, '\x1b') => (in_escape, painted) = (true, true),
+            (true, 'm') => in_escape = false,
+            (true, _) => {}
+            (false, _) => {
+                let w = ch.width().unwrap_or(0);
+                if used + w > room {
+                    break;
+                }
+                used += w;
+            }
+        }
+        out.push(ch);
+    }
+    out.truncate(out.trim_end().len());
+    out.push('…');
+    if painted {
+        out.push_str("\x1b[0m");
+    }
+    out
+}
+
+/// `--wrap`: `text` broken at spaces into lines of at most `width` visible
+/// characters, each starting with `indent`. A word longer than a line gets
+/// one to itself.
+fn wrap_words(text: &str, indent: &str, width: usize) -> Vec<String> {
+    let room = width.saturating_sub(indent.len()).max(1);
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split(' ') {
+        if !line.is_empty() && visible_width(&line) + 1 + visible_width(word) > room {
+            lines.push(format!("{indent}{line}"));
+            line.clear();
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    lines.push(format!("{indent}{line}"));
+    lines
+}
+
+/// Wraps `s` in an ANSI SGR sequence, or leaves it bare when colors are off.
+fn paint(code: &str, s: impl std::fmt::Display) -> String {
+    if COLOR.load(Ordering::Relaxed) {
+        format!("\x1b[{code}m{s}\x1b[0m")
+    } else {
+        s.to_string()
+    }
+}
+
+/// `--deterministic`: everything that varies between machines and runs is
+/// pinned, so the same repos always print the same bytes.
+fn make_deterministic(tz: Option<chrono_tz::Tz>) -> Result<(), String> {
+    let raw = std::env::var("WORK_NOW")
+        .map_err(|_| "work: --deterministic needs WORK_NOW (e.g. 2026-03-02T12:00:00Z)")?;
+    let fixed = chrono::DateTime::parse_from_rfc3339(&raw)
+        .map_err(|e| format!("work: invalid WORK_NOW '{raw}': {e}"))?;
+    let _ = FIXED_NOW.set(fixed);
+    COLOR.store(false, Ordering::Relaxed);
+    // UTC unless `--tz` says otherwise; either way not the machine's zone.
+    let _ = ZONE.set(tz.unwrap_or(chrono_tz::UTC));
+    Ok(())
+}
+
+/// Collapses commits that were found in more than one clone or worktree of
+/// the same repo. The first repo (in path order) keeps the commit and the
+/// rest are listed in `also_in`. Expects `commits` sorted by time, which
+/// keeps equal OIDs in repo order because the sort is stable.
+fn dedupe(commits: Vec<CommitLine>) -> Vec<CommitLine> {
+    let mut seen: HashMap<Oid, usize> = HashMap::new();
+    let mut out: Vec<CommitLine> = Vec::with_capacity(commits.len());
+    for c in commits {
+        if let Some(&i) = seen.get(&c.oid) {
+            out[i].also_in.push(c.repo);
+            continue;
+        }
+        seen.insert(c.oid, out.len());
+        out.push(c);
+    }
+    out
+}
+
+/// Drops every commit whose patch-id was already seen on an older commit,
+/// so a change cherry-picked onto release branches (or re-applied in a
+/// fork) counts once, at the time it was first made. Expects newest-first.
+fn dedupe_patches(commits: Vec<CommitLine>) -> Vec<CommitLine> {
+    let mut seen = std::collections::HashSet::new();
+    let mut keep: Vec<CommitLine> = commits
+        .into_iter()
+        .rev()
+        .filter(|c| c.patch_id.is_none_or(|id| seen.insert(id)))
+        .collect();
+    keep.reverse();
+    keep
+}
+
+/// The log's view of a `ScanReport` (see `Scan::from_report`): every
+/// repo's commits as one list, and the repos sorted by how their scan
+/// went. It's what a `Renderer` is given.
+pub struct Scan {
+    /// Canonicalized, longest first so nested roots win in `rel`.
+    pub roots: Vec<PathBuf>,
+    pub repos: Vec<PathBuf>,
+    pub id: Identity,
+    pub since: i64,
+    pub until: Option<i64>,
+    /// Newest first.
+    pub commits: Vec<CommitLine>,
+    /// Repos that failed once and then succeeded, with the first error.
+    pub retried: Vec<(PathBuf, String)>,
+    /// Repos that failed twice; their commits may be missing or partial.
+    pub failed: Vec<(PathBuf, String)>,
+    /// Directories discovery couldn't list, as in `ScanReport::errors`.
+    pub unreadable: Vec<(PathBuf, String)>,
+    /// Repos with nothing committed yet, with what HEAD names.
+    pub unborn: Vec<(PathBuf, String)>,
+}
+
+/// A repo's path relative to the root it was found under. `roots` must be
+/// longest first, as in `Scan::roots`.
+fn rel_to_roots<'a>(roots: &[PathBuf], repo: &'a Path) -> &'a Path {
+    roots
+        .iter()
+        .find_map(|root| repo.strip_prefix(root).ok())
+        .unwrap_or(repo)
+}
+
+/// Like `rel_to_roots`, but never empty: a scan root that is itself a repo
+/// is named after its directory.
+fn repo_name(roots: &[PathBuf], repo: &Path) -> String {
+    let rel = rel_to_roots(roots, repo);
+    if rel.as_os_str().is_empty() {
+        repo.file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| repo.display().to_string())
+    } else {
+        rel.display().to_string()
+    }
+}
+
+impl Scan {
+    /// The report as the log's one list: newest first, with a commit found
+    /// in several clones (and with `--dedupe-patches`, cherry-picks) shown
+    /// once.
+    pub fn from_report(report: ScanReport, args: &ScanArgs) -> Scan {
+        let mut scan = Scan {
+            roots: report.roots,
+            repos: Vec::with_capacity(report.repos.len()),
+            id: report.id,
+            since: report.since,
+            until: report.until,
+            commits: Vec::new(),
+            retried: Vec::new(),
+            failed: Vec::new(),
+            unreadable: report.errors,
+            unborn: Vec::new(),
+        };
+        for r in report.repos {
+            match r.outcome {
+                Outcome::Scanned => {}
+                Outcome::Retried(e) => scan.retried.push((r.path.clone(), e)),
+                Outcome::Failed(e) => scan.failed.push((r.path.clone(), e)),
+                Outcome::Unborn(e) => scan.unborn.push((r.path.clone(), e)),
+                Outcome::Cancelled => scan.failed.push((r.path.clone(), CANCELLED.to_string())),
+            }
+            scan.repos.push(r.path);
+            scan.commits.extend(r.commits);
+        }
+
+        // Ties broken by repo and hash so equal timestamps print in a stable order.
+        scan.commits.sort_by(|a, b| {
+            b.time
+                .cmp(&a.time)
+                .then_with(|| a.repo.cmp(&b.repo))
+                .then_with(|| a.oid.cmp(&b.oid))
+        });
+        if !args.no_dedupe {
+            scan.commits = dedupe(std::mem::take(&mut scan.commits));
+        }
+        if args.dedupe_patches {
+            scan.commits = dedupe_patches(std::mem::take(&mut scan.commits));
+        }
+        scan
+    }
+
+    /// `repo` relative to the root it was found under.
+    pub fn rel<'a>(&self, repo: &'a Path) -> &'a Path {
+        rel_to_roots(&self.roots, repo)
+    }
+
+    /// `repo` as the log names it: like `rel`, but a root that is itself a
+    /// repo is named after its directory.
+    pub fn name(&self, repo: &Path) -> String {
+        repo_name(&self.roots, repo)
+    }
+}
+
+fn scan(args: &ScanArgs) -> Result<Scan, String> {
+    scan_with(args, None)
+}
+
+/// What `discover` found.
+struct Discovery {
+    /// Canonical, longest first.
+    roots: Vec<PathBuf>,
+    repos: Vec<PathBuf>,
+    /// Directories that couldn't be listed, with the error. Any repos under
+    /// them are missing from `repos`.
+    unreadable: Vec<(PathBuf, String)>,
+}
+
+/// Resolves the scan roots (canonical, longest first) and finds the repos
+/// under them that pass the `--repo` / `--exclude-repo` filters.
+fn discover(args: &ScanArgs) -> Result<Discovery, String> {
+    let mut unreadable = Vec::new();
+    let found = discover_traced(args, &mut |p, why| {
+        if let Skip::Unreadable(e) = why {
+            unreadable.push((p.to_path_buf(), e));
+        }
+    });
+    unreadable.sort();
+    let (roots, mut repos) = match found {
+        Ok(found) => found,
+        Err(e) if unreadable.is_empty() => return Err(e),
+        Err(e) => {
+            return Err(format!(
+                "{e} ({} directories couldn't be read; `work repos discover --dry-run` lists them)",
+                unreadable.len()
+            ));
+        }
+    };
+    if args.pick {
+        repos = pick_repos(&roots, repos)?;
+    }
+    Ok(Discovery {
+        roots,
+        repos,
+        unreadable,
+    })
+}
+
+/// `--pick`: hands the repo names to `fzf --multi` and keeps what comes back.
+/// Lines are `index\tname` so two repos with the same name stay apart.
+fn pick_repos(roots: &[PathBuf], repos: Vec<PathBuf>) -> Result<Vec<PathBuf>, String> {
+    let mut child = Command::new("fzf")
+        .args([
+            "--multi",
+            "--delimiter=\t",
+            "--with-nth=2",
+            "--prompt=repos> ",
+            "--header=TAB to select, Enter to scan",
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => "work: --pick needs fzf on your PATH".to_string(),
+            _ => format!("work: cannot run fzf: {e}"),
+        })?;
+    let list: String = repos
+        .iter()
+        .enumerate()
+        .map(|(i, r)| format!("{i}\t{}\n", repo_name(roots, r)))
+        .collect();
+    if let Some(mut stdin) = child.stdin.take() {
+        // fzf may exit (Esc) before reading everything; that's not an error.
+        let _ = stdin.write_all(list.as_bytes());
+    }
+    let out = child
+        .wait_with_output()
+        .map_err(|e| format!("work: fzf failed: {e}"))?;
+
+    let picked: HashSet<usize> = String::from_utf8_lossy(&out.stdout)
+        .lines()
+        .filter_map(|l| l.split_once('\t')?.0.parse().ok())
+        .collect();
+    if picked.is_empty() {
+        return Err("work: no repos picked".to_string());
+    }
+    Ok(repos
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| picked.contains(i))
+        .map(|(_, r)| r)
+        .collect())
+}
+
+/// `discover`, reporting what it passes over to `skip` along the way.
+fn discover_traced(
+    args: &ScanArgs,
+    skip: SkipHook,
+) -> Result<(Vec<PathBuf>, Vec<PathBuf>), String> {
+    if args.paths.is_empty() {
+        return Err(match config::path() {
+            Some(p) => format!(
+                "work: no PATH given (pass one, or set `paths` in {})",
+                p.display()
+            ),
+            None => "work: no PATH given".to_string(),
+        });
+    }
+    let mut roots = Vec::new();
+    for path in &args.paths {
+        let root = path
+            .canonicalize()
+            .map_err(|_| format!("work: cannot access '{}'", path.display()))?;
+        roots.push(root);
+    }
+    roots.sort_by(|a, b| {
+        b.components()
+            .count()
+            .cmp(&a.components().count())
+            .then_with(|| a.cmp(b))
+    });
+    roots.dedup();
+
+    // Overlapping roots (~/code and ~/code/apps) would find repos twice.
+    let mut repos: Vec<PathBuf> = Vec::new();
+    let global = match args.no_global_ignore {
+        true => ignore::Ignore::default(),
+        false => ignore::Ignore::global(),
+    };
+    for root in &roots {
+        let ignore = ignore::Ignore::load(root, &args.ignore)?;
+        let walk = Walk {
+            max_depth: args.depth,
+            ignore: &ignore,
+            global: &global,
+            skip_hidden: args.skip_hidden,
+        };
+        repos.extend(find_repos(root, &walk, skip));
+    }
+    if args.submodules {
+        let subs: Vec<PathBuf> = repos.par_iter().flat_map_iter(|r| submodules(r)).collect();
+        repos.extend(subs);
+    }
+    repos.sort();
+    repos.dedup();
+    if repos.is_empty() {
+        let roots = roots
+            .iter()
+            .map(|r| r.display().to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        return Err(format!("No git repos found in {roots}"));
+    }
+
+    repos.retain(|r| {
+        let keep = repo_selected(args, &repo_name(&roots, r));
+        if !keep {
+            skip(r, Skip::Excluded);
+        }
+        keep
+    });
+    if repos.is_empty() {
+        return Err("No git repos match the --repo/--exclude-repo filters".to_string());
+    }
+
+    if args.active_only {
+        let (since, _) = resolve_window(args.window())?;
+        let since = UNIX_EPOCH + Duration::from_secs(since.max(0) as u64);
+        let active: Vec<bool> = repos.par_iter().map(|r| touched_since(r, since)).collect();
+        let mut active = active.into_iter();
+        repos.retain(|r| {
+            let keep = active.next().unwrap_or(true);
+            if !keep {
+                skip(r, Skip::Dormant);
+            }
+            keep
+        });
+        if repos.is_empty() {
+            return Err(format!(
+                "No repos have git activity in {} (--active-only)",
+                args.window().describe()
+            ));
+        }
+    }
+
+    Ok((roots, repos))
+}
+
+/// `--active-only`: whether anything git rewrites on a commit, checkout,
+/// fetch or ref update has been modified since `since`. Only stats a few
+/// files, so dormant clones are never opened. When in doubt (no such
+/// files, an unreadable git dir) the repo counts as active.
+fn touched_since(repo: &Path, since: SystemTime) -> bool {
+    let dot_git = repo.join(".git");
+    // Worktrees and submodules have a `.git` file pointing at the real one.
+    let git_dir = match fs::read_to_string(&dot_git) {
+        Ok(text) => match text.trim().strip_prefix("gitdir:") {
+            Some(dir) => repo.join(dir.trim()),
+            None => return true,
+        },
+        Err(_) => dot_git,
+    };
+    let mut newest = None;
+    for file in [
+        "HEAD",
+        "index",
+        "logs/HEAD",
+        "packed-refs",
+        "FETCH_HEAD",
+        "refs/heads",
+    ] {
+        if let Ok(t) = fs::metadata(git_dir.join(file)).and_then(|m| m.modified()) {
+            newest = newest.max(Some(t));
+        }
+    }
+    newest.is_none_or(|t| t >= since)
+}
+
+fn scan_with(args: &ScanArgs, on_event: Option<EventHook>) -> Result<Scan, String> {
+    scan_in(args, args.window(), on_event)
+}
+
+/// `scan_with` over `window` instead of the one the flags ask for, for
+/// views that look at two.
+fn scan_in(args: &ScanArgs, window: Window, on_event: Option<EventHook>) -> Result<Scan, String> {
+    if let (Some(min), Some(max)) = (args.min_changes, args.max_changes)
+        && min > max
+    {
+        return Err(format!(
+            "work: --min-changes {min} is more than --max-changes {max}, so nothing could match"
+        ));
+    }
+    let started = Instant::now();
+    let cache_before = (
+        statcache::HITS.load(Ordering::Relaxed),
+        statcache::MISSES.load(Ordering::Relaxed),
+    );
+    let scan = Scan::from_report(scanreport::scan_report_in(args, window, on_event)?, args);
+
+    if args.record_usage {
+        usage::record(&usage::Run {
+            time: now().timestamp(),
+            repos: scan.repos.len(),
+            commits: scan.commits.len(),
+            millis: started.elapsed().as_millis() as u64,
+            depth: args.depth,
+            deepest: scan
+                .repos
+                .iter()
+                .map(|r| scan.rel(r).components().count())
+                .max()
+                .unwrap_or(0),
+            cache: args.stat_cache.is_some(),
+            cache_hits: statcache::HITS.load(Ordering::Relaxed) - cache_before.0,
+            cache_misses: statcache::MISSES.load(Ordering::Relaxed) - cache_before.1,
+            threads: rayon::current_num_threads(),
+        });
+    }
+
+    Ok(scan)
+}
+
+/// Tells the user (on stderr, to keep stdout parseable) about repos that
+/// needed a second attempt or never scanned cleanly. Printed after the
+/// output, where it isn't scrolled away.
+fn report_warnings(scan: &Scan) {
+    let list = |repos: &[(PathBuf, String)]| {
+        repos
+            .iter()
+            .map(|(r, e)| format!("  {}: {e}", scan.name(r)))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    let no_stats = scan.commits.iter().filter(|c| c.no_stats).count();
+    if !scan.retried.is_empty()
+        || !scan.failed.is_empty()
+        || !scan.unreadable.is_empty()
+        || !scan.unborn.is_empty()
+        || no_stats > 0
+    {
+        eprintln!();
+    }
+    if !scan.retried.is_empty() {
+        eprintln!(
+            "work: retried {} repo(s) after a transient error:\n{}",
+            scan.retried.len(),
+            list(&scan.retried)
+        );
+    }
+    if !scan.failed.is_empty() {
+        eprintln!(
+            "work: {} repo(s) failed twice and may be missing commits:\n{}",
+            scan.failed.len(),
+            list(&scan.failed)
+        );
+    }
+    if !scan.unborn.is_empty() {
+        eprintln!(
+            "work: {} repo(s) have no commits yet:\n{}",
+            scan.unborn.len(),
+            list(&scan.unborn)
+        );
+    }
+    if no_stats > 0 {
+        eprintln!(
+            "work: {no_stats} commit(s) in partial clones show ? for stats: the objects aren't here (--allow-lazy-fetch fetches them)"
+        );
+    }
+    report_unreadable(&scan.unreadable);
+}
+
+/// Repos that wouldn't open, with why.
+type Unopened = Vec<(PathBuf, String)>;
+
+/// Repos a command couldn't open, on stderr after its output, so one
+/// missing from it (another user's, say) isn't a mystery.
+fn report_unopened(roots: &[PathBuf], repos: &[(PathBuf, String)]) {
+    if repos.is_empty() {
+        return;
+    }
+    let list = repos
+        .iter()
+        .map(|(r, e)| format!("  {}: {e}", repo_name(roots, r)))
+        .collect::<Vec<_>>()
+        .join("\n");
+    eprintln!("work: cannot open {} repo(s):\n{list}", repos.len());
+}
+
+/// Directories discovery had to skip, on stderr, so a repo missing from
+/// the output isn't a mystery.
+fn report_unreadable(dirs: &[(PathBuf, String)]) {
+    if dirs.is_empty() {
+        return;
+    }
+    let list = dirs
+        .iter()
+        .map(|(d, e)| format!("  {}: {e}", d.display()))
+        .collect::<Vec<_>>()
+        .join("\n");
+    eprintln!(
+        "work: skipped {} unreadable director(ies); repos under them weren't scanned:\n{list}",
+        dirs.len()
+    );
+}
+
+/// `--full-message`: the body under its commit line, indented, with a blank
+/// line after so consecutive messages don't run together.
+fn write_body(out: &mut dyn std::io::Write, c: &CommitLine, indent: &str) -> std::io::Result<()> {
+    let Some(body) = &c.body else {
+        return Ok(());
+    };
+    for line in body.lines() {
+        if line.is_empty() {
+            writeln!(out)?;
+        } else {
+            writeln!(out, "{indent}{line}")?;
+        }
+    }
+    writeln!(out)
+}
+
+/// `--notes`: the commit's git note under its line, labeled the way
+/// `git log` does it.
+fn write_note(out: &mut dyn std::io::Write, c: &CommitLine, indent: &str) -> std::io::Result<()> {
+    let Some(note) = &c.note else {
+        return Ok(());
+    };
+    writeln!(out, "{indent}{}", paint("33", "Notes:"))?;
+    for line in note.lines() {
+        writeln!(out, "{indent}    {line}")?;
+    }
+    writeln!(out)
+}
+
+#[derive(Default)]
+struct StreamState {
+    shown: usize,
+    seen: HashSet<Oid>,
+    insertions: usize,
+    deletions: usize,
+}
+
+/// `--stream`: prints each repo's commits as soon as its scan finishes,
+/// grouped under the repo name, instead of one globally sorted list at the
+/// end. `--limit` caps the total; clones are deduped first come, first
+/// served.
+fn run_stream(args: &Args) -> Result<(), String> {
+    let state = Mutex::new(StreamState::default());
+    let width = args.max_width.map(usize::from).or_else(terminal_width);
+
+    let print_repo = |e: Event| {
+        explain_on_found(args, &e);
+        let Event::Finished(repo) = e else {
+            return;
+        };
+        // Hold the lock while printing so groups don't interleave.
+        let mut st = state.lock().unwrap_or_else(|e| e.into_inner());
+        let mut group = Vec::new();
+        for c in &repo.commits {
+            if st.shown + group.len() >= args.limit {
+                break;
+            }
+            if !args.scan.no_dedupe && !st.seen.insert(c.oid) {
+                continue;
+            }
+            group.push(c);
+        }
+        if group.is_empty() {
+            return;
+        }
+
+        let name = &repo.name;
+        let files_width = group
+            .iter()
+            .map(|c| c.files.to_string().len())
+            .max()
+            .unwrap_or(1);
+        let ins_width = group
+            .iter()
+            .map(|c| c.insertions.to_string().len())
+            .max()
+            .unwrap_or(1);
+        let del_width = group
+            .iter()
+            .map(|c| c.deletions.to_string().len())
+            .max()
+            .unwrap_or(1);
+
+        if !args.raw {
+            println!("{}", paint("1", name));
+        }
+        for c in &group {
+            let t = format_time_local(c.time);
+            let short = &c.short;
+            if args.raw {
+                let [files, plus, minus] = c.stat_cells();
+                let summary = raw_field(&c.summary, args.null);
+                let fields = [
+                    &t,
+                    &raw_field(name, args.null),
+                    short,
+                    &files,
+                    &plus,
+                    &minus,
+                    &summary,
+                ];
+                println!(
+                    "{}",
+                    fields
+                        .map(String::as_str)
+                        .join(if args.null { "\0" } else { "\t" })
+                );
+            } else {
+                let [files, plus, minus] = c.stat_cells();
+                let meta = format!(
+                    "  {t}  {}  {files:>fw$} {} {}  ",
+                    paint("2", short),
+                    paint("32", format!("{plus:>iw$}", iw = ins_width + 1)),
+                    paint("31", format!("{minus:>dw$}", dw = del_width + 1)),
+                    fw = files_width + 1,
+                );
+                let summary = match width {
+                    Some(w) if !args.no_truncate => {
+                        truncate_visible(&c.summary, w.saturating_sub(visible_width(&meta)).max(10))
+                    }
+                    _ => c.summary.clone(),
+                };
+                println!("{meta}{summary}");
+                if args.full_message {
+                    let _ = write_body(&mut std::io::stdout(), c, "      ");
+                }
+                if args.notes {
+                    let _ = write_note(&mut std::io::stdout(), c, "      ");
+                }
+            }
+            st.insertions = st.insertions.saturating_add(c.insertions);
+            st.deletions = st.deletions.saturating_add(c.deletions);
+        }
+        st.shown += group.len();
+    };
+
+    // The output is its own progress, and would fight the status line.
+    let quiet = ScanArgs {
+        quiet: true,
+        ..args.scan.clone()
+    };
+    let scan = scan_with(&quiet, Some(&print_repo))?;
+    let st = state.into_inner().unwrap_or_else(|e| e.into_inner());
+    let printed = print_stream_summary(args, &st);
+    finish(args, &scan, printed)
+}
+
+/// `--jsonl`: like `--stream`, but each commit is a JSON line and nothing is
+/// held back for sorting, so a pipeline sees commits while the scan is
+/// still running. Ends with `scan-error` records and one `summary` record.
+/// Like `--events`, it's everything in the window: `--limit` doesn't apply.
+fn run_jsonl(args: &Args) -> Result<(), String> {
+    let scan = write_jsonl(args, &mut std::io::stdout())?;
+    finish(args, &scan, Ok(()))
+}
+
+/// `--jsonl`'s records: each repo's commits once its scan is over (after
+/// any retry, so a repo is never printed twice), then the repos that
+/// failed and the summary.
+fn write_jsonl(args: &Args, out: &mut (dyn std::io::Write + Send)) -> Result<Scan, String> {
+    let state = Mutex::new((StreamState::default(), out));
+    let print_repo = |e: Event| {
+        explain_on_found(args, &e);
+        let Event::Finished(repo) = e else {
+            return;
+        };
+        let rel = |_: &Path| repo.name.clone();
+        let mut guard = state.lock().unwrap_or_else(|e| e.into_inner());
+        let (st, out) = &mut *guard;
+        for c in &repo.commits {
+            if !args.scan.no_dedupe && !st.seen.insert(c.oid) {
+                continue;
+            }
+            let _ = writeln!(out, "{}", events::commit_json(c, &rel));
+            st.shown += 1;
+            st.insertions = st.insertions.saturating_add(c.insertions);
+            st.deletions = st.deletions.saturating_add(c.deletions);
+        }
+        let _ = out.flush();
+    };
+
+    let quiet = ScanArgs {
+        quiet: true,
+        ..args.scan.clone()
+    };
+    let scan = scan_with(&quiet, Some(&print_repo))?;
+    let (st, out) = state.into_inner().unwrap_or_else(|e| e.into_inner());
+    let write = |out: &mut dyn std::io::Write, record: serde_json::Value| {
+        writeln!(out, "{record}").map_err(|e| format!("work: cannot write output: {e}"))
+    };
+    for (r, e) in &scan.failed {
+        write(out, events::scan_error_json(&scan, r, e))?;
+    }
+    let summary = events::summary_json(&args.scan, &scan, st.shown, st.insertions, st.deletions);
+    write(out, summary)?;
+    Ok(scan)
+}
+
+fn print_stream_summary(args: &Args, st: &StreamState) -> Result<(), String> {
+    if st.shown == 0 {
+        return Err(args.scan.no_commits());
+    }
+
+    if !args.raw {
+        println!(
+            "\n{} commits shown ({})",
+            st.shown,
+            args.scan.window().label()
+        );
+        println!(
+            "Total LoC: {} {}",
+            paint("32", format!("+{}", st.insertions)),
+            paint("31", format!("-{}", st.deletions))
+        );
+    }
+    Ok(())
+}
+
+/// `--explain`: the query as it's actually run, after the command line,
+/// config file and defaults have been merged, in one paragraph.
+fn explain(args: &Args, plan: &Plan) -> String {
+    let s = &args.scan;
+    let roots = plan
+        .roots
+        .iter()
+        .map(|r| r.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut out = format!(
+        "Scanning {} repo(s) found up to {} level(s) below {roots}",
+        plan.repos.len(),
+        s.depth
+    );
+    if !s.repo_globs.is_empty() || !s.exclude_repo_globs.is_empty() {
+        out.push_str(" that pass --repo/--exclude-repo");
+    }
+    if s.submodules {
+        out.push_str(", submodules included");
+    }
+
+    let zone = ZONE.get().map_or("local time", |tz| tz.name());
+    let at = |ts: i64| {
+        let utc = chrono::DateTime::from_timestamp(ts, 0)
+            .map(|d| d.format("%Y-%m-%d %H:%M UTC").to_string())
+            .unwrap_or_else(|| INVALID_DATE.to_string());
+        format!("{} {zone} ({utc})", format_time_local(ts))
+    };
+    let _ = write!(
+        out,
+        ". Window: {}, from {} until {}",
+        s.window().label(),
+        at(plan.since),
+        plan.until.map_or("now".to_string(), at)
+    );
+
+    let who = match (&plan.id.name, &plan.id.email) {
+        _ if !s.authors.is_empty() => {
+            let who: Vec<&str> = s.authors.iter().map(AuthorPattern::as_str).collect();
+            format!("by authors matching {} (--author)", who.join(" or "))
+        }
+        _ if s.all => "by anyone (--all)".to_string(),
+        (None, None) => "by anyone (no git identity is configured)".to_string(),
+        (Some(n), Some(e)) => format!("authored as {e} or {n}"),
+        (Some(n), None) => format!("authored as {n}"),
+        (None, Some(e)) => format!("authored as {e}"),
+    };
+    let date = match s.date {
+        DateKind::Committer => "committer",
+        DateKind::Author => "author",
+    };
+    let merges = if s.merges { "included" } else { "skipped" };
+    let _ = write!(out, ", by {date} date. Commits {who}; merges {merges}");
+    if !s.no_dedupe {
+        out.push_str("; copies in other clones collapsed");
+    }
+    if s.dedupe_patches {
+        out.push_str("; cherry-picks collapsed by patch-id");
+    }
+    if s.no_bots {
+        out.push_str("; bots skipped");
+    }
+    if !s.path_filter.is_empty() {
+        let _ = write!(out, "; only commits touching {}", s.path_filter.join(", "));
+    }
+    if let Some(needle) = &s.find_code {
+        let _ = write!(out, "; only commits that add or remove {needle:?}");
+    }
+    if args.new_only {
+        out.push_str("; only commits earlier --new-only runs haven't shown");
+    }
+    if s.remote {
+        out.push_str(". Remotes are fetched first");
+    }
+    if let Some(secs) = s.repo_timeout {
+        let _ = write!(out, ". A repo is given up on after {secs}s");
+    }
+    if args.events || args.jsonl {
+        let flag = if args.events { "--events" } else { "--jsonl" };
+        let _ = write!(out, ". Every commit is printed ({flag} ignores --limit).");
+    } else if args.group_by.is_some() || args.by_ticket.is_some() {
+        out.push_str(". Every commit is counted.");
+    } else {
+        let _ = write!(out, ". At most {} commits are shown.", args.limit);
+    }
+    out
+}
+
+/// `--explain` as a scan hook: the query on stderr once discovery is done,
+/// before any repo is scanned, whatever the output mode.
+fn explain_on_found(args: &Args, e: &Event) {
+    if let Event::Found(plan) = e
+        && args.explain
+    {
+        eprintln!("{}\n", explain(args, plan));
+    }
+}
+
+/// What every log view does after printing: warnings and maintenance hints
+/// on stderr, then the exit status (`--strict` fails on unscanned repos).
+fn finish(args: &Args, scan: &Scan, printed: Result<(), String>) -> Result<(), String> {
+    report_warnings(scan);
+    if args.check_maintenance {
+        maintain::report_maintenance(scan);
+    }
+    printed?;
+    strict_status(args.strict, scan)
+}
+
+/// `--strict`'s exit status: an error if any repo couldn't be scanned.
+fn strict_status(strict: bool, scan: &Scan) -> Result<(), String> {
+    if strict && !scan.failed.is_empty() {
+        return Err(format!(
+            "work: {} repo(s) could not be scanned (--strict)",
+            scan.failed.len()
+        ));
+    }
+    Ok(())
+}
+
+/// What a subcommand that writes the scan somewhere else (a report, the
+/// database, a time tracker) does after writing: the warnings on stderr,
+/// so what it wrote isn't silently missing repos, then `--strict`.
+fn finish_export(strict: bool, scan: &Scan, written: Result<(), String>) -> Result<(), String> {
+    report_warnings(scan);
+    written?;
+    strict_status(strict, scan)
+}
+
+fn run(mut args: Args) -> Result<(), String> {
+    args.prepare_scan();
+    if args.stream {
+        return run_stream(&args);
+    }
+    if args.jsonl {
+        return run_jsonl(&args);
+    }
+    if args.plain {
+        COLOR.store(false, Ordering::Relaxed);
+    }
+    if args.whoami {
+        return whoami::whoami(&args);
+    }
+
+    let mut scan = scan_with(&args.scan, Some(&|e: Event| explain_on_found(&args, &e)))?;
+    let mut seen = if args.new_only {
+        Some(seen::Seen::load(seen::path(args.view.as_deref())?)?)
+    } else {
+        None
+    };
+    if let Some(seen) = &seen {
+        scan.commits.retain(|c| !seen.contains(&c.oid));
+    }
+    sort_commits(&mut scan.commits, args.sort, args.reverse);
+    let printed = print_log(&args, &scan);
+    if let Some(seen) = &mut seen {
+        // What --limit cut off hasn't been shown yet, so it's still new.
+        let shown = if args.events || args.group_by.is_some() || args.by_ticket.is_some() {
+            scan.commits.len()
+        } else {
+            args.limit
+        };
+        seen.record(scan.commits.iter().take(shown).map(|c| c.oid));
+        seen.save()?;
+    }
+    finish(&args, &scan, printed)
+}
+
+/// A `--raw` field. A tab or line break inside it would throw off the
+/// columns, so they become spaces; with `-z` NUL separates them instead, so
+/// tabs stay as they are.
+fn raw_field(s: &str, null: bool) -> String {
+    s.chars()
+        .map(|ch| match ch {
+            '\t' if !null => ' ',
+            '\n' | '\r' | '\0' => ' ',
+            _ => ch,
+        })
+        .collect()
+}
+
+/// `--sort` / `--reverse`. Scans come back newest first, and the sort is
+/// stable, so ties stay in time order.
+fn sort_commits(commits: &mut [CommitLine], key: SortKey, reverse: bool) {
+    match key {
+        SortKey::Time => {}
+        SortKey::Repo => commits.sort_by(|a, b| a.repo.cmp(&b.repo)),
+        SortKey::Size => {
+            commits.sort_by_key(|c| std::cmp::Reverse(c.insertions.saturating_add(c.deletions)))
+        }
+    }
+    if reverse {
+        commits.reverse();
+    }
+}
+
+fn print_log(args: &Args, scan: &Scan) -> Result<(), String> {
+    let all: Vec<&CommitLine> = scan.commits.iter().collect();
+    if args.events {
+        return render::print(&events::Events { args }, scan, &all);
+    }
+
+    // Summaries count every commit, not just the `--limit` a list shows.
+    let summary: Option<Box<dyn render::Renderer>> = match (args.group_by, &args.by_ticket) {
+        (Some(GroupBy::Domain), _) => Some(Box::new(domains::Domains { args })),
+        (Some(GroupBy::Language), _) => Some(Box::new(language::Languages { args })),
+        (None, Some(pattern)) => Some(Box::new(ticket::Tickets { args, pattern })),
+        (None, None) => None,
+    };
+    if let Some(summary) = summary {
+        if all.is_empty() {
+            return Err(format!(
+                "No commits found in {}",
+                args.scan.window().describe()
+            ));
+        }
+        return render::print(summary.as_ref(), scan, &all);
+    }
+
+    let commits = scan.commits.iter().take(args.limit).collect::<Vec<_>>();
+
+    if args.alfred {
+        // Alfred shows an empty list fine; an error would just be swallowed.
+        return render::print(&alfred::Alfred, scan, &commits);
+    }
+
+    if args.goal_bar && args.goal == config::Goal::default() {
+        return Err("work: --goal-bar needs a goal: set commits and/or lines under [goal] in the config file (see `work config path`)".into());
+    }
+    if commits.is_empty() {
+        if args.goal_bar {
+            let _ = write_goal_bars(&mut std::io::stdout(), &args.goal, &scan.commits);
+        }
+        return Err(if args.new_only {
+            let window = args.scan.window().describe();
+            format!("No new commits in {window} since the last --new-only run")
+        } else {
+            args.scan.no_commits()
+        });
+    }
+
+    if args.highlight_me && scan.id.name.is_none() && scan.id.email.is_none() {
+        return Err(
+            "work: --highlight-me needs user.name or user.email to tell your commits apart".into(),
+        );
+    }
+    let renderer: Box<dyn render::Renderer> = if args.plain {
+        Box::new(plain::Plain { args })
+    } else if args.raw {
+        Box::new(raw::Raw { args })
+    } else {
+        Box::new(pretty::Pretty { args })
+    };
+    render::print(renderer.as_ref(), scan, &commits)
+}
+
+/// Good, bad and unsigned commits, for the `--show-signatures` footer.
+fn signature_counts(commits: &[&CommitLine]) -> [usize; 3] {
+    let mut counts = [0; 3];
+    for c in commits {
+        match c.signature {
+            Some(signing::Signature::Good) => counts[0] += 1,
+            Some(signing::Signature::Bad) => counts[1] += 1,
+            Some(signing::Signature::Unsigned) => counts[2] += 1,
+            None => {}
+        }
+    }
+    counts
+}
+
+/// `--goal-bar`: 20 cells filled in proportion to `done` out of `goal`,
+/// yellow on the way and green once it's met.
+fn goal_bar(done: usize, goal: usize) -> String {
+    const CELLS: usize = 20;
+    let filled = (done.min(goal) * CELLS).checked_div(goal).unwrap_or(CELLS);
+    let bar = format!("{}{}", "█".repeat(filled), "░".repeat(CELLS - filled));
+    paint(if done >= goal { "32" } else { "33" }, bar)
+}
+
+/// A bar per goal in `[goal]`, counting every commit in the window rather
+/// than just the `--limit` shown.
+fn write_goal_bars(
+    out: &mut dyn std::io::Write,
+    goal: &config::Goal,
+    commits: &[CommitLine],
+) -> std::io::Result<()> {
+    let lines = commits.iter().fold(0usize, |n, c| {
+        n.saturating_add(c.insertions).saturating_add(c.deletions)
+    });
+    for (done, target, unit) in [
+        (commits.len(), goal.commits, "commits"),
+        (lines, goal.lines, "lines"),
+    ] {
+        let Some(target) = target else {
+            continue;
+        };
+        let met = if done >= target { " ✓" } else { "" };
+        writeln!(
+            out,
+            "Goal {}  {done}/{target} {unit}{met}",
+            goal_bar(done, target)
+        )?;
+    }
+    Ok(())
+}
+
+impl Cmd {
+    fn scan_mut(&mut self) -> Option<&mut ScanArgs> {
+        match self {
+            Cmd::Sync {
+                target: SyncTarget::Activitywatch(aw),
+            } => Some(&mut aw.scan),
+            Cmd::Sync {
+                target: SyncTarget::Timewarrior(tw),
+            } => Some(&mut tw.scan),
+            Cmd::Db {
+                action: db::DbAction::Sync(d),
+            } => Some(&mut d.scan),
+            Cmd::Report(r) => Some(&mut r.scan),
+            Cmd::Github(g) => Some(&mut g.scan),
+            Cmd::Menubar(m) => Some(&mut m.scan),
+            Cmd::Waybar(w) => Some(&mut w.scan),
+            Cmd::Maintain(m) => Some(&mut m.scan),
+            Cmd::Repos(r) => Some(r.scan_mut()),
+            Cmd::Open(o) => Some(&mut o.scan),
+            Cmd::Show(s) => Some(&mut s.scan),
+            #[cfg(feature = "gui")]
+            Cmd::Gui(g) => Some(&mut g.scan),
+            Cmd::Feed(f) => Some(&mut f.scan),
+            Cmd::Compare(c) => Some(&mut c.scan),
+            Cmd::Releases(r) => Some(&mut r.scan),
+            Cmd::Punchcard(p) => Some(&mut p.scan),
+            Cmd::Churn(c) => Some(&mut c.scan),
+            Cmd::Export(e) => Some(&mut e.scan),
+            Cmd::Watch(w) => Some(&mut w.log.scan),
+            Cmd::Db {
+                action: db::DbAction::Query(_),
+            }
+            | Cmd::View { .. }
+            | Cmd::Config { .. }
+            | Cmd::Bench(_)
+            | Cmd::Stats(_)
+            | Cmd::Completions { .. } => None,
+        }
+    }
+}
+
+impl Cli {
+    fn scan_mut(&mut self) -> Option<&mut ScanArgs> {
+        match self.command.as_mut() {
+            Some(cmd) => cmd.scan_mut(),
+            None => Some(&mut self.args.scan),
+        }
+    }
+}
+
+/// Fills in whatever the command line left out from the config file.
+/// Command-line values always win.
+fn apply_config(cli: &mut Cli, mut cfg: config::Config) -> Result<(), String> {
+    cli.tz = cli.tz.or(cfg.tz);
+    let profile = match &cli.profile {
+        Some(name) => Some(cfg.profiles.remove(name).ok_or_else(|| {
+            let known: Vec<&str> = cfg.profiles.keys().map(String::as_str).collect();
+            match known.as_slice() {
+                [] => format!(
+                    "work: no profile '{name}' (add a [profile.{name}] section to the config file)"
+                ),
+                _ => format!("work: no profile '{name}' (have: {})", known.join(", ")),
+            }
+        })?),
+        None => None,
+    };
+    if let Some(scan) = cli.scan_mut() {
+        let mut paths = cfg.paths;
+        let mut ignore = cfg.ignore;
+        if let Some(p) = profile {
+            if !p.paths.is_empty() {
+                paths = p.paths;
+            }
+            ignore.extend(p.ignore);
+            scan.exclude_repo_globs.extend(p.exclude_repo);
+            if p.name.is_some() || !p.emails.is_empty() {
+                let mut emails = p.emails.into_iter();
+                scan.identity = Some(Box::new(Identity {
+                    name: p.name,
+                    email: emails.next(),
+                    other_emails: emails.collect(),
+                }));
+            }
+            scan.default_window = p.window;
+        }
+        if scan.paths.is_empty() {
+            scan.paths = paths;
+        }
+        scan.bots = cfg.bots;
+        scan.ignore = ignore;
+        scan.record_usage = cfg.record_usage;
+    }
+    cli.args.goal = cfg.goal;
+    if let Some(Cmd::Watch(w)) = cli.command.as_mut() {
+        w.log.goal = cfg.goal;
+    }
+
+    match cli.command.as_mut() {
+        Some(Cmd::Github(g)) => {
+            g.token = g
+                .token
+                .take()
+                .or_else(|| std::env::var("GITHUB_TOKEN").ok().filter(|t| !t.is_empty()))
+                .or(cfg.github_token);
+            g.api_url = g.api_url.take().or(cfg.github_api_url);
+        }
+        Some(Cmd::Db { action }) => {
+            let db = match action {
+                db::DbAction::Sync(d) => &mut d.db,
+                db::DbAction::Query(q) => &mut q.db,
+            };
+            *db = db.take().or(cfg.db_path);
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// The window flags (`--week`, `--days`, ...) and whether each takes a value.
+fn window_flags() -> Vec<(String, bool)> {
+    let mut cmd = Cli::command();
+    // Groups named in `#[arg(group)]` only exist once the command is built.
+    cmd.build();
+    let ids: Vec<clap::Id> = cmd
+        .get_groups()
+        .filter(|g| g.get_id() == "window")
+        .flat_map(|g| g.get_args().cloned())
+        .collect();
+    cmd.get_arguments()
+        .filter(|a| ids.contains(a.get_id()))
+        .flat_map(|a| {
+            let takes_values = a.get_action().takes_values();
+            a.get_long_and_visible_aliases()
+                .unwrap_or_default()
+                .into_iter()
+                .map(move |long| (format!("--{long}"), takes_values))
+        })
+        .collect()
+}
+
+/// `work view NAME ARGS...`: the command line the view stands for, with
+/// `ARGS` after it, parsed as if it had been typed. A flag in `ARGS`
+/// replaces the view's, and so does a window flag the view's window.
+fn expand_view(cfg: &config::Config, name: &str, extra: &[String]) -> Result<Cli, String> {
+    let view = cfg.views.get(name).ok_or_else(|| {
+        let known = cfg.views.keys().cloned().collect::<Vec<_>>().join(", ");
+        format!("work: no view '{name}' in the config file (known: {known})")
+    })?;
+
+    let windows = window_flags();
+    let is_window = |arg: &str| {
+        windows
+            .iter()
+            .find(|(flag, _)| arg == flag || arg.starts_with(&format!("{flag}=")))
+    };
+    let mut view_args = Vec::new();
+    let mut it = view.iter();
+    while let Some(arg) = it.next() {
+        match is_window(arg) {
+            Some((flag, takes_value)) if extra.iter().any(|e| is_window(e).is_some()) => {
+                if *takes_value && arg == flag {
+                    it.next();
+                }
+            }
+            _ => view_args.push(arg),
+        }
+    }
+
+    let argv: Vec<&str> = std::iter::once("work")
+        .chain(view_args.into_iter().chain(extra).map(String::as_str))
+        .collect();
+    fn overriding(cmd: clap::Command) -> clap::Command {
+        cmd.args_override_self(true).mut_subcommands(overriding)
+    }
+    let cli = overriding(Cli::command())
+        .try_get_matches_from(&argv)
+        .and_then(|m| Cli::from_arg_matches(&m))
+        .map_err(|e| format!("work: view '{name}' is `{}`:\n{e}", argv.join(" ")))?;
+    if matches!(cli.command, Some(Cmd::View { .. })) {
+        return Err(format!("work: view '{name}' can't run another view"));
+    }
+    Ok(cli)
+}
+
+/// `work completions`: a script that calls back into `work` (see
+/// `CompleteEnv` in `main`) for each completion, so view and profile names
+/// come from the config file as it is when you press tab.
+fn completions(shell: clap_complete::Shell) -> Result<(), String> {
+    let shells = clap_complete::env::Shells::builtins();
+    let completer = shells
+        .completer(&shell.to_string())
+        .ok_or_else(|| format!("work: no completions for {shell}"))?;
+    completer
+        .write_registration("COMPLETE", "work", "work", "work", &mut std::io::stdout())
+        .map_err(|e| format!("work: cannot write output: {e}"))
+}
+
+fn list_views() -> Result<(), String> {
+    let cfg = config::load()?;
+    if cfg.views.is_empty() {
+        let path = config::path()
+            .map(|p| p.display().to_string())
+            .unwrap_or_default();
+        return Err(format!(
+            "work: no [view.NAME] tables in the config file {path}"
+        ));
+    }
+    let width = cfg.views.keys().map(String::len).max().unwrap_or(0);
+    for (name, args) in &cfg.views {
+        println!(
+            "{}  work {}",
+            paint("1", format!("{name:<width$}")),
+            args.join(" ")
+        );
+    }
+    Ok(())
+}
+
+/// The `work` command.
+pub fn main() {
+    unsafe { libc::signal(libc::SIGPIPE, libc::SIG_DFL) };
+    // A completion script asking for candidates: answer and exit.
+    clap_complete::CompleteEnv::with_factory(Cli::command).complete();
+    let mut cli = Cli::parse();
+    if let Some(Cmd::View {
+        name: Some(name),
+        args,
+    }) = &cli.command
+    {
+        match config::load().and_then(|cfg| expand_view(&cfg, name, args)) {
+            Ok(mut expanded) => {
+                expanded.args.view = Some(name.clone());
+                cli = expanded;
+            }
+            Err(e) => {
+                eprintln!("{e}");
+                std::process::exit(1);
+            }
+        }
+    }
+    let setup = if cli.scan_mut().is_some_and(|s| s.deterministic) {
+        make_deterministic(cli.tz)
+    } else if matches!(cli.command, Some(Cmd::Config { .. })) {
+        // `work config` has to work with a broken config file.
+        Ok(())
+    } else {
+        config::load().and_then(|cfg| apply_config(&mut cli, cfg))
+    };
+    if let Err(e) = setup {
+        eprintln!("{e}");
+        std::process::exit(1);
+    }
+    if let Some(tz) = cli.tz {
+        let _ = ZONE.set(tz);
+    }
+    if cli.scan_mut().is_some_and(|s| s.trust_all) {
+        trust_all();
+    }
+    if let Some(scan) = cli.scan_mut()
+        && !(scan.no_stat_cache || scan.deterministic)
+    {
+        scan.stat_cache = statcache::dir();
+    }
+    let result = match cli.command {
+        Some(Cmd::Config { action }) => config::config(action),
+        Some(Cmd::Bench(b)) => bench::bench(b),
+        Some(Cmd::Sync {
+            target: SyncTarget::Activitywatch(aw),
+        }) => activitywatch::sync(aw),
+        Some(Cmd::Sync {
+            target: SyncTarget::Timewarrior(tw),
+        }) => timewarrior::sync(tw),
+        Some(Cmd::Db {
+            action: db::DbAction::Sync(d),
+        }) => db::sync(*d),
+        Some(Cmd::Db {
+            action: db::DbAction::Query(q),
+        }) => db::query(q),
+        Some(Cmd::Report(r)) => report::report(r),
+        Some(Cmd::Github(g)) => github::github(g),
+        Some(Cmd::Menubar(m)) => menubar::menubar(m),
+        Some(Cmd::Waybar(w)) => waybar::waybar(w),
+        Some(Cmd::Maintain(m)) => maintain::maintain(m),
+        Some(Cmd::Repos(r)) => repos::repos(r),
+        Some(Cmd::Open(o)) => open::open(o),
+        Some(Cmd::Show(s)) => show::show(s),
+        #[cfg(feature = "gui")]
+        Some(Cmd::Gui(g)) => gui::gui(g),
+        Some(Cmd::Feed(f)) => feed::feed(f),
+        Some(Cmd::Compare(c)) => compare::compare(c),
+        Some(Cmd::Releases(r)) => releases::releases(r),
+        Some(Cmd::Punchcard(p)) => punchcard::punchcard(p),
+        Some(Cmd::Churn(c)) => churn::churn(c),
+        Some(Cmd::Export(e)) => export::export(e),
+        Some(Cmd::Watch(w)) => watch::watch(w),
+        Some(Cmd::Stats(s)) => usage::stats(s),
+        Some(Cmd::View { .. }) => list_views(),
+        Some(Cmd::Completions { shell }) => completions(shell),
+        None => run(cli.args),
+    };
+    if let Err(e) = result {
+        eprintln!("{e}");
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    fn init_repo(tmp: &Path, name: &str) -> PathBuf {
+        let dir = tmp.join(name);
+        fs::create_dir_all(&dir).unwrap();
+        Command::new("git")
+            .args(["init", "-q"])
+            .current_dir(&dir)
+            .status()
+            .unwrap();
+        // Configure identity locally so commits have predictable author.
+        Command::new("git")
+            .args(["config", "user.name", "Test User"])
+            .current_dir(&dir)
+            .status()
+            .unwrap();
+        Command::new("git")
+            .args(["config", "user.email", "test@example.com"])
+            .current_dir(&dir)
+            .status()
+            .unwrap();
+        dir
+    }
+
+    fn commit(dir: &Path, msg: &str) {
+        fs::write(dir.join("file.txt"), msg).unwrap();
+        Command::new("git")
+            .args(["add", "."])
+            .current_dir(dir)
+            .status()
+            .unwrap();
+        Command::new("git")
+            .args(["commit", "-m", msg, "-q"])
+            .current_dir(dir)
+            .status()
+            .unwrap();
+    }
+
+    #[test]
+    fn parses_default_log_and_subcommands() {
+        let cli = Cli::try_parse_from(["work", "--all", "-l", "5", "a"]).unwrap();
+        assert!(cli.command.is_none());
+        assert_eq!(cli.args.scan.paths, [PathBuf::from("a")]);
+        assert_eq!(cli.args.limit, 5);
+        assert_eq!(cli.args.columns.len(), 5);
+
+        let cli = Cli::try_parse_from(["work", "--columns", "summary,hash", "a"]).unwrap();
+        assert_eq!(cli.args.columns, [Column::Summary, Column::Hash]);
+        assert!(Cli::try_parse_from(["work", "--jsonl", "-r", "a"]).is_err());
+        assert!(Cli::try_parse_from(["work", "--highlight-me", "a"]).is_err());
+        assert!(Cli::try_parse_from(["work", "--all", "--highlight-me", "a"]).is_ok());
+        // --stream prints repo by repo, without the highlighting or the footer.
+        assert!(Cli::try_parse_from(["work", "--all", "--highlight-me", "--stream", "a"]).is_err());
+        assert!(Cli::try_parse_from(["work", "--plain", "--raw", "a"]).is_err());
+        assert!(Cli::try_parse_from(["work", "--plain", "--full-message", "a"]).is_ok());
+        assert!(Cli::try_parse_from(["work", "--max-width", "80", "--no-truncate", "a"]).is_err());
+        assert!(Cli::try_parse_from(["work", "--max-width", "8", "a"]).is_err());
+        let cli = Cli::try_parse_from(["work", "--on", "2026-03-04", "a"]).unwrap();
+        let day = |d| chrono::NaiveDate::from_ymd_opt(2026, 3, d).unwrap();
+        assert_eq!(cli.args.scan.window(), Window::Range(day(4), day(5)));
+        assert_eq!(cli.args.scan.window().label(), "2026-03-04");
+        let cli = Cli::try_parse_from(["work", "--this-week", "a"]).unwrap();
+        assert_eq!(cli.args.scan.window(), Window::Week);
+        assert!(Cli::try_parse_from(["work", "--yesterday", "--on", "2026-03-04", "a"]).is_err());
+
+        let cli = Cli::try_parse_from(["work", "completions", "zsh"]).unwrap();
+        assert!(matches!(cli.command, Some(Cmd::Completions { .. })));
+
+        // --tz works before or after a subcommand.
+        let cli = Cli::try_parse_from(["work", "report", "--tz", "Asia/Tokyo", "a"]).unwrap();
+        assert_eq!(cli.tz, Some(chrono_tz::Asia::Tokyo));
+        assert!(Cli::try_parse_from(["work", "--tz", "Mars/Olympus", "a"]).is_err());
+    }
+
+    #[test]
+    fn expands_views() {
+        let cfg = config::Config {
+            views: [(
+                "team".to_string(),
+                ["--all", "--limit", "2", "--days", "30", "/code"]
+                    .map(String::from)
+                    .to_vec(),
+            )]
+            .into(),
+            ..Default::default()
+        };
+        let cli = expand_view(&cfg, "team", &[]).unwrap();
+        assert!(cli.args.scan.all);
+        assert_eq!(cli.args.scan.days, Some(30));
+
+        // Later flags win, and any window flag replaces the view's window.
+        let extra = ["-l", "5", "--week"].map(String::from);
+        let cli = expand_view(&cfg, "team", &extra).unwrap();
+        assert_eq!(cli.args.limit, 5);
+        assert_eq!((cli.args.scan.days, cli.args.scan.week), (None, true));
+        assert_eq!(cli.args.scan.paths, [PathBuf::from("/code")]);
+
+        assert!(expand_view(&cfg, "nope", &[]).is_err());
+    }
+
+    #[test]
+    fn finds_repos_respects_depth() {
+        let tmp = tempfile::tempdir().unwrap();
+        init_repo(tmp.path(), "a");
+        init_repo(tmp.path(), "deep/nested/b");
+        let none = ignore::Ignore::default();
+        let mut walk = Walk {
+            max_depth: 1,
+            ignore: &none,
+            global: &none,
+            skip_hidden: false,
+        };
+        assert_eq!(find_repos(tmp.path(), &walk, &mut |_, _| {}).len(), 1);
+
+        let mut skipped = Vec::new();
+        walk.max_depth = 3;
+        let found = find_repos(tmp.path(), &walk, &mut |p, why| {
+            skipped.push((p.to_path_buf(), why))
+        });
+        assert_eq!(found.len(), 2);
+        assert!(skipped.is_empty());
+
+        std::os::unix::fs::symlink(tmp.path().join("deep"), tmp.path().join("link")).unwrap();
+        walk.max_depth = 1;
+        find_repos(tmp.path(), &walk, &mut |p, why| {
+            skipped.push((p.to_path_buf(), why))
+        });
+        assert_eq!(
+            skipped,
+            vec![
+                (tmp.path().join("deep/nested"), Skip::Depth(1)),
+                (tmp.path().join("link"), Skip::Symlink),
+            ]
+        );
+
+        // Hidden dirs only with --skip-hidden; the global gitignore by name.
+        init_repo(tmp.path(), ".dotfiles");
+        init_repo(tmp.path(), "web/node_modules/pkg");
+        let global = ignore::Ignore::load(tmp.path(), &["node_modules".to_string()]).unwrap();
+        walk = Walk {
+            max_depth: 3,
+            ignore: &none,
+            global: &global,
+            skip_hidden: true,
+        };
+        let mut skipped = Vec::new();
+        let found = find_repos(tmp.path(), &walk, &mut |p, why| {
+            skipped.push((p.to_path_buf(), why))
+        });
+        assert_eq!(found.len(), 2);
+        assert_eq!(
+            skipped,
+            vec![
+                (tmp.path().join(".dotfiles"), Skip::Hidden),
+                (tmp.path().join("link"), Skip::Symlink),
+                (tmp.path().join("web/node_modules"), Skip::GitIgnored),
+            ]
+        );
+    }
+
+    #[test]
+    fn collects_commits() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = init_repo(tmp.path(), "a");
+        commit(&repo, "one");
+        commit(&repo, "two");
+
+        let args = Args::parse_from(["work", "--all", "--raw", tmp.path().to_str().unwrap()]);
+
+        let since = chrono::Local::now().timestamp() - 7 * 24 * 60 * 60;
+        let (got, err) = collect_commits(
+            &repo,
+            since,
+            None,
+            &Identity {
+                name: None,
+                email: None,
+                other_emails: Vec::new(),
+            },
+            &args.scan,
+        );
+        assert_eq!(err, None);
+        assert!(got.len() >= 2);
+    }
+
+    #[test]
+    fn windows_by_author_or_committer_date() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = init_repo(tmp.path(), "a");
+        // Written ten days ago, rebased just now.
+        let written = chrono::Local::now().timestamp() - 10 * 24 * 60 * 60;
+        fs::write(repo.join("file.txt"), "old work").unwrap();
+        Command::new("git")
+            .args(["add", "."])
+            .current_dir(&repo)
+            .status()
+            .unwrap();
+        Command::new("git")
+            .args(["commit", "-qm", "old work"])
+            .env("GIT_AUTHOR_DATE", format!("@{written} +0000"))
+            .current_dir(&repo)
+            .status()
+            .unwrap();
+        let root = tmp.path().to_str().unwrap();
+
+        let got = scan(&Args::parse_from(["work", "--all", root]).scan).unwrap();
+        assert_eq!(got.commits.len(), 1);
+
+        let args = Args::parse_from(["work", "--all", "--date", "author", root]);
+        assert!(scan(&args.scan).unwrap().commits.is_empty());
+
+        let args = Args::parse_from(["work", "--all", "--date", "author", "--days", "30", root]);
+        assert_eq!(scan(&args.scan).unwrap().commits[0].time, written);
+    }
+
+    #[test]
+    fn leaves_implausible_dates_out_of_windows() {
+        use work::fixtures::FixtureRepo;
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = FixtureRepo::init(&tmp.path().join("import")).unwrap();
+        let hour_ago = chrono::Local::now().timestamp() - 3600;
+        repo.commit(hour_ago, "real", &[("a", "1")]).unwrap();
+        // 2106-02-07, just past u32 seconds.
+        repo.commit(4_294_967_296, "from the future", &[("a", "2")])
+            .unwrap();
+
+        let args = Args::parse_from(["work", "--all", tmp.path().to_str().unwrap()]);
+        let got = scan(&args.scan).unwrap();
+        assert_eq!(got.commits.len(), 1);
+        assert_eq!(got.commits[0].summary, "real");
+
+        assert_eq!(format_time_local(4_294_967_296), "(invalid date)  ");
+        assert_eq!(format_time_local(-86_400), "(invalid date)  ");
+        assert_eq!(format_clock_local(-86_400), "(invalid date)");
+        assert_eq!(format_time_rfc3339(-1), "(invalid date)");
+    }
+
+    #[test]
+    fn scans_multiple_roots() {
+        let tmp = tempfile::tempdir().unwrap();
+        let code = init_repo(tmp.path(), "code/app");
+        let oss = init_repo(tmp.path(), "oss/lib");
+        commit(&code, "app work");
+        commit(&oss, "lib work");
+
+        let args = Args::parse_from([
+            "work",
+            "--all",
+            tmp.path().join("code").to_str().unwrap(),
+            tmp.path().join("oss").to_str().unwrap(),
+            // Overlaps the first root; must not double up its repos.
+            tmp.path().join("code/app").to_str().unwrap(),
+        ]);
+        let got = scan(&args.scan).unwrap();
+
+        assert_eq!(got.repos.len(), 2);
+        assert_eq!(got.commits.len(), 2);
+        let oss = oss.canonicalize().unwrap();
+        assert_eq!(got.rel(&oss), Path::new("lib"));
+    }
+
+    #[test]
+    fn captures_commit_body() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = init_repo(tmp.path(), "a");
+        commit(&repo, "plain");
+        fs::write(repo.join("file.txt"), "two").unwrap();
+        Command::new("git")
+            .args([
+                "commit",
+                "-qam",
+                "subject",
+                "-m",
+                "why it matters",
+                "-m",
+                "Fixes #123",
+            ])
+            .current_dir(&repo)
+            .status()
+            .unwrap();
+
+        let args = Args::parse_from(["work", "--all", tmp.path().to_str().unwrap()]);
+        let got = scan(&args.scan).unwrap().commits;
+        let with_body = got.iter().find(|c| c.summary == "subject").unwrap();
+        assert_eq!(
+            with_body.body.as_deref(),
+            Some("why it matters\n\nFixes #123")
+        );
+        let plain = got.iter().find(|c| c.summary == "plain").unwrap();
+        assert_eq!(plain.body, None);
+    }
+
+    #[test]
+    fn fills_goal_bars_in_proportion() {
+        COLOR.store(false, Ordering::Relaxed);
+        assert_eq!(goal_bar(0, 5), "░".repeat(20));
+        assert_eq!(
+            goal_bar(2, 5),
+            format!("{}{}", "█".repeat(8), "░".repeat(12))
+        );
+        assert_eq!(goal_bar(9, 5), "█".repeat(20));
+        assert!(Cli::try_parse_from(["work", "--goal-bar", "a"]).is_err());
+        assert!(Cli::try_parse_from(["work", "--today", "--goal-bar", "a"]).is_ok());
+    }
+
+    #[test]
+    fn wraps_summaries_by_visible_width() {
+        assert_eq!(visible_width(&format!("ab{}", "\x1b[36mcd\x1b[0m")), 4);
+        assert_eq!(visible_width("修复 bug"), 8);
+        assert_eq!(pad_visible("李雷", 6), "李雷  ");
+        assert_eq!(
+            truncate_visible("fix the flaky retry test", 24),
+            "fix the flaky retry test"
+        );
+        assert_eq!(
+            truncate_visible("fix the flaky retry test", 12),
+            "fix the fla…"
+        );
+        // The wide 登 would straddle the edge, so it goes whole.
+        assert_eq!(truncate_visible("修复登录问题", 6), "修复…");
+        assert_eq!(
+            truncate_visible("\x1b[36mfix the flaky\x1b[0m", 8),
+            "\x1b[36mfix the…\x1b[0m"
+        );
+        assert_eq!(
+            wrap_words("fix the flaky retry test", "    ", 16),
+            ["    fix the", "    flaky retry", "    test"]
+        );
+        assert_eq!(
+            wrap_words("supercalifragilistic", "  ", 8),
+            ["  supercalifragilistic"]
+        );
+    }
+
+    #[test]
+    fn path_filter_limits_commits_and_stats() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = init_repo(tmp.path(), "a");
+        fs::create_dir_all(repo.join("docs")).unwrap();
+        fs::write(repo.join("docs/guide.md"), "1\n2\n").unwrap();
+        commit(&repo, "docs and code");
+        commit(&repo, "code only");
+
+        let args = Args::parse_from(["work", "--all", "--path", "docs", "x"]);
+        let (commits, err) = collect_commits(&repo, 0, None, &default_identity(), &args.scan);
+        assert!(err.is_none());
+        let got: Vec<(&str, usize, usize)> = commits
+            .iter()
+            .map(|c| (c.summary.as_str(), c.files, c.insertions))
+            .collect();
+        assert_eq!(got, [("docs and code", 1, 2)]);
+    }
+
+    #[test]
+    fn reads_notes_from_the_default_notes_ref() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = init_repo(tmp.path(), "a");
+        commit(&repo, "benchmarked");
+        commit(&repo, "plain");
+        Command::new("git")
+            .args([
+                "notes",
+                "add",
+                "-m",
+                "bench: 12ms\nreviewed-by: ana",
+                "HEAD~1",
+            ])
+            .current_dir(&repo)
+            .status()
+            .unwrap();
+
+        let args = Args::parse_from(["work", "--all", "x"]);
+        let (commits, err) = collect_commits(&repo, 0, None, &default_identity(), &args.scan);
+        assert!(err.is_none());
+        let got: Vec<(&str, Option<&str>)> = commits
+            .iter()
+            .map(|c| (c.summary.as_str(), c.note.as_deref()))
+            .collect();
+        assert_eq!(
+            got,
+            [
+                ("plain", None),
+                ("benchmarked", Some("bench: 12ms\nreviewed-by: ana"))
+            ]
+        );
+    }
+
+    #[test]
+    fn verifies_commit_signatures() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = init_repo(tmp.path(), "a");
+        let key = tmp.path().join("key");
+        let made = Command::new("ssh-keygen")
+            .args(["-q", "-t", "ed25519", "-N", "", "-C", "test", "-f"])
+            .arg(&key)
+            .status();
+        if !made.is_ok_and(|s| s.success()) {
+            eprintln!("skipping: ssh-keygen is not available");
+            return;
+        }
+        let public = fs::read_to_string(key.with_extension("pub")).unwrap();
+        fs::write(
+            tmp.path().join("allowed"),
+            format!("test@example.com {public}"),
+        )
+        .unwrap();
+        let git = |args: &[&str]| {
+            Command::new("git")
+                .args(args)
+                .current_dir(&repo)
+                .status()
+                .unwrap()
+        };
+        git(&["config", "gpg.format", "ssh"]);
+        git(&["config", "user.signingkey", key.to_str().unwrap()]);
+        git(&[
+            "config",
+            "gpg.ssh.allowedSignersFile",
+            tmp.path().join("allowed").to_str().unwrap(),
+        ]);
+
+        commit(&repo, "unsigned");
+        fs::write(repo.join("file.txt"), "signed").unwrap();
+        git(&["commit", "-qaS", "-m", "signed"]);
+        // Signed by a key the allowed signers file doesn't list.
+        let stranger = tmp.path().join("stranger");
+        Command::new("ssh-keygen")
+            .args(["-q", "-t", "ed25519", "-N", "", "-f"])
+            .arg(&stranger)
+            .status()
+            .unwrap();
+        fs::write(repo.join("file.txt"), "forged").unwrap();
+        git(&[
+            "-c",
+            &format!("user.signingkey={}", stranger.display()),
+            "commit",
+            "-qaS",
+            "-m",
+            "forged",
+        ]);
+
+        let args = Args::parse_from(["work", "--all", "--show-signatures", "x"]);
+        let mut scan = args.scan.clone();
+        scan.signatures = args.show_signatures;
+        let (commits, err) = collect_commits(&repo, 0, None, &default_identity(), &scan);
+        assert!(err.is_none());
+        let mut got: Vec<(&str, Option<signing::Signature>)> = commits
+            .iter()
+            .map(|c| (c.summary.as_str(), c.signature))
+            .collect();
+        got.sort();
+        assert_eq!(
+            got,
+            [
+                ("forged", Some(signing::Signature::Bad)),
+                ("signed", Some(signing::Signature::Good)),
+                ("unsigned", Some(signing::Signature::Unsigned)),
+            ]
+        );
+        assert_eq!(
+            signature_counts(&commits.iter().collect::<Vec<_>>()),
+            [1, 1, 1]
+        );
+        assert!(Cli::try_parse_from(["work", "--show-signatures", "--jsonl", "x"]).is_err());
+    }
+
+    #[test]
+    fn abbreviations_grow_past_ambiguous_prefixes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tmp.path()).unwrap();
+        // Blobs until two share their first four hex digits (a few hundred).
+        let mut seen = HashMap::new();
+        let (a, b) = (0..)
+            .find_map(|i| {
+                let oid = repo.blob(format!("blob {i}").as_bytes()).unwrap();
+                seen.insert(oid.to_string()[..4].to_string(), oid)
+                    .map(|other| (other, oid))
+            })
+            .unwrap();
+
+        let (short_a, short_b) = (abbreviate(&repo, a, 4), abbreviate(&repo, b, 4));
+        assert!(short_a.len() > 4 && short_b.len() > 4);
+        assert!(a.to_string().starts_with(&short_a) && !b.to_string().starts_with(&short_a));
+        assert_eq!(abbreviate(&repo, a, 40), a.to_string());
+        assert!(Cli::try_parse_from(["work", "--abbrev", "3", "a"]).is_err());
+        assert!(Cli::try_parse_from(["work", "--abbrev", "12", "--full-hash", "a"]).is_err());
+    }
+
+    #[test]
+    fn finds_commits_that_add_or_remove_code() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = init_repo(tmp.path(), "a");
+        commit(&repo, "one");
+        commit(&repo, "call parse_args here");
+        commit(&repo, "call parse_args there");
+        commit(&repo, "two");
+
+        let args = Args::parse_from(["work", "--all", "--find-code", "parse_args", "x"]);
+        let (commits, err) = collect_commits(&repo, 0, None, &default_identity(), &args.scan);
+        assert!(err.is_none());
+        let mut got: Vec<&str> = commits.iter().map(|c| c.summary.as_str()).collect();
+        got.sort();
+        // Moving the call within the file doesn't change how many there are.
+        assert_eq!(got, ["call parse_args here", "two"]);
+    }
+
+    #[test]
+    fn active_only_skips_dormant_clones() {
+        let tmp = tempfile::tempdir().unwrap();
+        for name in ["busy", "dormant"] {
+            let repo = init_repo(tmp.path(), name);
+            commit(&repo, name);
+        }
+        let long_ago = SystemTime::now() - Duration::from_secs(30 * 24 * 60 * 60);
+        let git_dir = tmp.path().join("dormant/.git");
+        for file in [
+            "HEAD",
+            "index",
+            "logs/HEAD",
+            "packed-refs",
+            "FETCH_HEAD",
+            "refs/heads",
+        ] {
+            if let Ok(f) = fs::File::open(git_dir.join(file)) {
+                f.set_modified(long_ago).unwrap();
+            }
+        }
+
+        let root = tmp.path().to_str().unwrap();
+        let args = Args::parse_from(["work", "--all", "--active-only", root]);
+        let mut skipped = Vec::new();
+        let (_, repos) = discover_traced(&args.scan, &mut |p, why| {
+            skipped.push((p.to_path_buf(), why))
+        })
+        .unwrap();
+        assert_eq!(repos, [tmp.path().canonicalize().unwrap().join("busy")]);
+        assert_eq!(
+            skipped,
+            [(
+                tmp.path().canonicalize().unwrap().join("dormant"),
+                Skip::Dormant
+            )]
+        );
+    }
+
+    #[test]
+    fn first_parent_follows_merges_not_branches() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = init_repo(tmp.path(), "a");
+        commit(&repo, "base");
+        let git = |args: &[&str]| {
+            Command::new("git")
+                .args(args)
+                .current_dir(&repo)
+                .status()
+                .unwrap()
+        };
+        git(&["checkout", "-q", "-b", "topic"]);
+        commit(&repo, "side 1");
+        commit(&repo, "side 2");
+        git(&["checkout", "-q", "-"]);
+        git(&["merge", "-q", "--no-ff", "topic", "-m", "land topic"]);
+
+        let summaries = |flags: &[&str]| {
+            let args = Args::parse_from([&["work", "--all"], flags, &["x"]].concat());
+            let (commits, err) = collect_commits(&repo, 0, None, &default_identity(), &args.scan);
+            assert!(err.is_none());
+            let mut got: Vec<(String, usize)> =
+                commits.into_iter().map(|c| (c.summary, c.files)).collect();
+            got.sort();
+            got
+        };
+        let s = |m: &str, f: usize| (m.to_string(), f);
+        assert_eq!(
+            summaries(&[]),
+            [s("base", 1), s("side 1", 1), s("side 2", 1)]
+        );
+        assert_eq!(
+            summaries(&["--first-parent"]),
+            [s("base", 1), s("land topic", 1)]
+        );
+    }
+
+    #[test]
+    fn scan_reports_each_repo_as_it_finishes() {
+        let tmp = tempfile::tempdir().unwrap();
+        for name in ["a", "b", "c"] {
+            let repo = init_repo(tmp.path(), name);
+            commit(&repo, name);
+        }
+
+        let seen = Mutex::new(Vec::new());
+        let hook = |e: Event| {
+            if let Event::Finished(repo) = e {
+                seen.lock().unwrap().push(repo.commits.len());
+            }
+        };
+        let args = Args::parse_from(["work", "--all", tmp.path().to_str().unwrap()]);
+        scan_with(&args.scan, Some(&hook)).unwrap();
+
+        assert_eq!(seen.into_inner().unwrap(), vec![1, 1, 1]);
+    }
+
+    #[test]
+    fn filters_repos_by_glob() {
+        let tmp = tempfile::tempdir().unwrap();
+        for name in ["apps/web", "apps/api", "libs/ui"] {
+            let repo = init_repo(tmp.path(), name);
+            commit(&repo, name);
+        }
+        let root = tmp.path().to_str().unwrap();
+
+        let args = Args::parse_from(["work", "--all", "--repo", "apps/*", root]);
+        assert_eq!(scan(&args.scan).unwrap().repos.len(), 2);
+
+        let args = Args::parse_from([
+            "work",
+            "--all",
+            "--repo",
+            "apps/*",
+            "--exclude-repo",
+            "*/api",
+            root,
+        ]);
+        let got = scan(&args.scan).unwrap();
+        assert_eq!(got.repos.len(), 1);
+        assert!(got.repos[0].ends_with("apps/web"));
+
+        let args = Args::parse_from(["work", "--all", "--repo", "nope", root]);
+        assert!(scan(&args.scan).is_err());
+    }
+
+    #[test]
+    fn counts_lfs_pointers_apart_from_lines() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = init_repo(tmp.path(), "a");
+        let pointer = |size: u32| {
+            format!(
+                "version https://git-lfs.github.com/spec/v1\noid sha256:{:064x}\nsize {size}\n",
+                size
+            )
+        };
+        fs::write(repo.join("model.bin"), pointer(1_000)).unwrap();
+        commit(&repo, "one\n");
+        fs::write(repo.join("model.bin"), pointer(2_501_000)).unwrap();
+        commit(&repo, "one\ntwo\n");
+
+        let opened = Repository::open(&repo).unwrap();
+        let head = opened.head().unwrap().peel_to_commit().unwrap();
+        let args = Args::parse_from(["work", "x"]);
+        let stats = diff_stats(&opened, &head, &stat_pathspecs(&args.scan), false, None);
+        assert_eq!(
+            stats,
+            DiffStats {
+                files: 2,
+                insertions: 1,
+                deletions: 0,
+                lfs_files: 1,
+                lfs_bytes: 2_500_000,
+                ..DiffStats::default()
+            }
+        );
+        assert_eq!(format_bytes(2_500_000), "+2.5 MB");
+        assert_eq!(format_bytes(-340_400), "-340 KB");
+        assert_eq!(format_bytes(12), "+12 B");
+    }
+
+    #[test]
+    fn classifies_mode_and_symlink_only_commits() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = init_repo(tmp.path(), "a");
+        let git = |args: &[&str]| {
+            Command::new("git")
+                .args(args)
+                .current_dir(&repo)
+                .status()
+                .unwrap();
+        };
+        std::os::unix::fs::symlink("file.txt", repo.join("link")).unwrap();
+        commit(&repo, "one\n");
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(repo.join("file.txt"), fs::Permissions::from_mode(0o755)).unwrap();
+        git(&["commit", "-qam", "chmod"]);
+        fs::remove_file(repo.join("link")).unwrap();
+        std::os::unix::fs::symlink("elsewhere.txt", repo.join("link")).unwrap();
+        git(&["commit", "-qam", "retarget"]);
+        commit(&repo, "one\ntwo\n");
+
+        let by_summary = |flags: &[&str]| {
+            let mut argv = vec!["work", "--all"];
+            argv.extend(flags);
+            argv.push(tmp.path().to_str().unwrap());
+            let args = Args::parse_from(argv);
+            let commits = scan(&args.scan).unwrap().commits;
+            move |summary: &str| {
+                let c = commits.iter().find(|c| c.summary == summary).unwrap();
+                (c.mode_only, c.files, c.insertions, c.deletions)
+            }
+        };
+        let counted = by_summary(&[]);
+        assert_eq!(counted("chmod"), (true, 1, 0, 0));
+        assert_eq!(counted("retarget"), (true, 1, 1, 1));
+        assert_eq!(counted("one two"), (false, 1, 1, 0));
+        let excluded = by_summary(&["--exclude-mode-changes"]);
+        assert_eq!(excluded("chmod"), (true, 0, 0, 0));
+        assert_eq!(excluded("retarget"), (true, 0, 0, 0));
+        assert_eq!(excluded("one two"), (false, 1, 1, 0));
+    }
+
+    #[test]
+    fn filters_by_other_authors() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = init_repo(tmp.path(), "a");
+        let people = [
+            ("Ana Lima", "ana@corp.io"),
+            ("Bo Chen", "bo@oss.dev"),
+            ("Kaf", "kaf@corp.io"),
+        ];
+        for (name, email) in people {
+            fs::write(repo.join("file.txt"), name).unwrap();
+            Command::new("git")
+                .args(["add", "."])
+                .current_dir(&repo)
+                .status()
+                .unwrap();
+            Command::new("git")
+                .args(["commit", "-qm", name])
+                .env("GIT_AUTHOR_NAME", name)
+                .env("GIT_AUTHOR_EMAIL", email)
+                .current_dir(&repo)
+                .status()
+                .unwrap();
+        }
+        let authors = |patterns: &[&str]| {
+            let mut argv = vec!["work"];
+            for p in patterns {
+                argv.extend(["--author", p]);
+            }
+            argv.push(tmp.path().to_str().unwrap());
+            let args = Args::parse_from(argv);
+            let mut got: Vec<String> = scan(&args.scan)
+                .unwrap()
+                .commits
+                .into_iter()
+                .map(|c| c.author_name)
+                .collect();
+            got.sort();
+            got
+        };
+        assert_eq!(authors(&["ana"]), ["Ana Lima"]);
+        assert_eq!(authors(&["*@corp.io"]), ["Ana Lima", "Kaf"]);
+        assert_eq!(authors(&["/^b.*n$/", "KAF"]), ["Bo Chen", "Kaf"]);
+        assert!(authors(&["nobody"]).is_empty());
+        assert!(parse_author_pattern("/(/").is_err());
+    }
+
+    #[test]
+    fn raw_fields_cannot_break_columns() {
+        assert_eq!(
+            raw_field("Fix\tparser\r\nagain", false),
+            "Fix parser  again"
+        );
+        assert_eq!(raw_field("Fix\tparser\0", true), "Fix\tparser ");
+        assert!(Cli::try_parse_from(["work", "-z", "a"]).is_err());
+        assert!(Cli::try_parse_from(["work", "-rz", "a"]).unwrap().args.null);
+    }
+
+    #[test]
+    fn counts_commits_under_the_repos_own_identity() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = init_repo(tmp.path(), "a");
+        // init_repo sets a local identity; the global one is somebody else's.
+        let people = [
+            ("Test User", "test@example.com"),
+            ("Kaf", "kaf@home.dev"),
+            ("Ana", "ana@corp.io"),
+        ];
+        for (name, email) in people {
+            fs::write(repo.join("file.txt"), name).unwrap();
+            Command::new("git")
+                .args(["add", "."])
+                .current_dir(&repo)
+                .status()
+                .unwrap();
+            Command::new("git")
+                .args(["commit", "-qm", name])
+                .env("GIT_AUTHOR_NAME", name)
+                .env("GIT_AUTHOR_EMAIL", email)
+                .current_dir(&repo)
+                .status()
+                .unwrap();
+        }
+        let global = Identity {
+            name: Some("Kaf".to_string()),
+            email: Some("kaf@home.dev".to_string()),
+            other_emails: Vec::new(),
+        };
+        let args = Args::parse_from(["work", "x"]);
+        let (commits, err) = collect_commits(&repo, 0, None, &global, &args.scan);
+        assert!(err.is_none());
+        let mut got: Vec<&str> = commits.iter().map(|c| c.author_name.as_str()).collect();
+        got.sort();
+        assert_eq!(got, ["Kaf", "Test User"]);
+
+        let opened = Repository::open(&repo).unwrap();
+        let local = repo_identity(&opened, &global).unwrap();
+        assert_eq!(repo_identity(&opened, &local), None);
+
+        // A profile identity that isn't git's: a repo without its own
+        // brings nothing in, and one with only a local email brings just that.
+        let file = |name: &str, text: &str| {
+            let path = tmp.path().join(name);
+            fs::write(&path, text).unwrap();
+            path
+        };
+        let home = file(
+            "gitconfig",
+            "[user]\n\tname = Kaf\n\temail = kaf@home.dev\n",
+        );
+        let config = |local: Option<&Path>| {
+            let mut cfg = Config::new().unwrap();
+            cfg.add_file(&home, git2::ConfigLevel::Global, false)
+                .unwrap();
+            if let Some(local) = local {
+                cfg.add_file(local, git2::ConfigLevel::Local, false)
+                    .unwrap();
+            }
+            cfg
+        };
+        let profile = Identity {
+            name: Some("Ana".to_string()),
+            email: Some("ana@corp.io".to_string()),
+            other_emails: Vec::new(),
+        };
+        assert_eq!(own_identity(&config(None), &config(None), &profile), None);
+        let work = file("local", "[user]\n\temail = kaf@corp.io\n");
+        assert_eq!(
+            own_identity(&config(Some(&work)), &config(None), &profile),
+            Some(Identity {
+                name: None,
+                email: Some("kaf@corp.io".to_string()),
+                other_emails: Vec::new(),
+            })
+        );
+    }
+
+    #[test]
+    fn filters_on_trailers() {
+        let found = trailers(
+            "Fix login\n\nLong story.\n\nFixes: #42\nCo-authored-by: Kaf <kaf@corp.io>\nSigned-off-by: Ana <ana@corp.io>\n",
+        );
+        assert_eq!(found.len(), 3);
+        assert_eq!(found[0], ("Fixes".to_string(), "#42".to_string()));
+        let only = |specs: &[&str]| {
+            let filters: Vec<TrailerFilter> = specs
+                .iter()
+                .map(|s| parse_trailer_filter(s).unwrap())
+                .collect();
+            has_trailers(&found, &filters)
+        };
+        assert!(only(&[]));
+        assert!(only(&["fixes"]));
+        assert!(only(&["Co-authored-by=KAF@corp.io", "Fixes=#42"]));
+        assert!(!only(&["Co-authored-by=ana@corp.io"]));
+        assert!(!only(&["Reviewed-by"]));
+        assert!(parse_trailer_filter("=x").is_err());
+    }
+
+    #[test]
+    fn filters_on_change_size() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = init_repo(tmp.path(), "r");
+        commit(&repo, "one\n");
+        commit(&repo, "one\ntwo\nthree\n");
+        commit(&repo, "four\n");
+
+        let sizes = |flags: &[&str]| {
+            let args = Args::parse_from([&["work", "--all"], flags, &["x"]].concat());
+            let (commits, err) = collect_commits(&repo, 0, None, &default_identity(), &args.scan);
+            assert!(err.is_none());
+            let mut sizes: Vec<usize> =
+                commits.iter().map(|c| c.insertions + c.deletions).collect();
+            sizes.sort();
+            sizes
+        };
+        assert_eq!(sizes(&[]), [1, 2, 4]);
+        assert_eq!(sizes(&["--min-changes", "2"]), [2, 4]);
+        assert_eq!(sizes(&["--max-changes", "2"]), [1, 2]);
+        assert_eq!(sizes(&["--min-changes", "2", "--max-changes", "3"]), [2]);
+
+        let args = Args::parse_from(["work", "--min-changes", "5", "--max-changes", "1", "x"]);
+        let err = scan(&args.scan).err().unwrap();
+        assert!(err.contains("more than --max-changes"), "{err}");
+    }
+
+    #[test]
+    fn partial_clones_fetch_stats_only_when_allowed() {
+        let tmp = tempfile::tempdir().unwrap();
+        let origin = init_repo(tmp.path(), "origin");
+        commit(&origin, "one\n");
+        commit(&origin, "one\ntwo\n");
+        let git = |dir: &Path, args: &[&str]| {
+            Command::new("git")
+                .args(args)
+                .current_dir(dir)
+                .status()
+                .unwrap();
+        };
+        git(&origin, &["config", "uploadpack.allowFilter", "true"]);
+        let url = format!("file://{}", origin.display());
+        git(
+            tmp.path(),
+            &["clone", "-q", "--filter=blob:none", &url, "clone"],
+        );
+        let clone = tmp.path().join("clone");
+
+        let stats = |flags: &[&str]| {
+            let args = Args::parse_from([&["work", "--all"], flags, &["x"]].concat());
+            let (commits, err) = collect_commits(&clone, 0, None, &default_identity(), &args.scan);
+            assert!(err.is_none());
+            commits
+                .iter()
+                .map(|c| (c.no_stats, c.insertions))
+                .collect::<Vec<_>>()
+        };
+        // The checkout only brought in the newest blob; the diff needs the older one too.
+        assert_eq!(stats(&[]), [(true, 0), (true, 0)]);
+        assert_eq!(stats(&["--allow-lazy-fetch"]), [(false, 1), (false, 1)]);
+    }
+
+    #[test]
+    fn walks_detached_heads_and_lists_unborn_ones() {
+        let tmp = tempfile::tempdir().unwrap();
+        let detached = init_repo(tmp.path(), "detached");
+        commit(&detached, "one");
+        commit(&detached, "two");
+        Command::new("git")
+            .args(["checkout", "-q", "--detach", "HEAD~1"])
+            .current_dir(&detached)
+            .status()
+            .unwrap();
+        commit(&detached, "on a detached head");
+        init_repo(tmp.path(), "fresh");
+
+        let args = Args::parse_from(["work", "--all", tmp.path().to_str().unwrap()]);
+        let got = scan(&args.scan).unwrap();
+        let mut summaries: Vec<&str> = got.commits.iter().map(|c| c.summary.as_str()).collect();
+        summaries.sort();
+        assert_eq!(summaries, ["on a detached head", "one"]);
+        assert!(got.failed.is_empty() && got.retried.is_empty());
+        assert_eq!(got.unborn.len(), 1);
+        assert!(got.unborn[0].0.ends_with("fresh"));
+        assert!(
+            got.unborn[0]
+                .1
+                .starts_with("no commits yet (HEAD is an unborn `")
+        );
+    }
+
+    #[test]
+    fn writes_each_repo_to_jsonl_once_then_errors_and_a_summary() {
+        let tmp = tempfile::tempdir().unwrap();
+        let app = init_repo(tmp.path(), "app");
+        commit(&app, "one");
+        commit(&app, "two");
+        fs::create_dir_all(tmp.path().join("broken")).unwrap();
+        fs::write(tmp.path().join("broken/.git"), "gitdir: /nowhere").unwrap();
+
+        let root = tmp.path().to_str().unwrap();
+        let args = Args::parse_from(["work", "--jsonl", "--all", "--no-dedupe", root]);
+        let mut out = Vec::new();
+        write_jsonl(&args, &mut out).unwrap();
+        let records: Vec<serde_json::Value> = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        let types: Vec<&str> = records
+            .iter()
+            .map(|r| r["type"].as_str().unwrap())
+            .collect();
+        assert_eq!(types, ["commit", "commit", "scan-error", "summary"]);
+        assert_eq!(records[0]["repo"], "app");
+        assert_eq!(records[2]["repo"], "broken");
+        let summary = &records[3];
+        assert_eq!(summary["window"], "last 7 days");
+        assert_eq!(summary["commits"], 2);
+        assert_eq!(summary["failed"], 1);
+        assert_eq!(summary["insertions"], 2);
+    }
+
+    #[test]
+    fn keeps_going_when_a_repo_is_broken() {
+        let tmp = tempfile::tempdir().unwrap();
+        let good = init_repo(tmp.path(), "good");
+        commit(&good, "fine");
+        fs::create_dir_all(tmp.path().join("broken")).unwrap();
+        fs::write(tmp.path().join("broken/.git"), "gitdir: /nowhere").unwrap();
+
+        // A missing object used to end the walk silently; now it's reported.
+        let corrupt = init_repo(tmp.path(), "corrupt");
+        commit(&corrupt, "lost");
+        let repo = Repository::open(&corrupt).unwrap();
+        let lost = repo.head().unwrap().target().unwrap().to_string();
+        commit(&corrupt, "kept");
+        fs::remove_file(
+            corrupt
+                .join(".git/objects")
+                .join(&lost[..2])
+                .join(&lost[2..]),
+        )
+        .unwrap();
+
+        let args = Args::parse_from(["work", "--all", tmp.path().to_str().unwrap()]);
+        let got = scan(&args.scan).unwrap();
+        assert_eq!(got.commits.len(), 1);
+        assert!(got.retried.is_empty());
+        assert_eq!(got.failed.len(), 2);
+        assert!(got.failed[0].0.ends_with("broken"));
+        assert!(got.failed[1].0.ends_with("corrupt"));
+    }
+
+    #[test]
+    fn scans_submodules_under_their_parent() {
+        let tmp = tempfile::tempdir().unwrap();
+        let lib = init_repo(&tmp.path().join("src"), "lib");
+        commit(&lib, "lib work");
+        let app = init_repo(&tmp.path().join("root"), "app");
+        commit(&app, "app work");
+        Command::new("git")
+            .args(["-c", "protocol.file.allow=always", "submodule", "add", "-q"])
+            .arg(&lib)
+            .arg("vendor/lib")
+            .current_dir(&app)
+            .status()
+            .unwrap();
+
+        let root = tmp.path().join("root");
+        let root = root.to_str().unwrap();
+        let got = scan(&Args::parse_from(["work", "--all", root]).scan).unwrap();
+        assert_eq!(got.repos.len(), 1);
+
+        let args = Args::parse_from(["work", "--all", "--submodules", root]);
+        let got = scan(&args.scan).unwrap();
+        let names: Vec<String> = got.repos.iter().map(|r| got.name(r)).collect();
+        assert_eq!(names, vec!["app", "app/vendor/lib"]);
+        assert!(got.commits.iter().any(|c| c.summary == "lib work"));
+    }
+
+    #[test]
+    fn dedupes_commits_across_clones() {
+        let tmp = tempfile::tempdir().unwrap();
+        let origin = init_repo(tmp.path(), "a");
+        commit(&origin, "shared");
+        Command::new("git")
+            .args(["clone", "-q", "a", "b"])
+            .current_dir(tmp.path())
+            .status()
+            .unwrap();
+
+        let root = tmp.path().to_str().unwrap();
+        let args = Args::parse_from(["work", "--all", root]);
+        let got = scan(&args.scan).unwrap();
+        assert_eq!(got.commits.len(), 1);
+        assert_eq!(got.commits[0].also_in.len(), 1);
+        assert!(got.commits[0].also_in[0].ends_with("b"));
+
+        let args = Args::parse_from(["work", "--all", "--no-dedupe", root]);
+        assert_eq!(scan(&args.scan).unwrap().commits.len(), 2);
+    }
+
+    #[test]
+    fn abandons_a_hung_fetch() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = init_repo(tmp.path(), "a");
+        // A transport that never answers, like a remote behind a dead mount.
+        for args in [
+            &["config", "protocol.ext.allow", "always"][..],
+            &["remote", "add", "origin", "ext::sleep 30"],
+        ] {
+            Command::new("git")
+                .args(args)
+                .current_dir(&repo)
+                .status()
+                .unwrap();
+        }
+
+        let started = Instant::now();
+        let err = fetch_repo(&repo, Some(started + Duration::from_millis(300))).unwrap_err();
+        assert!(err.starts_with(TIMED_OUT), "{err}");
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+
+    #[test]
+    fn sorts_by_time_repo_and_size() {
+        let line = |repo: &str, time: i64, insertions: usize| CommitLine {
+            insertions,
+            ..CommitLine::stub(repo, time)
+        };
+        let commits = vec![line("b", 30, 1), line("a", 20, 50), line("b", 10, 5)];
+        let order = |key, reverse| {
+            let mut c = commits.clone();
+            sort_commits(&mut c, key, reverse);
+            c.iter().map(|c| c.time).collect::<Vec<_>>()
+        };
+        assert_eq!(order(SortKey::Time, false), [30, 20, 10]);
+        assert_eq!(order(SortKey::Time, true), [10, 20, 30]);
+        assert_eq!(order(SortKey::Repo, false), [20, 30, 10]);
+        assert_eq!(order(SortKey::Size, false), [20, 10, 30]);
+    }
+
+    #[test]
+    fn recognizes_bots() {
+        let mut args = Args::parse_from(["work", "--all", "--no-bots", "x"]).scan;
+        let bot = |args: &ScanArgs, name: &str, email: &str| is_bot(args, Some(name), Some(email));
+        assert!(bot(
+            &args,
+            "dependabot[bot]",
+            "49699333+dependabot[bot]@users.noreply.github.com"
+        ));
+        assert!(bot(&args, "Renovate Bot", "bot@renovateapp.com"));
+        assert!(bot(&args, "GitHub Action", "action@github.com"));
+        assert!(!bot(&args, "Ana Botha", "ana@example.com"));
+
+        args.bots = vec![parse_glob("*-ci@corp.io").unwrap()];
+        assert!(bot(&args, "Release", "release-ci@CORP.io"));
+    }
+
+    #[test]
+    fn exports_warn_and_honor_strict() {
+        let mut scan = Scan::stub(&["/code"], vec![CommitLine::stub("/code/api", 0)]);
+        assert_eq!(finish_export(true, &scan, Ok(())), Ok(()));
+
+        scan.failed
+            .push((PathBuf::from("/code/bad"), "corrupted".to_string()));
+        assert_eq!(finish_export(false, &scan, Ok(())), Ok(()));
+        let err = finish_export(true, &scan, Ok(())).unwrap_err();
+        assert!(err.contains("1 repo(s) could not be scanned"), "{err}");
+        // A failed write is the error that matters.
+        assert_eq!(
+            finish_export(true, &scan, Err("work: cannot write".into())),
+            Err("work: cannot write".to_string())
+        );
+    }
+
+    #[test]
+    fn explains_the_effective_query() {
+        let tmp = tempfile::tempdir().unwrap();
+        commit(&init_repo(tmp.path(), "a"), "one");
+
+        let root = tmp.path().to_str().unwrap();
+        let args = Args::parse_from(["work", "--all", "--week", "-l", "5", "--merges", root]);
+        let got = Mutex::new(String::new());
+        let hook = |e: Event| {
+            if let Event::Found(plan) = e {
+                *got.lock().unwrap() = explain(&args, &plan);
+            }
+        };
+        scan_with(&args.scan, Some(&hook)).unwrap();
+        let got = got.into_inner().unwrap();
+        assert!(
+            got.starts_with("Scanning 1 repo(s) found up to 3 level(s) below"),
+            "{got}"
+        );
+        assert!(got.contains("Window: this week, from "), "{got}");
+        assert!(got.contains(" UTC) until now"), "{got}");
+        assert!(got.contains("by anyone (--all); merges included"), "{got}");
+        assert!(got.ends_with("At most 5 commits are shown."), "{got}");
+    }
+
+    #[test]
+    fn dedupes_cherry_picks_by_patch_id() {
+        let tmp = tempfile::tempdir().unwrap();
+        let a = init_repo(tmp.path(), "a");
+        commit(&a, "base");
+        Command::new("git")
+            .args(["clone", "-q", "a", "b"])
+            .current_dir(tmp.path())
+            .status()
+            .unwrap();
+        for (dir, msg) in [
+            (&a, "fix bug"),
+            (&tmp.path().join("b"), "fix bug (backport)"),
+        ] {
+            fs::write(dir.join("file.txt"), "fixed").unwrap();
+            Command::new("git")
+                .args([
+                    "-c",
+                    "user.name=T",
+                    "-c",
+                    "user.email=t@x",
+                    "commit",
+                    "-qam",
+                    msg,
+                ])
+                .current_dir(dir)
+                .status()
+                .unwrap();
+        }
+
+        let root = tmp.path().to_str().unwrap();
+        let args = Args::parse_from(["work", "--all", root]);
+        assert_eq!(scan(&args.scan).unwrap().commits.len(), 3);
+
+        let args = Args::parse_from(["work", "--all", "--dedupe-patches", root]);
+        let got = scan(&args.scan).unwrap();
+        assert_eq!(got.commits.len(), 2);
+    }
+
+    #[test]
+    fn stat_excludes_skip_lockfiles() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = init_repo(tmp.path(), "a");
+        fs::create_dir_all(repo.join("web")).unwrap();
+        fs::write(repo.join("web/package-lock.json"), "1\n2\n3\n4\n").unwrap();
+        fs::write(repo.join("gen.rs"), "1\n2\n").unwrap();
+        fs::write(repo.join(".gitattributes"), "gen.rs linguist-generated\n").unwrap();
+        commit(&repo, "real\n");
+
+        let opened = Repository::open(&repo).unwrap();
+        let head = opened.head().unwrap().peel_to_commit().unwrap();
+
+        let args = Args::parse_from(["work", "x"]);
+        let stats = diff_stats(&opened, &head, &stat_pathspecs(&args.scan), false, None);
+        // file.txt + .gitattributes only
+        assert_eq!((stats.files, stats.insertions), (2, 2));
+
+        let args = Args::parse_from(["work", "--no-default-stat-excludes", "x"]);
+        let stats = diff_stats(&opened, &head, &stat_pathspecs(&args.scan), false, None);
+        assert_eq!((stats.files, stats.insertions), (3, 6));
+
+        // A reindent: one real change among the whitespace ones.
+        commit(&repo, "real\n");
+        fs::write(repo.join("gen.rs"), "1\n2\n").unwrap();
+        commit(&repo, "  real\n\tmore\n");
+        let head = opened.head().unwrap().peel_to_commit().unwrap();
+        let specs = stat_pathspecs(&args.scan);
+        let lines = |ws| {
+            let stats = diff_stats(&opened, &head, &specs, ws, None);
+            (stats.files, stats.insertions, stats.deletions)
+        };
+        assert_eq!(lines(false), (1, 2, 1));
+        assert_eq!(lines(true), (1, 1, 0));
+    }
+
+    #[test]
+    fn collects_tag_and_branch_events() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = init_repo(tmp.path(), "a");
+        commit(&repo, "one");
+        Command::new("git")
+            .args(["tag", "-a", "v1", "-m", "first release"])
+            .current_dir(&repo)
+            .status()
+            .unwrap();
+        Command::new("git")
+            .args(["branch", "topic"])
+            .current_dir(&repo)
+            .status()
+            .unwrap();
+
+        let args = Args::parse_from(["work", "--all", "--events", "x"]);
+        let since = chrono::Local::now().timestamp() - 60 * 60;
+        let id = Identity {
+            name: None,
+            email: None,
+            other_emails: Vec::new(),
+        };
+        let got = collect_ref_events(&repo, since, None, &id, &args.scan).unwrap();
+
+        assert!(
+            got.iter()
+                .any(|e| matches!(&e.kind, RefEventKind::Tag { name, .. } if name == "v1"))
+        );
+        assert!(got.iter().any(
+            |e| matches!(&e.kind, RefEventKind::BranchCreated { branch, .. } if branch == "topic")
+        ));
+    }
+}
@@ -0,0 +1,235 @@
+use crate::{
+    Discovery, Scan, ScanArgs, Unopened, discover, git_command, open_repo, repo_name,
+    report_unopened, report_unreadable,
+};
+use rayon::prelude::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Loose objects past which revwalks start paying for it. git's own
+/// `gc.auto` waits for 6700; scans notice well before that.
+const LOOSE_OBJECT_LIMIT: usize = 2000;
+
+/// Same as git's `gc.autoPackLimit`.
+const PACK_LIMIT: usize = 50;
+
+/// Below this many objects a missing commit-graph costs nothing noticeable.
+const COMMIT_GRAPH_MIN_OBJECTS: usize = 10_000;
+
+#[derive(clap::Args, Debug)]
+pub struct MaintainArgs {
+    #[command(flatten)]
+    pub scan: ScanArgs,
+
+    /// Run maintenance on every repo, not just the ones that look neglected
+    #[arg(long)]
+    pub force: bool,
+
+    /// List the repos that would be maintained without touching them
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+/// The state of a repo's object store, as far as scan speed is concerned.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ObjectHealth {
+    pub loose: usize,
+    pub packs: usize,
+    pub packed_objects: usize,
+    pub commit_graph: bool,
+}
+
+impl ObjectHealth {
+    /// What's wrong, in words, or nothing if the store is in decent shape.
+    pub fn problems(&self) -> Vec<String> {
+        let mut out = Vec::new();
+        if self.loose >= LOOSE_OBJECT_LIMIT {
+            out.push(format!("{} loose objects", self.loose));
+        }
+        if self.packs > PACK_LIMIT {
+            out.push(format!("{} packs", self.packs));
+        }
+        if !self.commit_graph && self.loose + self.packed_objects >= COMMIT_GRAPH_MIN_OBJECTS {
+            out.push("no commit-graph".to_string());
+        }
+        out
+    }
+}
+
+/// Object count from a v2 pack index: the last fanout entry.
+fn idx_object_count(idx: &Path) -> usize {
+    let Ok(bytes) = fs::read(idx) else {
+        return 0;
+    };
+    if bytes.len() < 8 + 256 * 4 || bytes[..4] != [0xff, b't', b'O', b'c'] {
+        return 0;
+    }
+    let last = &bytes[8 + 255 * 4..8 + 256 * 4];
+    u32::from_be_bytes([last[0], last[1], last[2], last[3]]) as usize
+}
+
+/// Looks at the object directory directly rather than asking git, so it
+/// costs a few hundred `readdir`s per repo and no subprocesses.
+pub fn object_health(repo_path: &Path) -> Result<ObjectHealth, String> {
+    let repo = open_repo(repo_path)?;
+    // Worktrees share the main repo's objects.
+    let objects = repo.commondir().join("objects");
+
+    let mut health = ObjectHealth::default();
+    let entries =
+        fs::read_dir(&objects).map_err(|e| format!("cannot read {}: {e}", objects.display()))?;
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name.len() == 2 && name.chars().all(|c| c.is_ascii_hexdigit()) {
+            health.loose += fs::read_dir(entry.path()).map_or(0, |d| d.count());
+        }
+    }
+    if let Ok(packs) = fs::read_dir(objects.join("pack")) {
+        for entry in packs.flatten() {
+            let path = entry.path();
+            if path.extension().is_some_and(|e| e == "idx") {
+                health.packs += 1;
+                health.packed_objects += idx_object_count(&path);
+            }
+        }
+    }
+    let info = objects.join("info");
+    health.commit_graph =
+        info.join("commit-graph").is_file() || info.join("commit-graphs").is_dir();
+    Ok(health)
+}
+
+/// Repos (and their problems) that would scan faster after maintenance,
+/// and the ones that couldn't be looked at, with why.
+fn neglected(repos: &[PathBuf]) -> (Vec<(PathBuf, Vec<String>)>, Unopened) {
+    let checked: Vec<(&PathBuf, Result<ObjectHealth, String>)> =
+        repos.par_iter().map(|r| (r, object_health(r))).collect();
+    let mut out = Vec::new();
+    let mut unopened = Vec::new();
+    for (r, health) in checked {
+        match health.map(|h| h.problems()) {
+            Ok(problems) if problems.is_empty() => {}
+            Ok(problems) => out.push((r.clone(), problems)),
+            Err(e) => unopened.push((r.clone(), e)),
+        }
+    }
+    (out, unopened)
+}
+
+/// `--check-maintenance`: lists neglected repos on stderr and points at
+/// `work maintain`. Repos that won't open are among the scan's failures
+/// already, so they aren't listed twice.
+pub fn report_maintenance(scan: &Scan) {
+    let (repos, _) = neglected(&scan.repos);
+    if repos.is_empty() {
+        return;
+    }
+    eprintln!(
+        "work: {} repo(s) would scan faster after `work maintain`:",
+        repos.len()
+    );
+    for (r, problems) in &repos {
+        eprintln!("  {}: {}", scan.name(r), problems.join(", "));
+    }
+}
+
+fn run_maintenance(repo: &Path) -> Result<(), String> {
+    let out = git_command()
+        .args(["maintenance", "run", "--quiet"])
+        .current_dir(repo)
+        .output()
+        .map_err(|e| format!("cannot run git maintenance: {e}"))?;
+    if out.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&out.stderr).trim().to_string())
+    }
+}
+
+/// Runs `git maintenance run` across the neglected repos (or all of them
+/// with `--force`) in parallel.
+pub fn maintain(args: MaintainArgs) -> Result<(), String> {
+    let Discovery {
+        roots,
+        repos,
+        unreadable,
+    } = discover(&args.scan)?;
+    report_unreadable(&unreadable);
+    let total = repos.len();
+    let mut unopened = Vec::new();
+    let targets: Vec<PathBuf> = if args.force {
+        repos
+    } else {
+        let (found, skipped) = neglected(&repos);
+        unopened = skipped;
+        found.into_iter().map(|(r, _)| r).collect()
+    };
+    report_unopened(&roots, &unopened);
+    if targets.is_empty() {
+        match unopened.len() {
+            0 => println!("All {total} repos look fine"),
+            n => println!("The other {} repos look fine", total - n),
+        }
+        return Ok(());
+    }
+
+    if args.dry_run {
+        for r in &targets {
+            println!("{}", repo_name(&roots, r));
+        }
+        return Ok(());
+    }
+
+    let results: Vec<(PathBuf, Result<(), String>)> = targets
+        .par_iter()
+        .map(|r| (r.clone(), run_maintenance(r)))
+        .collect();
+
+    let mut failed = 0;
+    for (r, res) in &results {
+        match res {
+            Ok(()) => println!("{}: done", repo_name(&roots, r)),
+            Err(e) => {
+                failed += 1;
+                eprintln!("{}: {e}", repo_name(&roots, r));
+            }
+        }
+    }
+    if failed > 0 {
+        return Err(format!(
+            "work: maintenance failed in {failed} of {} repos",
+            results.len()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_loose_objects_and_flags_neglect() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = git2::Repository::init(tmp.path()).unwrap();
+        for i in 0..5 {
+            repo.blob(format!("blob {i}").as_bytes()).unwrap();
+        }
+
+        let health = object_health(tmp.path()).unwrap();
+        assert_eq!(health.loose, 5);
+        assert_eq!(health.packs, 0);
+        assert!(!health.commit_graph);
+        assert!(health.problems().is_empty());
+
+        let neglected = ObjectHealth {
+            loose: 12000,
+            ..health
+        };
+        assert_eq!(
+            neglected.problems(),
+            vec!["12000 loose objects", "no commit-graph"]
+        );
+    }
+}
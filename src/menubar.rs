@@ -0,0 +1,114 @@
+use crate::{Scan, ScanArgs, format_clock_local, scan};
+use std::io::{self, Write};
+
+#[derive(clap::Args, Debug)]
+pub struct MenubarArgs {
+    #[command(flatten)]
+    pub scan: ScanArgs,
+
+    /// Max number of commits in the dropdown
+    #[arg(short, long, default_value = "20")]
+    pub limit: usize,
+}
+
+/// `|` separates an item from its parameters in the plugin format.
+fn item_text(s: &str) -> String {
+    s.replace('|', "¦")
+}
+
+/// Prints an xbar / SwiftBar / Argos plugin body: the commit count as the
+/// menu bar title, then a dropdown of recent commits. Errors are shown in
+/// the dropdown rather than failing, since the host app would just show a
+/// broken icon.
+pub fn menubar(mut args: MenubarArgs) -> Result<(), String> {
+    args.scan.today_by_default();
+    let mut out = io::stdout().lock();
+    let written = match scan(&args.scan) {
+        Ok(scan) => write_plugin(&mut out, &scan, args.limit),
+        Err(e) => write_error(&mut out, &e),
+    };
+    written.map_err(|e| format!("work: cannot write output: {e}"))
+}
+
+fn write_plugin(out: &mut dyn Write, scan: &Scan, limit: usize) -> io::Result<()> {
+    writeln!(out, "⎇ {}", scan.commits.len())?;
+    writeln!(out, "---")?;
+
+    if scan.commits.is_empty() {
+        writeln!(out, "No commits yet")?;
+    }
+
+    for c in scan.commits.iter().take(limit) {
+        // The date is implied by the window.
+        writeln!(
+            out,
+            "{}  {}  {} | font=Menlo trim=false",
+            format_clock_local(c.time),
+            item_text(&scan.rel(&c.repo).display().to_string()),
+            item_text(&c.summary)
+        )?;
+    }
+
+    let (ins, del) = scan.commits.iter().fold((0usize, 0usize), |(i, d), c| {
+        (
+            i.saturating_add(c.insertions),
+            d.saturating_add(c.deletions),
+        )
+    });
+    writeln!(out, "---")?;
+    writeln!(out, "+{ins} -{del} | color=gray")?;
+    writeln!(out, "Refresh | refresh=true")
+}
+
+fn write_error(out: &mut dyn Write, e: &str) -> io::Result<()> {
+    writeln!(out, "⎇ !")?;
+    writeln!(out, "---")?;
+    writeln!(out, "{}", item_text(e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CommitLine;
+
+    fn plugin(scan: &Scan, limit: usize) -> String {
+        let mut out = Vec::new();
+        write_plugin(&mut out, scan, limit).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn writes_the_plugin_format() {
+        let mut c = CommitLine::stub("/code/a|b", 1_772_452_800);
+        c.summary = "Split a | b".to_string();
+        c.insertions = 5;
+        c.deletions = 2;
+        let mut older = CommitLine::stub("/code/api", 1_772_449_200);
+        older.insertions = 1;
+        let scan = Scan::stub(&["/code"], vec![c, older]);
+
+        let clock = format_clock_local(1_772_452_800);
+        assert_eq!(
+            plugin(&scan, 1),
+            format!(
+                "⎇ 2\n---\n{clock}  a¦b  Split a ¦ b | font=Menlo trim=false\n---\n+6 -2 | color=gray\nRefresh | refresh=true\n"
+            )
+        );
+
+        let empty = Scan::stub(&["/code"], Vec::new());
+        assert_eq!(
+            plugin(&empty, 20),
+            "⎇ 0\n---\nNo commits yet\n---\n+0 -0 | color=gray\nRefresh | refresh=true\n"
+        );
+    }
+
+    #[test]
+    fn shows_errors_in_the_dropdown() {
+        let mut out = Vec::new();
+        write_error(&mut out, "work: no repos | anywhere").unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "⎇ !\n---\nwork: no repos ¦ anywhere\n"
+        );
+    }
+}
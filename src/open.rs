@@ -0,0 +1,171 @@
+use crate::forge::{commit_url, repo_web_url};
+use crate::{ScanArgs, Unopened, discover, open_repo, report_unopened, report_unreadable};
+use git2::Oid;
+use rayon::prelude::*;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+#[derive(clap::Args, Debug)]
+pub struct OpenArgs {
+    /// Commit hash, or a unique prefix of one like the log's short hashes
+    #[arg(value_name = "HASH")]
+    pub hash: String,
+
+    #[command(flatten)]
+    pub scan: ScanArgs,
+
+    /// Print the commit's URL instead of opening it
+    #[arg(long)]
+    pub print_url: bool,
+}
+
+/// Every repo that has a commit starting with `prefix`. Clones of one repo
+/// all match; that's the same commit, not an ambiguity. Repos that won't
+/// open come back apart, with why, since the commit may be in one of them.
+fn locate(repos: &[PathBuf], prefix: &str) -> (Vec<(PathBuf, Oid)>, Unopened) {
+    let looked: Vec<_> = repos
+        .par_iter()
+        .map(|r| {
+            let repo = open_repo(r).map_err(|e| (r.clone(), e))?;
+            let commit = repo.find_commit_by_prefix(prefix).ok();
+            Ok(commit.map(|c| (r.clone(), c.id())))
+        })
+        .collect();
+    let mut found = Vec::new();
+    let mut unopened = Vec::new();
+    for l in looked {
+        match l {
+            Ok(hit) => found.extend(hit),
+            Err(e) => unopened.push(e),
+        }
+    }
+    (found, unopened)
+}
+
+fn open_in_browser(url: &str) -> Result<(), String> {
+    let opener = if cfg!(target_os = "macos") {
+        "open"
+    } else {
+        "xdg-open"
+    };
+    let status = Command::new(opener)
+        .arg(url)
+        .status()
+        .map_err(|e| format!("work: cannot run {opener}: {e} (try --print-url)"))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("work: {opener} failed ({status})"))
+    }
+}
+
+/// A commit found by hash, and where.
+pub struct Located {
+    /// The scan roots, for naming the repos.
+    pub roots: Vec<PathBuf>,
+    /// Every discovered repo that has the commit.
+    pub repos: Vec<PathBuf>,
+    pub oid: Oid,
+}
+
+/// Finds the commit `hash` (a full hash or a prefix of 4+ hex digits) in
+/// the repos `scan` discovers. A prefix of different commits is an error
+/// listing them.
+pub fn find(scan: &ScanArgs, hash: &str) -> Result<Located, String> {
+    let prefix = hash.trim();
+    if prefix.len() < 4 || !prefix.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!(
+            "work: '{prefix}' is not a commit hash (4+ hex digits)"
+        ));
+    }
+    let discovery = discover(scan)?;
+    report_unreadable(&discovery.unreadable);
+
+    let (found, unopened) = locate(&discovery.repos, prefix);
+    report_unopened(&discovery.roots, &unopened);
+    let mut oids: Vec<Oid> = found.iter().map(|(_, oid)| *oid).collect();
+    oids.sort();
+    oids.dedup();
+    let oid = match oids.as_slice() {
+        [] => return Err(format!("work: no commit {prefix} in the scanned repos")),
+        [oid] => *oid,
+        _ => {
+            let list = oids
+                .iter()
+                .map(|o| o.to_string()[..12].to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            return Err(format!(
+                "work: {prefix} is ambiguous ({list}); give more of the hash"
+            ));
+        }
+    };
+    Ok(Located {
+        roots: discovery.roots,
+        repos: found.into_iter().map(|(r, _)| r).collect(),
+        oid,
+    })
+}
+
+/// `work open <hash>`: finds the commit in the discovered repos and opens
+/// it on the forge `origin` points at.
+pub fn open(args: OpenArgs) -> Result<(), String> {
+    let found = find(&args.scan, &args.hash)?;
+    let hash = found.oid.to_string();
+    let mut why = None;
+    let url = found
+        .repos
+        .iter()
+        .find_map(|r| match repo_web_url(r) {
+            Ok(web) => web,
+            Err(e) => {
+                why.get_or_insert_with(|| format!("work: cannot open {}: {e}", r.display()));
+                None
+            }
+        })
+        .map(|web| commit_url(&web, &hash))
+        .ok_or_else(|| {
+            let repo: &Path = &found.repos[0];
+            why.unwrap_or_else(|| {
+                format!(
+                    "work: {} has no remote with a web page for {}",
+                    repo.display(),
+                    &hash[..7]
+                )
+            })
+        })?;
+
+    if args.print_url {
+        println!("{url}");
+        Ok(())
+    } else {
+        open_in_browser(&url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use work::fixtures::FixtureRepo;
+
+    #[test]
+    fn locates_a_commit_by_prefix() {
+        let tmp = tempfile::tempdir().unwrap();
+        let a = FixtureRepo::init(&tmp.path().join("a")).unwrap();
+        let b = FixtureRepo::init(&tmp.path().join("b")).unwrap();
+        let oid = a.commit(1_772_452_800, "in a", &[("a.txt", "a")]).unwrap();
+        b.commit(1_772_452_800, "in b", &[("b.txt", "b")]).unwrap();
+
+        let repos = vec![a.path().to_path_buf(), b.path().to_path_buf()];
+        let (got, unopened) = locate(&repos, &oid.to_string()[..7]);
+        assert_eq!(got, vec![(a.path().to_path_buf(), oid)]);
+        assert!(unopened.is_empty());
+        assert!(locate(&repos, "0000000").0.is_empty());
+
+        let gone = vec![tmp.path().join("gone")];
+        let (got, unopened) = locate(&gone, "0000000");
+        assert!(got.is_empty());
+        assert_eq!(unopened.len(), 1);
+        assert_eq!(unopened[0].0, gone[0]);
+    }
+}
@@ -0,0 +1,137 @@
+use crate::render::Renderer;
+use crate::{
+    Args, Column, CommitLine, Scan, format_bytes, format_time_local, matches_identity,
+    signature_counts, write_body,
+};
+use std::io::{self, Write};
+
+/// `--plain`: `label: value` pairs in `--columns` order, one commit per
+/// line, so a screen reader or braille display gets words instead of
+/// aligned columns and escape codes.
+pub struct Plain<'a> {
+    pub args: &'a Args,
+}
+
+impl Renderer for Plain<'_> {
+    fn render(&self, out: &mut dyn Write, scan: &Scan, commits: &[&CommitLine]) -> io::Result<()> {
+        let args = self.args;
+        let mut mine = 0;
+        for c in commits {
+            let mut fields: Vec<String> = args
+                .columns
+                .iter()
+                .map(|col| match col {
+                    Column::Time => format!("time: {}", format_time_local(c.time)),
+                    Column::Repo => format!("repo: {}", scan.name(&c.repo)),
+                    Column::Hash => format!("hash: {}", c.short),
+                    Column::Stats if c.no_stats => "stats: unavailable".to_string(),
+                    Column::Stats => {
+                        let mut stats = format!(
+                            "files: {}, added: {}, removed: {}",
+                            c.files, c.insertions, c.deletions
+                        );
+                        if c.lfs_files > 0 {
+                            stats += &format!(
+                                ", lfs files: {}, lfs bytes: {}",
+                                c.lfs_files,
+                                format_bytes(c.lfs_bytes)
+                            );
+                        }
+                        if c.mode_only {
+                            stats += ", mode changes only";
+                        }
+                        stats
+                    }
+                    Column::Summary => format!("summary: {}", c.summary),
+                    Column::Author => format!("author: {}", c.author_name),
+                })
+                .collect();
+            if args.highlight_me {
+                let yours = matches_identity(&scan.id, Some(&c.author_name), Some(&c.author_email));
+                mine += usize::from(yours);
+                fields.push(format!("yours: {}", if yours { "yes" } else { "no" }));
+            }
+            if let Some(s) = c.signature.filter(|_| args.show_signatures) {
+                fields.push(format!("signature: {}", s.word()));
+            }
+            writeln!(out, "{}", fields.join(", "))?;
+            if args.full_message {
+                write_body(out, c, "")?;
+            }
+            if args.notes
+                && let Some(note) = &c.note
+            {
+                for line in note.lines() {
+                    writeln!(out, "note: {line}")?;
+                }
+            }
+        }
+        let added: usize = commits.iter().map(|c| c.insertions).sum();
+        let removed: usize = commits.iter().map(|c| c.deletions).sum();
+        writeln!(
+            out,
+            "commits shown: {} ({}), lines added: {added}, lines removed: {removed}",
+            commits.len(),
+            args.scan.window().label()
+        )?;
+        if args.highlight_me {
+            writeln!(out, "yours: {mine} of {}", commits.len())?;
+        }
+        if args.show_signatures {
+            let [good, bad, unsigned] = signature_counts(commits);
+            writeln!(
+                out,
+                "signatures: {good} good, {bad} bad, {unsigned} unsigned"
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[test]
+    fn labels_every_field() {
+        let scan = Scan::stub(
+            &["/code"],
+            vec![
+                CommitLine {
+                    summary: "Fix login".to_string(),
+                    files: 2,
+                    insertions: 3,
+                    deletions: 1,
+                    note: Some("Reviewed".to_string()),
+                    ..CommitLine::stub("/code/api", 1_772_452_800)
+                },
+                CommitLine {
+                    summary: "Vendor assets".to_string(),
+                    no_stats: true,
+                    ..CommitLine::stub("/code/web", 1_772_452_800)
+                },
+            ],
+        );
+        let commits: Vec<&CommitLine> = scan.commits.iter().collect();
+        let args = Args::parse_from([
+            "work",
+            "--plain",
+            "--notes",
+            "--columns",
+            "repo,stats,summary",
+            "x",
+        ]);
+        let mut out = Vec::new();
+        Plain { args: &args }
+            .render(&mut out, &scan, &commits)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "repo: api, files: 2, added: 3, removed: 1, summary: Fix login\n\
+             note: Reviewed\n\
+             repo: web, stats: unavailable, summary: Vendor assets\n\
+             commits shown: 2 (last 7 days), lines added: 3, lines removed: 1\n"
+        );
+    }
+}
@@ -0,0 +1,295 @@
+use crate::render::Renderer;
+use crate::{
+    Args, Column, CommitLine, Scan, format_bytes, format_time_local, matches_identity, pad_visible,
+    paint, signature_counts, signing, terminal_width, truncate_visible, visible_width, wrap_words,
+    write_body, write_goal_bars, write_note,
+};
+use std::io::{self, Write};
+
+/// The log for a terminal: `--columns` aligned and colored, summaries cut
+/// or wrapped to the width, then the totals.
+pub struct Pretty<'a> {
+    pub args: &'a Args,
+}
+
+impl Renderer for Pretty<'_> {
+    fn render(&self, out: &mut dyn Write, scan: &Scan, commits: &[&CommitLine]) -> io::Result<()> {
+        let args = self.args;
+        let mut total_ins: usize = 0;
+        let mut total_del: usize = 0;
+
+        // For pretty alignment we compute widths from the *displayed* commits.
+        let repo_width = commits
+            .iter()
+            .map(|c| visible_width(&scan.rel(&c.repo).display().to_string()))
+            .max()
+            .unwrap_or(0);
+
+        let files_width = commits
+            .iter()
+            .map(|c| c.files.to_string().len())
+            .max()
+            .unwrap_or(1);
+        let ins_width = commits
+            .iter()
+            .map(|c| c.insertions.to_string().len())
+            .max()
+            .unwrap_or(1);
+        let del_width = commits
+            .iter()
+            .map(|c| c.deletions.to_string().len())
+            .max()
+            .unwrap_or(1);
+
+        let author_width = commits
+            .iter()
+            .map(|c| visible_width(&c.author_name))
+            .max()
+            .unwrap_or(0);
+
+        let (mut mine, mut mine_ins, mut mine_del) = (0, 0usize, 0usize);
+        let width = args.max_width.map(usize::from).or_else(terminal_width);
+
+        let (mut lfs_files, mut lfs_bytes) = (0, 0i64);
+        for c in commits {
+            total_ins = total_ins.saturating_add(c.insertions);
+            total_del = total_del.saturating_add(c.deletions);
+            lfs_files += c.lfs_files;
+            lfs_bytes += c.lfs_bytes;
+            let is_mine = args.highlight_me
+                && matches_identity(&scan.id, Some(&c.author_name), Some(&c.author_email));
+            if is_mine {
+                mine += 1;
+                mine_ins = mine_ins.saturating_add(c.insertions);
+                mine_del = mine_del.saturating_add(c.deletions);
+            }
+
+            let cells: Vec<String> = args
+                .columns
+                .iter()
+                .enumerate()
+                .map(|(i, col)| {
+                    // The last column isn't padded, so lines don't end in spaces.
+                    let pad = |s: String, width: usize| {
+                        if i + 1 == args.columns.len() {
+                            s
+                        } else {
+                            pad_visible(&s, width)
+                        }
+                    };
+                    match col {
+                        Column::Time => format_time_local(c.time),
+                        // Colors:
+                        // - repo: bold
+                        // - hash: dim
+                        // - +ins: green
+                        // - -del: red
+                        Column::Repo => paint(
+                            "1",
+                            pad(scan.rel(&c.repo).display().to_string(), repo_width),
+                        ),
+                        Column::Hash => paint("2", &c.short),
+                        Column::Stats => {
+                            // Align by padding *before* the sign, not between sign and digits.
+                            let [files, plus, minus] = c.stat_cells();
+                            let files = format!("{files:>w$}", w = files_width + 1);
+                            let plus = format!("{plus:>w$}", w = ins_width + 1);
+                            let minus = format!("{minus:>w$}", w = del_width + 1);
+                            format!("{files} {} {}", paint("32", plus), paint("31", minus))
+                        }
+                        Column::Summary if is_mine && c.also_in.is_empty() => {
+                            paint("36", &c.summary)
+                        }
+                        Column::Summary if c.also_in.is_empty() => c.summary.clone(),
+                        Column::Summary => {
+                            let paths = c
+                                .also_in
+                                .iter()
+                                .map(|p| scan.rel(p).display().to_string())
+                                .collect::<Vec<_>>()
+                                .join(", ");
+                            format!(
+                                "{}  {}",
+                                c.summary,
+                                paint("2", format!("(also in {paths})"))
+                            )
+                        }
+                        Column::Author => paint("35", pad(c.author_name.clone(), author_width)),
+                    }
+                })
+                .zip(&args.columns)
+                .map(|(cell, col)| {
+                    let tags: Vec<String> = c
+                        .lfs_summary()
+                        .into_iter()
+                        .chain(c.mode_only.then(|| "mode only".to_string()))
+                        .collect();
+                    if *col == Column::Summary && !tags.is_empty() {
+                        format!("{cell}  {}", paint("2", format!("({})", tags.join("; "))))
+                    } else {
+                        cell
+                    }
+                })
+                .collect();
+
+            // A marker as well as the color, so it survives copy and paste.
+            let mut mark = match (args.highlight_me, is_mine) {
+                (false, _) => String::new(),
+                (true, true) => "* ".to_string(),
+                (true, false) => "  ".to_string(),
+            };
+            if let Some(s) = c.signature.filter(|_| args.show_signatures) {
+                let color = match s {
+                    signing::Signature::Good => "32",
+                    signing::Signature::Bad => "31",
+                    signing::Signature::Unsigned => "2",
+                };
+                mark += &format!("{} ", paint(color, s.mark()));
+            }
+            let line = format!("{mark}{}", cells.join("  "));
+            let summary = args.columns.iter().position(|c| *c == Column::Summary);
+            match (width, summary) {
+                (Some(width), Some(i)) if args.wrap && visible_width(&line) > width => {
+                    let meta: Vec<&str> = cells
+                        .iter()
+                        .enumerate()
+                        .filter(|(j, _)| *j != i)
+                        .map(|(_, cell)| cell.trim_end())
+                        .collect();
+                    writeln!(out, "{mark}{}", meta.join("  "))?;
+                    for l in wrap_words(&cells[i], "    ", width) {
+                        writeln!(out, "{l}")?;
+                    }
+                }
+                // Everything but the summary keeps its place; the summary
+                // gets what's left, but never fewer than 10 columns.
+                (Some(width), Some(i)) if !args.no_truncate && visible_width(&line) > width => {
+                    let room =
+                        width.saturating_sub(visible_width(&line) - visible_width(&cells[i]));
+                    let mut cells = cells.clone();
+                    cells[i] = truncate_visible(&cells[i], room.max(10));
+                    writeln!(out, "{mark}{}", cells.join("  "))?;
+                }
+                _ => writeln!(out, "{line}")?,
+            }
+            if args.full_message {
+                write_body(out, c, "    ")?;
+            }
+            if args.notes {
+                write_note(out, c, "    ")?;
+            }
+        }
+
+        writeln!(
+            out,
+            "\n{} commits shown ({})",
+            commits.len(),
+            args.scan.window().label()
+        )?;
+        writeln!(
+            out,
+            "Total LoC: {} {}",
+            paint("32", format!("+{total_ins}")),
+            paint("31", format!("-{total_del}"))
+        )?;
+        if lfs_files > 0 {
+            writeln!(
+                out,
+                "LFS: {lfs_files} file change(s), {}",
+                format_bytes(lfs_bytes)
+            )?;
+        }
+        if args.highlight_me {
+            let share = |part: usize, whole: usize| match whole {
+                0 => 0,
+                _ => (part as f64 * 100.0 / whole as f64).round() as usize,
+            };
+            writeln!(
+                out,
+                "Yours (*): {mine} commits ({}%), {} {} ({}% of lines)",
+                share(mine, commits.len()),
+                paint("32", format!("+{mine_ins}")),
+                paint("31", format!("-{mine_del}")),
+                share(
+                    mine_ins.saturating_add(mine_del),
+                    total_ins.saturating_add(total_del)
+                )
+            )?;
+        }
+        if args.show_signatures {
+            let [good, bad, unsigned] = signature_counts(commits);
+            writeln!(
+                out,
+                "Signatures: {}, {}, - {unsigned} unsigned",
+                paint("32", format!("✓ {good} good")),
+                paint("31", format!("✗ {bad} bad")),
+            )?;
+        }
+        if args.goal_bar {
+            writeln!(out)?;
+            write_goal_bars(out, &args.goal, &scan.commits)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Identity;
+    use clap::Parser;
+
+    fn render(flags: &[&str], scan: &Scan) -> String {
+        crate::COLOR.store(false, std::sync::atomic::Ordering::Relaxed);
+        let args = Args::parse_from([&["work"], flags, &["x"]].concat());
+        let commits: Vec<&CommitLine> = scan.commits.iter().collect();
+        let mut out = Vec::new();
+        Pretty { args: &args }
+            .render(&mut out, scan, &commits)
+            .unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn aligns_columns_and_adds_totals() {
+        let line = |repo: &str, summary: &str, author: &str, ins: usize| CommitLine {
+            summary: summary.to_string(),
+            author_name: author.to_string(),
+            author_email: format!("{}@example.com", author.to_lowercase()),
+            files: 1,
+            insertions: ins,
+            deletions: 1,
+            ..CommitLine::stub(repo, 1_772_452_800)
+        };
+        let mut scan = Scan::stub(
+            &["/code"],
+            vec![
+                line("/code/api", "Fix login", "Kaf", 3),
+                line("/code/web-app", "Add form", "Ana", 12),
+            ],
+        );
+        let text = render(&["--columns", "repo,stats,summary"], &scan);
+        assert_eq!(
+            text,
+            "api      1f  +3 -1  Fix login\n\
+             web-app  1f +12 -1  Add form\n\
+             \n\
+             2 commits shown (last 7 days)\n\
+             Total LoC: +15 -2\n"
+        );
+
+        scan.id = Identity {
+            name: None,
+            email: Some("kaf@example.com".to_string()),
+            other_emails: Vec::new(),
+        };
+        let text = render(
+            &["--columns", "author,summary", "--highlight-me", "--all"],
+            &scan,
+        );
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "* Kaf  Fix login");
+        assert_eq!(lines[1], "  Ana  Add form");
+        assert_eq!(lines[5], "Yours (*): 1 commits (50%), +3 -1 (24% of lines)");
+    }
+}
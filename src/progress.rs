@@ -0,0 +1,76 @@
+use std::io::{IsTerminal, Write};
+use std::sync::Mutex;
+
+/// Longest repo name shown before it's cut down to its tail.
+const NAME_MAX: usize = 50;
+
+const SPINNER: &[char] = &['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+struct State {
+    done: usize,
+    frame: usize,
+}
+
+/// A one-line `⠹ [12/340] group/repo` status on stderr while repos are
+/// scanned in parallel. Does nothing when stderr isn't a terminal, so pipes,
+/// cron jobs and status bars never see it.
+pub struct Progress {
+    total: usize,
+    enabled: bool,
+    state: Mutex<State>,
+}
+
+impl Progress {
+    pub fn new(total: usize, quiet: bool) -> Self {
+        Progress {
+            total,
+            enabled: !quiet && std::io::stderr().is_terminal(),
+            state: Mutex::new(State { done: 0, frame: 0 }),
+        }
+    }
+
+    /// Shows `name` as the repo being worked on.
+    pub fn start(&self, name: &str) {
+        if self.enabled {
+            let mut st = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            self.draw(&mut st, name);
+        }
+    }
+
+    pub fn finish(&self, name: &str) {
+        if self.enabled {
+            let mut st = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            st.done += 1;
+            self.draw(&mut st, name);
+        }
+    }
+
+    fn draw(&self, st: &mut State, name: &str) {
+        st.frame = (st.frame + 1) % SPINNER.len();
+        // A wrapped line can't be erased with `\r`, so keep long names short.
+        let chars = name.chars().count();
+        let name: String = if chars > NAME_MAX {
+            let tail: String = name.chars().skip(chars - NAME_MAX + 1).collect();
+            format!("…{tail}")
+        } else {
+            name.to_string()
+        };
+        let mut err = std::io::stderr().lock();
+        let _ = write!(
+            err,
+            "\r\x1b[K{} [{}/{}] {name}",
+            SPINNER[st.frame], st.done, self.total
+        );
+        let _ = err.flush();
+    }
+
+    /// Erases the status line so the real output starts on a clean line.
+    pub fn clear(&self) {
+        if self.enabled {
+            let _guard = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            let mut err = std::io::stderr().lock();
+            let _ = write!(err, "\r\x1b[K");
+            let _ = err.flush();
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use crate::{CommitLine, ScanArgs, paint, report_warnings, scan, zoned};
+use chrono::{Datelike, Timelike};
+
+#[derive(clap::Args, Debug)]
+pub struct PunchcardArgs {
+    #[command(flatten)]
+    pub scan: ScanArgs,
+
+    /// Raw output for piping: a line per weekday, its name then 24 tab-separated hourly counts
+    #[arg(short, long)]
+    pub raw: bool,
+}
+
+const DAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+/// Commits per weekday (Monday first) and hour, in the display time zone.
+fn grid(commits: &[CommitLine]) -> [[usize; 24]; 7] {
+    let mut grid = [[0; 24]; 7];
+    for c in commits {
+        if let Some(t) = zoned(c.time) {
+            grid[t.weekday().num_days_from_monday() as usize][t.hour() as usize] += 1;
+        }
+    }
+    grid
+}
+
+/// A two-column cell shaded by `n` relative to the busiest hour: an empty
+/// hour is a dim dot, the busiest a full block.
+fn shade(n: usize, max: usize) -> String {
+    if n == 0 {
+        return paint("2", " ·");
+    }
+    let block = match n * 4 / max.max(1) {
+        0 => "░░",
+        1 => "▒▒",
+        2 | 3 => "▓▓",
+        _ => "██",
+    };
+    paint("32", block)
+}
+
+/// `work punchcard`: commits in the window on a weekday × hour grid.
+pub fn punchcard(args: PunchcardArgs) -> Result<(), String> {
+    let scan = scan(&args.scan)?;
+    if scan.commits.is_empty() {
+        report_warnings(&scan);
+        return Err(args.scan.no_commits());
+    }
+    let grid = grid(&scan.commits);
+
+    if args.raw {
+        for (day, hours) in DAYS.iter().zip(&grid) {
+            let counts: Vec<String> = hours.iter().map(usize::to_string).collect();
+            println!("{day}\t{}", counts.join("\t"));
+        }
+    } else {
+        let max = grid.iter().flatten().copied().max().unwrap_or(0);
+        let hours: String = (0..24).map(|h| format!("{h:>3}")).collect();
+        println!("   {}", paint("2", hours));
+        for (day, row) in DAYS.iter().zip(&grid) {
+            let cells: String = row.iter().map(|&n| format!(" {}", shade(n, max))).collect();
+            let total: usize = row.iter().sum();
+            println!("{day}{cells} {total:>3}");
+        }
+
+        let (day, hour) = (0..7 * 24)
+            .map(|i| (i / 24, i % 24))
+            .max_by_key(|&(d, h)| (grid[d][h], std::cmp::Reverse((d, h))))
+            .unwrap_or((0, 0));
+        let small_hours: usize = grid.iter().map(|row| row[..6].iter().sum::<usize>()).sum();
+        println!(
+            "\n{} commits ({}), busiest {} {hour:02}:00 with {}, {small_hours} between midnight and 6am",
+            scan.commits.len(),
+            args.scan.window().label(),
+            DAYS[day],
+            grid[day][hour]
+        );
+    }
+    report_warnings(&scan);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn counts_by_weekday_and_local_hour() {
+        crate::COLOR.store(false, std::sync::atomic::Ordering::Relaxed);
+        let day = |d| crate::local_midnight(NaiveDate::from_ymd_opt(2026, 3, d).unwrap());
+        let at = |t| CommitLine::stub("a", t);
+        // Monday 2am twice, then late on Sunday.
+        let commits = vec![
+            at(day(16) + 2 * 3600 + 59),
+            at(day(16) + 2 * 3600 + 3599),
+            at(day(22) + 22 * 3600 + 1800),
+        ];
+        let got = grid(&commits);
+        assert_eq!(got[0][2], 2);
+        assert_eq!(got[6][22], 1);
+        assert_eq!(got.iter().flatten().sum::<usize>(), 3);
+
+        assert_eq!(shade(0, 8), " ·");
+        assert_eq!(shade(1, 8), "░░");
+        assert_eq!(shade(8, 8), "██");
+    }
+}
@@ -0,0 +1,78 @@
+use crate::render::Renderer;
+use crate::{Args, Column, CommitLine, Scan, format_time_local, raw_field};
+use std::io::{self, Write};
+
+/// `--raw`: the `--columns` fields, tab-separated (NUL-separated with
+/// `-z`), one commit per line and nothing else. Stats are
+/// `Nf\t+ins\t-del`; `--show-signatures` adds good, bad or unsigned at the
+/// end.
+pub struct Raw<'a> {
+    pub args: &'a Args,
+}
+
+impl Renderer for Raw<'_> {
+    fn render(&self, out: &mut dyn Write, scan: &Scan, commits: &[&CommitLine]) -> io::Result<()> {
+        let args = self.args;
+        let sep = if args.null { "\0" } else { "\t" };
+        for c in commits {
+            let mut cells: Vec<String> = args
+                .columns
+                .iter()
+                .map(|col| match col {
+                    Column::Time => format_time_local(c.time),
+                    Column::Repo => raw_field(&scan.rel(&c.repo).display().to_string(), args.null),
+                    Column::Hash => c.short.clone(),
+                    Column::Stats => c.stat_cells().join(sep),
+                    Column::Summary => raw_field(&c.summary, args.null),
+                    Column::Author => raw_field(&c.author_name, args.null),
+                })
+                .collect();
+            if let Some(s) = c.signature.filter(|_| args.show_signatures) {
+                cells.push(s.word().to_string());
+            }
+            writeln!(out, "{}", cells.join(sep))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[test]
+    fn separates_fields_with_tabs_or_nuls() {
+        let scan = Scan::stub(
+            &["/code"],
+            vec![CommitLine {
+                summary: "Fix\tlogin".to_string(),
+                files: 2,
+                insertions: 3,
+                deletions: 1,
+                ..CommitLine::stub("/code/api", 1_772_452_800)
+            }],
+        );
+        let commits: Vec<&CommitLine> = scan.commits.iter().collect();
+        let render = |flags: &[&str]| {
+            let args = Args::parse_from(
+                [
+                    &["work", "-r", "--columns", "repo,hash,stats,summary"],
+                    flags,
+                    &["x"],
+                ]
+                .concat(),
+            );
+            let mut out = Vec::new();
+            Raw { args: &args }
+                .render(&mut out, &scan, &commits)
+                .unwrap();
+            String::from_utf8(out).unwrap()
+        };
+        assert_eq!(render(&[]), "api\t0000000\t2f\t+3\t-1\tFix login\n");
+        assert_eq!(
+            render(&["-z"]),
+            ["api", "0000000", "2f", "+3", "-1", "Fix\tlogin\n"].join("\0")
+        );
+    }
+}
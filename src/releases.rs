@@ -0,0 +1,273 @@
+use crate::{
+    Discovery, Identity, ScanArgs, discover, format_time_local, is_bot, open_repo, paint,
+    report_unopened, report_unreadable, resolve_window,
+};
+use git2::{Oid, Repository};
+use rayon::prelude::*;
+use std::path::{Path, PathBuf};
+use work::window;
+
+#[derive(clap::Args, Debug)]
+pub struct ReleasesArgs {
+    #[command(flatten)]
+    pub scan: ScanArgs,
+
+    /// Raw output for piping (tab-separated)
+    #[arg(short, long)]
+    pub raw: bool,
+}
+
+/// An annotated tag made in the window.
+#[derive(Debug, PartialEq, Eq)]
+struct Release {
+    repo: PathBuf,
+    time: i64,
+    name: String,
+    tagger: String,
+    /// First line of the tag message.
+    message: String,
+    /// Commits in the tag that no earlier tag (annotated or not) contains.
+    commits: usize,
+    /// The closest tag those commits were counted from; `None` for a
+    /// repo's first tag.
+    previous: Option<String>,
+}
+
+/// Every tag in `repo` as (name, commit it points at), lightweight ones too.
+fn tag_targets(repo: &Repository) -> Vec<(String, Oid)> {
+    let mut out = Vec::new();
+    let Ok(names) = repo.tag_names(None) else {
+        return out;
+    };
+    for name in names.iter().flatten() {
+        let Ok(obj) = repo.revparse_single(&format!("refs/tags/{name}")) else {
+            continue;
+        };
+        if let Ok(commit) = obj.peel_to_commit() {
+            out.push((name.to_string(), commit.id()));
+        }
+    }
+    out
+}
+
+/// Commits reachable from `target` but from none of the earlier tags, and
+/// the nearest of those tags by commit time.
+fn since_previous(
+    repo: &Repository,
+    target: Oid,
+    tags: &[(String, Oid)],
+) -> Result<(usize, Option<String>), git2::Error> {
+    let mut walk = repo.revwalk()?;
+    walk.push(target)?;
+    let mut previous: Option<(i64, &str)> = None;
+    for (name, oid) in tags {
+        // Hiding a descendant would hide `target` itself.
+        if *oid == target || !repo.graph_descendant_of(target, *oid)? {
+            continue;
+        }
+        walk.hide(*oid)?;
+        let t = repo.find_commit(*oid)?.time().seconds();
+        if previous.is_none_or(|(best, _)| t > best) {
+            previous = Some((t, name));
+        }
+    }
+    Ok((walk.count(), previous.map(|(_, n)| n.to_string())))
+}
+
+fn releases_in(
+    repo_path: &Path,
+    since: i64,
+    until: Option<i64>,
+    id: &Identity,
+    args: &ScanArgs,
+) -> Result<Vec<Release>, String> {
+    let repo = open_repo(repo_path)?;
+    let tags = tag_targets(&repo);
+    let mut out = Vec::new();
+    let mut annotated = Vec::new();
+    let _ = repo.tag_foreach(|oid, _| {
+        annotated.push(oid);
+        true
+    });
+    for oid in annotated {
+        let Ok(tag) = repo.find_tag(oid) else {
+            continue;
+        };
+        let Some(tagger) = tag.tagger() else {
+            continue;
+        };
+        let t = tagger.when().seconds();
+        if !window::is_plausible(t) || t < since || until.is_some_and(|u| t >= u) {
+            continue;
+        }
+        if !args.wants_author(id, tagger.name(), tagger.email())
+            || (args.no_bots && is_bot(args, tagger.name(), tagger.email()))
+        {
+            continue;
+        }
+        let Ok(target) = tag.target().and_then(|o| o.peel_to_commit()) else {
+            continue;
+        };
+        let Ok((commits, previous)) = since_previous(&repo, target.id(), &tags) else {
+            continue;
+        };
+        out.push(Release {
+            repo: repo_path.to_path_buf(),
+            time: t,
+            name: tag.name().unwrap_or("(invalid utf-8)").to_string(),
+            tagger: tagger.name().unwrap_or("").to_string(),
+            message: tag
+                .message()
+                .unwrap_or("")
+                .lines()
+                .next()
+                .unwrap_or("")
+                .trim()
+                .to_string(),
+            commits,
+            previous,
+        });
+    }
+    Ok(out)
+}
+
+/// `work releases`: annotated tags made in the window, newest first, with
+/// how much went into each since the tag before it.
+pub fn releases(args: ReleasesArgs) -> Result<(), String> {
+    let Discovery {
+        roots,
+        repos,
+        unreadable,
+    } = discover(&args.scan)?;
+    let (since, until) = resolve_window(args.scan.window())?;
+    let id = args.scan.identity();
+    let found: Vec<(&PathBuf, Result<Vec<Release>, String>)> = repos
+        .par_iter()
+        .map(|r| (r, releases_in(r, since, until, &id, &args.scan)))
+        .collect();
+    let mut releases = Vec::new();
+    let mut unopened = Vec::new();
+    for (r, found) in found {
+        match found {
+            Ok(found) => releases.extend(found),
+            Err(e) => unopened.push((r.clone(), e)),
+        }
+    }
+    releases.sort_by(|a, b| b.time.cmp(&a.time).then_with(|| a.repo.cmp(&b.repo)));
+
+    let printed = print_releases(&args, &roots, &releases);
+    report_unopened(&roots, &unopened);
+    report_unreadable(&unreadable);
+    printed
+}
+
+fn print_releases(
+    args: &ReleasesArgs,
+    roots: &[PathBuf],
+    releases: &[Release],
+) -> Result<(), String> {
+    if releases.is_empty() {
+        let window = args.scan.window().describe();
+        return Err(if !args.scan.authors.is_empty() {
+            format!("No tagged releases by those authors in {window}")
+        } else if args.scan.all {
+            format!("No tagged releases in {window}")
+        } else {
+            format!("No tagged releases by you in {window} (try --all)")
+        });
+    }
+    let repo_width = releases
+        .iter()
+        .map(|r| crate::repo_name(roots, &r.repo).chars().count())
+        .max()
+        .unwrap_or(0);
+    let name_width = releases
+        .iter()
+        .map(|r| r.name.chars().count())
+        .max()
+        .unwrap_or(0);
+    for r in releases {
+        let t = format_time_local(r.time);
+        let repo = crate::repo_name(roots, &r.repo);
+        if args.raw {
+            // time\trepo\ttag\ttagger\tcommits\tprevious tag\tmessage
+            println!(
+                "{t}\t{repo}\t{}\t{}\t{}\t{}\t{}",
+                r.name,
+                crate::raw_field(&r.tagger, false),
+                r.commits,
+                r.previous.as_deref().unwrap_or(""),
+                crate::raw_field(&r.message, false)
+            );
+        } else {
+            let since = match &r.previous {
+                Some(p) => format!("{} commits since {p}", r.commits),
+                None => format!("{} commits, first tag", r.commits),
+            };
+            println!(
+                "{t}  {}  {}  {}  {}",
+                paint("1", format!("{repo:<repo_width$}")),
+                paint("33", format!("{:<name_width$}", r.name)),
+                paint("2", since),
+                r.message
+            );
+        }
+    }
+    if !args.raw {
+        println!(
+            "\n{} releases ({})",
+            releases.len(),
+            args.scan.window().label()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+    use git2::{ObjectType, Signature, Time};
+    use work::fixtures::FixtureRepo;
+
+    #[test]
+    fn counts_commits_since_the_previous_tag() {
+        let tmp = tempfile::tempdir().unwrap();
+        let f = FixtureRepo::init(&tmp.path().join("app")).unwrap();
+        let repo = f.repo();
+        let first = f.commit(1_772_452_800, "one", &[("a", "1")]).unwrap();
+        repo.tag_lightweight("v0", &repo.find_object(first, None).unwrap(), false)
+            .unwrap();
+        f.commit(1_772_456_400, "two", &[("a", "2")]).unwrap();
+        let head = f.commit(1_772_460_000, "three", &[("a", "3")]).unwrap();
+        let sig = Signature::new("Rel Eng", "rel@corp.io", &Time::new(1_772_463_600, 0)).unwrap();
+        let obj = repo.find_object(head, Some(ObjectType::Commit)).unwrap();
+        repo.tag("v1", &obj, &sig, "Release 1\n\nNotes", false)
+            .unwrap();
+
+        let args = crate::Args::parse_from(["work", "--all", "x"]);
+        let id = Identity {
+            name: None,
+            email: None,
+            other_emails: Vec::new(),
+        };
+        let got = releases_in(f.path(), 1_772_460_000, None, &id, &args.scan).unwrap();
+        assert_eq!(
+            got,
+            vec![Release {
+                repo: f.path().to_path_buf(),
+                time: 1_772_463_600,
+                name: "v1".to_string(),
+                tagger: "Rel Eng".to_string(),
+                message: "Release 1".to_string(),
+                commits: 2,
+                previous: Some("v0".to_string()),
+            }]
+        );
+        assert!(
+            releases_in(f.path(), 1_772_463_601, None, &id, &args.scan)
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
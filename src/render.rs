@@ -0,0 +1,23 @@
+use crate::{CommitLine, Scan};
+use std::io::{self, Write};
+
+/// One of the log's output formats, each in its own module. The log picks
+/// one from the flags and hands it the commits to show, over the `Scan`
+/// it built from the library's `ScanReport`; another front end can do the
+/// same with a format of its own.
+pub trait Renderer {
+    /// Writes `commits`, the ones `--limit` lets through in display order.
+    /// `scan` has the rest: the roots repos are named after, whose commits
+    /// are yours, and every commit in the window for totals that count all
+    /// of them.
+    fn render(&self, out: &mut dyn Write, scan: &Scan, commits: &[&CommitLine]) -> io::Result<()>;
+}
+
+/// `renderer` to stdout.
+pub fn print(renderer: &dyn Renderer, scan: &Scan, commits: &[&CommitLine]) -> Result<(), String> {
+    let mut out = io::stdout().lock();
+    renderer
+        .render(&mut out, scan, commits)
+        .and_then(|()| out.flush())
+        .map_err(|e| format!("work: cannot write output: {e}"))
+}
@@ -0,0 +1,547 @@
+use crate::render::{self, Renderer};
+use crate::{CommitLine, Scan, ScanArgs, finish_export, scan};
+use chrono::NaiveDate;
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+pub enum ReportFormat {
+    /// Markdown, for PR descriptions, wikis and chat
+    Md,
+    /// A standalone HTML page with a chart and a sortable commit table
+    Html,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+pub enum WebhookPayload {
+    /// Slack Block Kit message, for an incoming webhook
+    Slack,
+    /// Plain JSON with the totals and per-repo counts
+    Json,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct ReportArgs {
+    #[command(flatten)]
+    pub scan: ScanArgs,
+
+    /// Report format
+    #[arg(long, value_enum, default_value = "md")]
+    pub format: ReportFormat,
+
+    /// Write the report to a file instead of stdout
+    #[arg(short, long, value_name = "FILE")]
+    pub output: Option<PathBuf>,
+
+    /// POST a summary to this URL (a Slack incoming webhook, say) instead of printing the report
+    #[arg(long, value_name = "URL", conflicts_with_all = ["format", "output"])]
+    pub webhook: Option<String>,
+
+    /// Shape of the --webhook payload
+    #[arg(long, value_enum, default_value = "slack", requires = "webhook")]
+    pub payload: WebhookPayload,
+
+    /// Exit with status 1 if any repo couldn't be scanned
+    #[arg(long)]
+    pub strict: bool,
+}
+
+/// Commits grouped by repo, busiest repo first.
+struct RepoGroup<'a> {
+    name: String,
+    commits: Vec<&'a CommitLine>,
+    insertions: usize,
+    deletions: usize,
+}
+
+fn group_by_repo(scan: &Scan) -> Vec<RepoGroup<'_>> {
+    let mut groups: Vec<RepoGroup> = Vec::new();
+    for c in &scan.commits {
+        let name = scan.name(&c.repo);
+        let i = match groups.iter().position(|g| g.name == name) {
+            Some(i) => i,
+            None => {
+                groups.push(RepoGroup {
+                    name,
+                    commits: Vec::new(),
+                    insertions: 0,
+                    deletions: 0,
+                });
+                groups.len() - 1
+            }
+        };
+        let g = &mut groups[i];
+        g.commits.push(c);
+        g.insertions = g.insertions.saturating_add(c.insertions);
+        g.deletions = g.deletions.saturating_add(c.deletions);
+    }
+    groups.sort_by(|a, b| {
+        b.commits
+            .len()
+            .cmp(&a.commits.len())
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    groups
+}
+
+fn date(ts: i64) -> String {
+    crate::zoned(ts)
+        .map(|d| d.format("%Y-%m-%d").to_string())
+        .unwrap_or_else(|| crate::INVALID_DATE.to_string())
+}
+
+fn markdown(scan: &Scan, window: &str) -> String {
+    let groups = group_by_repo(scan);
+    let total_ins: usize = groups.iter().map(|g| g.insertions).sum();
+    let total_del: usize = groups.iter().map(|g| g.deletions).sum();
+    let end = scan.until.unwrap_or_else(|| crate::now().timestamp());
+
+    let mut out = String::new();
+    let _ = writeln!(out, "# Work report: {window}\n");
+    let _ = writeln!(
+        out,
+        "_{} to {} · {} commits in {} repos · +{total_ins} -{total_del}_\n",
+        date(scan.since),
+        date(end),
+        scan.commits.len(),
+        groups.len()
+    );
+
+    if groups.is_empty() {
+        let _ = writeln!(out, "No commits.");
+        return out;
+    }
+
+    for g in &groups {
+        let _ = writeln!(out, "## {}\n", g.name);
+        for c in &g.commits {
+            let _ = writeln!(
+                out,
+                "- {} (`{}`, +{} -{})",
+                c.summary, c.short, c.insertions, c.deletions
+            );
+        }
+        let _ = writeln!(out);
+    }
+
+    let _ = writeln!(out, "## Totals\n");
+    let _ = writeln!(out, "| Repo | Commits | Added | Removed |");
+    let _ = writeln!(out, "|------|--------:|------:|--------:|");
+    for g in &groups {
+        let _ = writeln!(
+            out,
+            "| {} | {} | +{} | -{} |",
+            g.name,
+            g.commits.len(),
+            g.insertions,
+            g.deletions
+        );
+    }
+    let _ = writeln!(
+        out,
+        "| **Total** | **{}** | **+{total_ins}** | **-{total_del}** |",
+        scan.commits.len()
+    );
+    out
+}
+
+const STYLE: &str = "
+body { font: 15px/1.5 system-ui, sans-serif; color: #1f2328; max-width: 1100px; margin: 2rem auto; padding: 0 1rem; }
+h1 { margin-bottom: 0; }
+.meta { color: #656d76; margin-top: .25rem; }
+table { border-collapse: collapse; width: 100%; margin: 1rem 0 2rem; }
+th, td { text-align: left; padding: .35rem .6rem; border-bottom: 1px solid #d0d7de; vertical-align: top; }
+th { background: #f6f8fa; }
+#commits th { cursor: pointer; user-select: none; }
+#commits th:hover { background: #eaeef2; }
+.num { text-align: right; font-variant-numeric: tabular-nums; }
+.add { color: #1a7f37; }
+.del { color: #cf222e; }
+code { font-size: 13px; color: #656d76; }
+.bar { display: inline-block; height: .6rem; background: #54aeff; border-radius: 2px; }
+svg rect { fill: #54aeff; }
+svg rect:hover { fill: #0969da; }
+svg text { font-size: 10px; fill: #656d76; }
+@media print { #commits th { cursor: auto; } }
+";
+
+/// Click a header to sort by that column; click again to reverse. Cells
+/// with `data-sort` sort by that value (numbers and timestamps).
+const SORT_SCRIPT: &str = "
+document.querySelectorAll('#commits th').forEach((th, col) => {
+  th.addEventListener('click', () => {
+    const body = th.closest('table').tBodies[0];
+    const asc = th.dataset.dir !== 'asc';
+    th.closest('tr').querySelectorAll('th').forEach(h => delete h.dataset.dir);
+    th.dataset.dir = asc ? 'asc' : 'desc';
+    const key = tr => {
+      const td = tr.cells[col];
+      return td.dataset.sort !== undefined ? Number(td.dataset.sort) : td.textContent.toLowerCase();
+    };
+    const rows = Array.from(body.rows).sort((a, b) => {
+      const x = key(a), y = key(b);
+      return (x < y ? -1 : x > y ? 1 : 0) * (asc ? 1 : -1);
+    });
+    rows.forEach(r => body.appendChild(r));
+  });
+});
+";
+
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for ch in s.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Commits per day across the window as an inline SVG bar chart, with a
+/// tooltip on each bar.
+fn activity_chart(scan: &Scan, end: i64) -> String {
+    let day = |ts: i64| crate::zoned(ts).map(|d| d.date_naive());
+    let (Some(first), Some(last)) = (day(scan.since), day(end.saturating_sub(1).max(scan.since)))
+    else {
+        return String::new();
+    };
+    let mut counts: BTreeMap<NaiveDate, usize> = first
+        .iter_days()
+        .take_while(|d| *d <= last)
+        .map(|d| (d, 0))
+        .collect();
+    for c in &scan.commits {
+        if let Some(n) = day(c.time).and_then(|d| counts.get_mut(&d)) {
+            *n += 1;
+        }
+    }
+
+    let max = counts.values().copied().max().unwrap_or(0).max(1);
+    let (bar, gap, height) = (14, 2, 120);
+    let width = counts.len() * (bar + gap);
+    let mut svg = format!(
+        "<svg viewBox=\"0 0 {width} {}\" width=\"100%\" height=\"{}\" preserveAspectRatio=\"none\" role=\"img\" aria-label=\"Commits per day\">\n",
+        height + 16,
+        height + 16
+    );
+    for (i, (d, n)) in counts.iter().enumerate() {
+        let h = if *n == 0 {
+            0
+        } else {
+            (n * height / max).max(2)
+        };
+        let x = i * (bar + gap);
+        let _ = writeln!(
+            svg,
+            "<rect x=\"{x}\" y=\"{}\" width=\"{bar}\" height=\"{h}\"><title>{d}: {n} commit{}</title></rect>",
+            height - h,
+            if *n == 1 { "" } else { "s" }
+        );
+    }
+    let _ = writeln!(
+        svg,
+        "<text x=\"0\" y=\"{}\">{first}</text><text x=\"{width}\" y=\"{}\" text-anchor=\"end\">{last}</text>",
+        height + 13,
+        height + 13
+    );
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn html(scan: &Scan, window: &str) -> String {
+    let groups = group_by_repo(scan);
+    let total_ins: usize = groups.iter().map(|g| g.insertions).sum();
+    let total_del: usize = groups.iter().map(|g| g.deletions).sum();
+    let end = scan.until.unwrap_or_else(|| crate::now().timestamp());
+    let title = escape(&format!("Work report: {window}"));
+
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n<title>{title}</title>\n<style>{STYLE}</style>\n</head>\n<body>"
+    );
+    let _ = writeln!(out, "<h1>{title}</h1>");
+    let _ = writeln!(
+        out,
+        "<p class=\"meta\">{} to {} · {} commits in {} repos · <span class=\"add\">+{total_ins}</span> <span class=\"del\">-{total_del}</span></p>",
+        date(scan.since),
+        date(end),
+        scan.commits.len(),
+        groups.len()
+    );
+
+    if groups.is_empty() {
+        let _ = writeln!(out, "<p>No commits.</p>\n</body>\n</html>");
+        return out;
+    }
+
+    let _ = writeln!(out, "<h2>Activity</h2>");
+    out.push_str(&activity_chart(scan, end));
+
+    let _ = writeln!(out, "<h2>Repos</h2>\n<table>");
+    let _ = writeln!(
+        out,
+        "<thead><tr><th>Repo</th><th class=\"num\">Commits</th><th></th><th class=\"num\">Added</th><th class=\"num\">Removed</th></tr></thead>\n<tbody>"
+    );
+    let busiest = groups[0].commits.len();
+    for g in &groups {
+        let _ = writeln!(
+            out,
+            "<tr><td>{}</td><td class=\"num\">{}</td><td><span class=\"bar\" style=\"width: {}px\"></span></td><td class=\"num add\">+{}</td><td class=\"num del\">-{}</td></tr>",
+            escape(&g.name),
+            g.commits.len(),
+            g.commits.len() * 200 / busiest,
+            g.insertions,
+            g.deletions
+        );
+    }
+    let _ = writeln!(
+        out,
+        "</tbody>\n<tfoot><tr><th>Total</th><th class=\"num\">{}</th><th></th><th class=\"num add\">+{total_ins}</th><th class=\"num del\">-{total_del}</th></tr></tfoot>\n</table>",
+        scan.commits.len()
+    );
+
+    let _ = writeln!(out, "<h2>Commits</h2>\n<table id=\"commits\">");
+    let _ = writeln!(
+        out,
+        "<thead><tr><th>Date</th><th>Repo</th><th>Commit</th><th>Summary</th><th class=\"num\">Added</th><th class=\"num\">Removed</th></tr></thead>\n<tbody>"
+    );
+    for c in &scan.commits {
+        let _ = writeln!(
+            out,
+            "<tr><td data-sort=\"{}\">{}</td><td>{}</td><td><code>{}</code></td><td>{}</td><td class=\"num add\" data-sort=\"{}\">+{}</td><td class=\"num del\" data-sort=\"{}\">-{}</td></tr>",
+            c.time,
+            escape(&crate::format_time_local(c.time)),
+            escape(&scan.name(&c.repo)),
+            c.short,
+            escape(&c.summary),
+            c.insertions,
+            c.insertions,
+            c.deletions,
+            c.deletions
+        );
+    }
+    let _ = writeln!(out, "</tbody>\n</table>");
+    let _ = writeln!(out, "<script>{SORT_SCRIPT}</script>\n</body>\n</html>");
+    out
+}
+
+/// Repos listed by name in a Slack message; the rest are counted. Keeps a
+/// busy week well under Slack's 3000-character limit per section.
+const SLACK_REPOS: usize = 15;
+
+fn slack_payload(scan: &Scan, window: &str) -> Value {
+    let groups = group_by_repo(scan);
+    let total_ins: usize = groups.iter().map(|g| g.insertions).sum();
+    let total_del: usize = groups.iter().map(|g| g.deletions).sum();
+    let end = scan.until.unwrap_or_else(|| crate::now().timestamp());
+    let headline = format!(
+        "{} commits in {} repos · +{total_ins} -{total_del}",
+        scan.commits.len(),
+        groups.len()
+    );
+
+    let mut blocks = vec![
+        json!({
+            "type": "header",
+            "text": {"type": "plain_text", "text": format!("Work report: {window}")},
+        }),
+        json!({
+            "type": "context",
+            "elements": [{"type": "mrkdwn", "text": format!("{} to {}", date(scan.since), date(end))}],
+        }),
+        json!({
+            "type": "section",
+            "text": {"type": "mrkdwn", "text": format!("*{headline}*")},
+        }),
+    ];
+    if !groups.is_empty() {
+        let mut lines: Vec<String> = groups
+            .iter()
+            .take(SLACK_REPOS)
+            .map(|g| {
+                format!(
+                    "• *{}*: {} commits, +{} -{}",
+                    g.name,
+                    g.commits.len(),
+                    g.insertions,
+                    g.deletions
+                )
+            })
+            .collect();
+        if groups.len() > SLACK_REPOS {
+            lines.push(format!("…and {} more repos", groups.len() - SLACK_REPOS));
+        }
+        blocks.push(json!({
+            "type": "section",
+            "text": {"type": "mrkdwn", "text": lines.join("\n")},
+        }));
+    }
+    // `text` is what notifications and screen readers show.
+    json!({"text": format!("Work report: {window}: {headline}"), "blocks": blocks})
+}
+
+fn json_payload(scan: &Scan, window: &str) -> Value {
+    let groups = group_by_repo(scan);
+    json!({
+        "window": window,
+        "since": scan.since,
+        "until": scan.until.unwrap_or_else(|| crate::now().timestamp()),
+        "commits": scan.commits.len(),
+        "insertions": groups.iter().map(|g| g.insertions).sum::<usize>(),
+        "deletions": groups.iter().map(|g| g.deletions).sum::<usize>(),
+        "repos": groups.iter().map(|g| json!({
+            "name": g.name,
+            "commits": g.commits.len(),
+            "insertions": g.insertions,
+            "deletions": g.deletions,
+        })).collect::<Vec<_>>(),
+    })
+}
+
+/// Webhook URLs are secrets (anyone with a Slack one can post to the
+/// channel), so errors name the host and never the whole URL.
+fn post(url: &str, body: &Value) -> Result<(), String> {
+    let host = url
+        .split("://")
+        .nth(1)
+        .and_then(|rest| rest.split('/').next())
+        .unwrap_or("the webhook");
+    ureq::post(url)
+        .send_json(body)
+        .map(|_| ())
+        .map_err(|e| match e {
+            ureq::Error::StatusCode(code) => format!("work: webhook at {host} answered {code}"),
+            e => format!("work: cannot post to webhook at {host}: {e}"),
+        })
+}
+
+pub fn report(args: ReportArgs) -> Result<(), String> {
+    let scan = scan(&args.scan)?;
+    let written = write_report(&args, &scan);
+    finish_export(args.strict, &scan, written)
+}
+
+fn write_report(args: &ReportArgs, scan: &Scan) -> Result<(), String> {
+    let window = args.scan.window().label();
+    if let Some(url) = &args.webhook {
+        let body = match args.payload {
+            WebhookPayload::Slack => slack_payload(scan, &window),
+            WebhookPayload::Json => json_payload(scan, &window),
+        };
+        return post(url, &body);
+    }
+    let renderer: Box<dyn Renderer> = match args.format {
+        ReportFormat::Md => Box::new(Markdown { window: &window }),
+        ReportFormat::Html => Box::new(Html { window: &window }),
+    };
+
+    let commits: Vec<&CommitLine> = scan.commits.iter().collect();
+    match &args.output {
+        Some(path) => fs::File::create(path)
+            .and_then(|mut file| renderer.render(&mut file, scan, &commits))
+            .map_err(|e| format!("work: cannot write '{}': {e}", path.display())),
+        None => render::print(renderer.as_ref(), scan, &commits),
+    }
+}
+
+/// `--format md`. A report covers the whole window, so it ignores which
+/// commits it's given.
+struct Markdown<'a> {
+    window: &'a str,
+}
+
+impl Renderer for Markdown<'_> {
+    fn render(&self, out: &mut dyn Write, scan: &Scan, _: &[&CommitLine]) -> io::Result<()> {
+        out.write_all(markdown(scan, self.window).as_bytes())
+    }
+}
+
+/// `--format html`, the whole window like `Markdown`.
+struct Html<'a> {
+    window: &'a str,
+}
+
+impl Renderer for Html<'_> {
+    fn render(&self, out: &mut dyn Write, scan: &Scan, _: &[&CommitLine]) -> io::Result<()> {
+        out.write_all(html(scan, self.window).as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(repo: &str, summary: &str, insertions: usize) -> CommitLine {
+        CommitLine {
+            summary: summary.to_string(),
+            insertions,
+            deletions: 1,
+            ..CommitLine::stub(repo, 0)
+        }
+    }
+
+    #[test]
+    fn groups_markdown_by_repo_busiest_first() {
+        let scan = Scan::stub(
+            &["/code"],
+            vec![
+                line("/code/ui", "tweak", 1),
+                line("/code/api", "add route", 10),
+                line("/code/api", "fix route", 2),
+            ],
+        );
+
+        let md = markdown(&scan, "last week");
+        let api = md.find("## api").unwrap();
+        let ui = md.find("## ui").unwrap();
+        assert!(api < ui);
+        assert!(md.contains("- add route (`0000000`, +10 -1)"));
+        assert!(md.contains("| **Total** | **3** | **+13** | **-3** |"));
+
+        let slack = slack_payload(&scan, "last week");
+        assert_eq!(slack["blocks"][0]["text"]["text"], "Work report: last week");
+        assert_eq!(
+            slack["blocks"][3]["text"]["text"],
+            "• *api*: 2 commits, +12 -2\n• *ui*: 1 commits, +1 -1"
+        );
+        let plain = json_payload(&scan, "last week");
+        assert_eq!(plain["insertions"], 13);
+        assert_eq!(plain["repos"][0]["name"], "api");
+    }
+
+    #[test]
+    fn html_is_standalone_and_escaped() {
+        let start = NaiveDate::from_ymd_opt(2026, 3, 1).unwrap();
+        let since = crate::local_midnight(start);
+        let scan = Scan {
+            since,
+            until: Some(crate::local_midnight(start + chrono::Days::new(3))),
+            ..Scan::stub(
+                &["/code"],
+                vec![CommitLine {
+                    time: crate::local_midnight(start + chrono::Days::new(1)) + 60,
+                    ..line("/code/api", "Render <b> & \"quotes\"", 4)
+                }],
+            )
+        };
+
+        let page = html(&scan, "last week");
+        assert!(page.starts_with("<!DOCTYPE html>"));
+        assert!(page.contains("Render &lt;b&gt; &amp; &quot;quotes&quot;"));
+        assert!(!page.contains("<b>"));
+        // One bar per day in the window, the middle one holding the commit.
+        assert_eq!(page.matches("<rect").count(), 3);
+        assert!(page.contains("2026-03-02: 1 commit<"));
+        assert!(page.contains("<table id=\"commits\">"));
+    }
+}
@@ -0,0 +1,638 @@
+use crate::{
+    DateKind, Discovery, ScanArgs, Skip, discover, discover_traced, format_time_local, open_repo,
+    paint, repo_name, report_unreadable,
+};
+use git2::{IndexEntryExtendedFlag, Repository, Status, StatusOptions};
+use rayon::prelude::*;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+#[derive(clap::Args, Debug)]
+#[command(args_conflicts_with_subcommands = true)]
+pub struct ReposArgs {
+    #[command(subcommand)]
+    pub action: Option<ReposAction>,
+
+    #[command(flatten)]
+    pub scan: ScanArgs,
+
+    /// Raw output for piping (tab-separated)
+    #[arg(short, long)]
+    pub raw: bool,
+
+    /// Only repos with work that exists nowhere else: unpushed commits on any branch, uncommitted changes, stashes
+    #[arg(long)]
+    pub unpushed: bool,
+}
+
+#[derive(clap::Subcommand, Debug)]
+pub enum ReposAction {
+    /// List the repos that would be scanned
+    // Boxed: `work repos` already carries one set of scan args inline.
+    Discover(Box<DiscoverArgs>),
+}
+
+#[derive(clap::Args, Debug)]
+pub struct DiscoverArgs {
+    #[command(flatten)]
+    pub scan: ScanArgs,
+
+    /// Also list the directories that are passed over, and why
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Raw output for piping (tab-separated)
+    #[arg(short, long)]
+    pub raw: bool,
+}
+
+impl ReposArgs {
+    pub fn scan_mut(&mut self) -> &mut ScanArgs {
+        match &mut self.action {
+            Some(ReposAction::Discover(d)) => &mut d.scan,
+            None => &mut self.scan,
+        }
+    }
+}
+
+/// Where a repo's work tree stands, for `work repos`.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct RepoStatus {
+    /// Branch name, `detached at <hash>`, or the unborn branch's name.
+    branch: String,
+    /// None before the first commit.
+    last_commit: Option<i64>,
+    /// Commits ahead of and behind the upstream; None without one.
+    ahead_behind: Option<(usize, usize)>,
+    dirty: bool,
+    /// Local branches with commits their upstream doesn't have, or, for
+    /// branches without one, that no remote branch has.
+    unpushed: Vec<Unpushed>,
+    stashes: usize,
+    /// Whether the repo has any remote to push to.
+    has_remote: bool,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Unpushed {
+    branch: String,
+    commits: usize,
+    has_upstream: bool,
+}
+
+impl RepoStatus {
+    /// Work that would be lost with this clone: unpushed commits, changes,
+    /// stashes, or commits in a repo with nowhere to push them.
+    fn has_unpushed(&self) -> bool {
+        !self.unpushed.is_empty()
+            || self.dirty
+            || self.stashes > 0
+            || (!self.has_remote && self.last_commit.is_some())
+    }
+
+    /// `main ↑2, spike 5 (no upstream), dirty, 1 stash`.
+    fn unpushed_summary(&self) -> String {
+        let mut parts: Vec<String> = self
+            .unpushed
+            .iter()
+            .map(|u| match u.has_upstream {
+                true => format!("{} ↑{}", u.branch, u.commits),
+                false => format!("{} {} (no upstream)", u.branch, u.commits),
+            })
+            .collect();
+        if !self.has_remote && self.last_commit.is_some() {
+            parts.push("no remote".to_string());
+        }
+        if self.dirty {
+            parts.push("dirty".to_string());
+        }
+        match self.stashes {
+            0 => {}
+            1 => parts.push("1 stash".to_string()),
+            n => parts.push(format!("{n} stashes")),
+        }
+        parts.join(", ")
+    }
+}
+
+/// Where `git push` would send branch `name`, as the remote-tracking ref
+/// that mirrors it, like `@{push}`. The remote is `branch.<name>.pushRemote`,
+/// else `remote.pushDefault`, else the branch's own remote. Pushing to
+/// another remote than the one it pulls from (a fork) goes to the branch of
+/// the same name there; otherwise `push.default` decides, and for the
+/// default `simple` that's the upstream.
+fn push_target(repo: &Repository, branch: &git2::Branch, name: &str) -> Option<git2::Oid> {
+    let cfg = repo.config().and_then(|mut c| c.snapshot()).ok()?;
+    let get = |key: &str| cfg.get_string(key).ok();
+    let fetch_remote = get(&format!("branch.{name}.remote"));
+    let remote = get(&format!("branch.{name}.pushRemote"))
+        .or_else(|| get("remote.pushDefault"))
+        .or_else(|| fetch_remote.clone())?;
+    let mode = get("push.default").unwrap_or_else(|| "simple".to_string());
+    if mode == "nothing" {
+        return None;
+    }
+    let triangular = fetch_remote.as_deref() != Some(remote.as_str());
+    if !triangular && matches!(mode.as_str(), "simple" | "upstream") {
+        return branch.upstream().ok()?.get().target();
+    }
+
+    // The same name on the push remote, through its fetch refspecs.
+    let src = format!("refs/heads/{name}");
+    let remote = repo.find_remote(&remote).ok()?;
+    let tracking = remote
+        .refspecs()
+        .filter(|s| s.direction() == git2::Direction::Fetch && s.src_matches(&src))
+        .find_map(|s| s.transform(&src).ok())?;
+    repo.refname_to_id(tracking.as_str()?).ok()
+}
+
+/// Every local branch with commits that aren't on its push target, or on
+/// any remote branch if it has none yet. Repos without remotes have nothing
+/// to compare with, so they get none.
+fn unpushed_branches(repo: &Repository) -> Vec<Unpushed> {
+    let Ok(branches) = repo.branches(Some(git2::BranchType::Local)) else {
+        return Vec::new();
+    };
+    let mut out = Vec::new();
+    for (branch, _) in branches.flatten() {
+        let (Some(name), Some(tip)) = (branch.name().ok().flatten(), branch.get().target()) else {
+            continue;
+        };
+        let Ok(mut walk) = repo.revwalk() else {
+            continue;
+        };
+        if walk.push(tip).is_err() {
+            continue;
+        }
+        let upstream = push_target(repo, &branch, name);
+        let hidden = match upstream {
+            Some(up) => walk.hide(up),
+            None => walk.hide_glob("refs/remotes/*"),
+        };
+        if hidden.is_err() {
+            continue;
+        }
+        let commits = walk.count();
+        if commits > 0 {
+            out.push(Unpushed {
+                branch: name.to_string(),
+                commits,
+                has_upstream: upstream.is_some(),
+            });
+        }
+    }
+    out.sort_by(|a, b| a.branch.cmp(&b.branch));
+    out
+}
+
+fn status(path: &Path, date: DateKind) -> Result<RepoStatus, String> {
+    let repo = open_repo(path)?;
+    let mut st = RepoStatus {
+        stashes: repo.reflog("refs/stash").map_or(0, |r| r.len()),
+        has_remote: repo.remotes().is_ok_and(|r| !r.is_empty()),
+        ..RepoStatus::default()
+    };
+    if st.has_remote {
+        st.unpushed = unpushed_branches(&repo);
+    }
+
+    let head = match repo.head() {
+        Ok(h) => h,
+        Err(e) if e.code() == git2::ErrorCode::UnbornBranch => {
+            // HEAD still names the branch the first commit will go on.
+            let name = repo
+                .find_reference("HEAD")
+                .ok()
+                .and_then(|r| r.symbolic_target().map(str::to_string))
+                .unwrap_or_default();
+            st.branch = name.trim_start_matches("refs/heads/").to_string();
+            st.dirty = is_dirty(&repo);
+            return Ok(st);
+        }
+        Err(e) => return Err(e.message().to_string()),
+    };
+
+    let commit = head.peel_to_commit().map_err(|e| e.message().to_string())?;
+    st.last_commit = Some(match date {
+        DateKind::Committer => commit.time().seconds(),
+        DateKind::Author => commit.author().when().seconds(),
+    });
+
+    if head.is_branch() {
+        let name = head.shorthand().unwrap_or("HEAD").to_string();
+        let branch = repo.find_branch(&name, git2::BranchType::Local).ok();
+        let upstream = branch
+            .as_ref()
+            .and_then(|b| b.upstream().ok())
+            .and_then(|u| u.get().target());
+        if let Some(up) = upstream {
+            st.ahead_behind = repo.graph_ahead_behind(commit.id(), up).ok();
+            // Behind what we pull from, ahead of where we push to: with a
+            // fork as push remote those are two different refs.
+            let push = branch.as_ref().and_then(|b| push_target(&repo, b, &name));
+            let push = push.filter(|p| *p != up);
+            if let (Some(push), Some((_, behind))) = (push, st.ahead_behind) {
+                let ahead = repo.graph_ahead_behind(commit.id(), push).map(|(a, _)| a);
+                st.ahead_behind = ahead.ok().map(|a| (a, behind));
+            }
+        }
+        st.branch = name;
+    } else {
+        let hash = commit.id().to_string();
+        st.branch = format!("detached at {}", &hash[..7]);
+    }
+
+    st.dirty = is_dirty(&repo);
+    Ok(st)
+}
+
+/// Index entries marked skip-worktree: outside a sparse-checkout cone, or
+/// set by hand with `git update-index --skip-worktree`.
+fn skip_worktree(repo: &Repository) -> HashSet<Vec<u8>> {
+    let Ok(index) = repo.index() else {
+        return HashSet::new();
+    };
+    index
+        .iter()
+        .filter(|e| {
+            IndexEntryExtendedFlag::from_bits_truncate(e.flags_extended)
+                .contains(IndexEntryExtendedFlag::SKIP_WORKTREE)
+        })
+        .map(|e| e.path)
+        .collect()
+}
+
+/// Uncommitted changes or untracked files. Bare repos are never dirty.
+/// libgit2 doesn't know about sparse checkouts, so files left out of the
+/// work tree on purpose are dropped here instead of counting as deleted;
+/// `git status` ignores them the same way.
+fn is_dirty(repo: &Repository) -> bool {
+    if repo.is_bare() {
+        return false;
+    }
+    let mut opts = StatusOptions::new();
+    opts.include_untracked(true)
+        .recurse_untracked_dirs(false)
+        .exclude_submodules(true);
+    let Ok(statuses) = repo.statuses(Some(&mut opts)) else {
+        return false;
+    };
+    if statuses.is_empty() {
+        return false;
+    }
+    let skipped = skip_worktree(repo);
+    let staged = Status::INDEX_NEW
+        | Status::INDEX_MODIFIED
+        | Status::INDEX_DELETED
+        | Status::INDEX_RENAMED
+        | Status::INDEX_TYPECHANGE;
+    statuses
+        .iter()
+        .any(|e| e.status().intersects(staged) || !skipped.contains(e.path_bytes()))
+}
+
+/// `work repos discover`: the repos a scan would cover, and with `--dry-run`
+/// every directory it doesn't look inside, so a missing repo has a reason.
+fn discover_repos(args: DiscoverArgs) -> Result<(), String> {
+    let mut skipped: Vec<(PathBuf, Skip)> = Vec::new();
+    let found = discover_traced(&args.scan, &mut |p, why| {
+        skipped.push((p.to_path_buf(), why))
+    });
+    skipped.sort_by(|a, b| a.0.cmp(&b.0));
+    let repos = found
+        .as_ref()
+        .map(|(_, r)| r.as_slice())
+        .unwrap_or_default();
+
+    if args.raw {
+        // scan\tpath, or skip\tpath\treason
+        for r in repos {
+            println!("scan\t{}", r.display());
+        }
+        if args.dry_run {
+            for (p, why) in &skipped {
+                println!("skip\t{}\t{why}", p.display());
+            }
+        }
+    } else {
+        for r in repos {
+            println!("{}", r.display());
+        }
+        if args.dry_run && !skipped.is_empty() {
+            let width = skipped
+                .iter()
+                .map(|(p, _)| p.display().to_string().chars().count())
+                .max()
+                .unwrap_or(0);
+            println!("\n{}", paint("1", "Skipped:"));
+            for (p, why) in &skipped {
+                println!(
+                    "{:<width$}  {}",
+                    p.display().to_string(),
+                    paint("2", why.to_string())
+                );
+            }
+        }
+    }
+    // "No git repos found" still deserves the skip list above it.
+    found.map(|_| ())
+}
+
+/// `work repos`: one line per discovered repo with its branch, last commit,
+/// ahead/behind its upstream and whether the work tree is dirty. With
+/// `--unpushed`, only the repos with work not pushed anywhere, and what.
+pub fn repos(args: ReposArgs) -> Result<(), String> {
+    if let Some(ReposAction::Discover(d)) = args.action {
+        return discover_repos(*d);
+    }
+    let Discovery {
+        roots,
+        repos,
+        unreadable,
+    } = discover(&args.scan)?;
+    let mut rows: Vec<(String, Result<RepoStatus, String>)> = repos
+        .par_iter()
+        .map(|r: &PathBuf| (repo_name(&roots, r), status(r, args.scan.date)))
+        .collect();
+    let unpushed = rows
+        .iter()
+        .filter(|(_, st)| st.as_ref().is_ok_and(RepoStatus::has_unpushed))
+        .count();
+
+    if args.unpushed {
+        // Errors stay: a repo that can't be read might have anything in it.
+        rows.retain(|(_, st)| st.as_ref().map_or(true, RepoStatus::has_unpushed));
+        print_unpushed(&rows, args.raw, repos.len());
+        report_unreadable(&unreadable);
+        return Ok(());
+    }
+
+    if args.raw {
+        // repo\tbranch\tlast commit\tahead\tbehind\tdirty|clean|error
+        for (name, st) in &rows {
+            match st {
+                Ok(st) => {
+                    let (ahead, behind) = st
+                        .ahead_behind
+                        .map(|(a, b)| (a.to_string(), b.to_string()))
+                        .unwrap_or_default();
+                    println!(
+                        "{name}\t{}\t{}\t{ahead}\t{behind}\t{}",
+                        st.branch,
+                        st.last_commit.map(format_time_local).unwrap_or_default(),
+                        if st.dirty { "dirty" } else { "clean" }
+                    );
+                }
+                Err(e) => println!("{name}\t\t\t\t\terror: {e}"),
+            }
+        }
+        report_unreadable(&unreadable);
+        return Ok(());
+    }
+
+    let name_width = rows.iter().map(|(n, _)| n.len()).max().unwrap_or(0);
+    let branch_width = rows
+        .iter()
+        .filter_map(|(_, st)| st.as_ref().ok())
+        .map(|st| st.branch.chars().count())
+        .max()
+        .unwrap_or(0);
+    for (name, st) in &rows {
+        let name = paint("1", format!("{name:<name_width$}"));
+        let st = match st {
+            Ok(st) => st,
+            Err(e) => {
+                println!("{name}  {}", paint("31", format!("error: {e}")));
+                continue;
+            }
+        };
+        let last = match st.last_commit {
+            Some(t) => format_time_local(t),
+            None => format!("{:<16}", "(no commits)"),
+        };
+        let sync = match st.ahead_behind {
+            Some((0, 0)) => "up to date".to_string(),
+            Some((a, b)) => format!("↑{a} ↓{b}"),
+            None => paint("2", "no upstream"),
+        };
+        let dirty = if st.dirty {
+            format!("  {}", paint("33", "dirty"))
+        } else {
+            String::new()
+        };
+        println!(
+            "{name}  {}  {last}  {sync}{dirty}",
+            paint("36", format!("{:<branch_width$}", st.branch))
+        );
+    }
+    if unpushed > 0 {
+        println!(
+            "\n{unpushed} of {} repos have unpushed work (see `work repos --unpushed`)",
+            rows.len()
+        );
+    }
+    report_unreadable(&unreadable);
+    Ok(())
+}
+
+/// `work repos --unpushed`: each repo with work that's only in this clone.
+fn print_unpushed(rows: &[(String, Result<RepoStatus, String>)], raw: bool, total: usize) {
+    if raw {
+        // repo\tkind\tbranch\tcount, kind being ahead or no-upstream (a
+        // branch and its unpushed commits), no-remote, dirty, stashes (and
+        // how many), or error (with the message in place of the branch).
+        for (name, st) in rows {
+            let st = match st {
+                Ok(st) => st,
+                Err(e) => {
+                    println!("{name}\terror\t{e}\t");
+                    continue;
+                }
+            };
+            for u in &st.unpushed {
+                let kind = if u.has_upstream {
+                    "ahead"
+                } else {
+                    "no-upstream"
+                };
+                println!("{name}\t{kind}\t{}\t{}", u.branch, u.commits);
+            }
+            if !st.has_remote && st.last_commit.is_some() {
+                println!("{name}\tno-remote\t\t");
+            }
+            if st.dirty {
+                println!("{name}\tdirty\t\t");
+            }
+            if st.stashes > 0 {
+                println!("{name}\tstashes\t\t{}", st.stashes);
+            }
+        }
+        return;
+    }
+
+    if rows.is_empty() {
+        println!("Everything is pushed in all {total} repos");
+    }
+    let name_width = rows.iter().map(|(n, _)| n.len()).max().unwrap_or(0);
+    for (name, st) in rows {
+        let name = paint("1", format!("{name:<name_width$}"));
+        match st {
+            Ok(st) => println!("{name}  {}", st.unpushed_summary()),
+            Err(e) => println!("{name}  {}", paint("31", format!("error: {e}"))),
+        }
+    }
+    let unpushed = rows.iter().filter(|(_, st)| st.is_ok()).count();
+    if unpushed > 0 {
+        println!("\n{unpushed} of {total} repos have unpushed work");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::Signature;
+    use std::fs;
+    use work::fixtures::FixtureRepo;
+
+    #[test]
+    fn reports_branch_upstream_and_dirt() {
+        let tmp = tempfile::tempdir().unwrap();
+        let origin = FixtureRepo::init(&tmp.path().join("origin")).unwrap();
+        origin
+            .commit(1_772_452_800, "first", &[("a.txt", "a")])
+            .unwrap();
+
+        let path = tmp.path().join("clone");
+        let clone = Repository::clone(origin.path().to_str().unwrap(), &path).unwrap();
+        let st = status(&path, DateKind::Committer).unwrap();
+        assert_eq!(st.last_commit, Some(1_772_452_800));
+        assert_eq!(st.ahead_behind, Some((0, 0)));
+        assert!(!st.dirty);
+
+        let sig = Signature::now("Me", "me@example.com").unwrap();
+        let head = clone.head().unwrap().peel_to_commit().unwrap();
+        let tree = head.tree().unwrap();
+        clone
+            .commit(Some("HEAD"), &sig, &sig, "local", &tree, &[&head])
+            .unwrap();
+        fs::write(path.join("scratch.txt"), "wip").unwrap();
+
+        let st = status(&path, DateKind::Committer).unwrap();
+        assert_eq!(st.ahead_behind, Some((1, 0)));
+        assert!(st.dirty);
+        fs::remove_file(path.join("scratch.txt")).unwrap();
+
+        // A branch that was never pushed: its commits aren't on any remote.
+        let local = clone.head().unwrap().peel_to_commit().unwrap();
+        let spike = clone
+            .commit(None, &sig, &sig, "spike", &tree, &[&local])
+            .unwrap();
+        clone
+            .branch("spike", &clone.find_commit(spike).unwrap(), false)
+            .unwrap();
+        let st = status(&path, DateKind::Committer).unwrap();
+        let branch = st.branch.clone();
+        assert_eq!(
+            st.unpushed,
+            [
+                Unpushed {
+                    branch: branch.clone(),
+                    commits: 1,
+                    has_upstream: true
+                },
+                Unpushed {
+                    branch: "spike".to_string(),
+                    commits: 2,
+                    has_upstream: false
+                },
+            ]
+        );
+        assert!(st.has_unpushed());
+        assert_eq!(
+            st.unpushed_summary(),
+            format!("{branch} ↑1, spike 2 (no upstream)")
+        );
+
+        // Outside a sparse-checkout cone: absent on purpose, not deleted.
+        let mut index = clone.index().unwrap();
+        let mut entry = index.get_path(Path::new("a.txt"), 0).unwrap();
+        entry.flags_extended |= IndexEntryExtendedFlag::SKIP_WORKTREE.bits();
+        index.add(&entry).unwrap();
+        index.write().unwrap();
+        fs::remove_file(path.join("a.txt")).unwrap();
+        assert!(!status(&path, DateKind::Committer).unwrap().dirty);
+
+        // The origin has no upstream of its own, or anywhere to push to.
+        let st = status(origin.path(), DateKind::Committer).unwrap();
+        assert_eq!(st.ahead_behind, None);
+        assert_eq!(st.unpushed_summary(), "no remote");
+    }
+
+    #[test]
+    fn counts_unpushed_against_the_push_remote() {
+        let tmp = tempfile::tempdir().unwrap();
+        let origin = FixtureRepo::init(&tmp.path().join("origin")).unwrap();
+        origin
+            .commit(1_772_452_800, "first", &[("a.txt", "a")])
+            .unwrap();
+        let path = tmp.path().join("clone");
+        let clone = Repository::clone(origin.path().to_str().unwrap(), &path).unwrap();
+        let branch = clone.head().unwrap().shorthand().unwrap().to_string();
+        let first = clone.head().unwrap().peel_to_commit().unwrap();
+
+        // Pull from origin, push to a fork that still has the first commit.
+        clone
+            .remote("fork", "https://example.com/me/fork.git")
+            .unwrap();
+        clone
+            .reference(
+                &format!("refs/remotes/fork/{branch}"),
+                first.id(),
+                false,
+                "fork",
+            )
+            .unwrap();
+        clone
+            .config()
+            .unwrap()
+            .set_str("remote.pushDefault", "fork")
+            .unwrap();
+
+        // A commit that's upstream already, say from a merged pull request.
+        let sig = Signature::now("Me", "me@example.com").unwrap();
+        let tree = first.tree().unwrap();
+        let local = clone
+            .commit(Some("HEAD"), &sig, &sig, "local", &tree, &[&first])
+            .unwrap();
+        clone
+            .reference(
+                &format!("refs/remotes/origin/{branch}"),
+                local,
+                true,
+                "pull",
+            )
+            .unwrap();
+
+        let st = status(&path, DateKind::Committer).unwrap();
+        assert_eq!(st.ahead_behind, Some((1, 0)));
+        assert_eq!(
+            st.unpushed,
+            [Unpushed {
+                branch: branch.clone(),
+                commits: 1,
+                has_upstream: true
+            }]
+        );
+
+        // branch.<name>.pushRemote wins over remote.pushDefault.
+        clone
+            .config()
+            .unwrap()
+            .set_str(&format!("branch.{branch}.pushRemote"), "origin")
+            .unwrap();
+        let st = status(&path, DateKind::Committer).unwrap();
+        assert_eq!(st.ahead_behind, Some((0, 0)));
+        assert!(st.unpushed.is_empty());
+    }
+}
@@ -0,0 +1,342 @@
+use crate::{
+    CANCELLED, CommitLine, Discovery, Identity, ScanArgs, TIMED_OUT, UNBORN, discover, progress,
+    repo_name, resolve_window, scan_repo, trust_all,
+};
+use rayon::prelude::*;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use work::window::Window;
+
+/// How scanning one repo went.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Scanned,
+    /// Failed once and then succeeded, with the first error.
+    Retried(String),
+    /// Failed twice, or ran out of `--repo-timeout`; its commits may be
+    /// missing or partial.
+    Failed(String),
+    /// Nothing committed yet, with what HEAD names.
+    Unborn(String),
+    /// Stopped by `Cancel` before it was done, or before it started.
+    Cancelled,
+}
+
+/// One repo's part of a scan.
+#[derive(Clone, Debug)]
+pub struct RepoResult {
+    pub path: PathBuf,
+    /// Relative to the scan root it was found under, as the log names it.
+    pub name: String,
+    /// Newest first. Copies of a commit in other clones are all here; the
+    /// log collapses them.
+    pub commits: Vec<CommitLine>,
+    pub outcome: Outcome,
+    /// Both attempts, for a repo that was retried.
+    pub elapsed: Duration,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Timings {
+    /// Finding the repos under the roots.
+    pub discovery: Duration,
+    /// Scanning them, retries included.
+    pub scan: Duration,
+}
+
+/// Everything one scan found, repo by repo, for front ends that show more
+/// than the log's single list (which is this, flattened).
+#[derive(Clone, Debug)]
+pub struct ScanReport {
+    /// Canonicalized, longest first.
+    pub roots: Vec<PathBuf>,
+    /// Whose commits were kept.
+    pub id: Identity,
+    pub since: i64,
+    pub until: Option<i64>,
+    /// In path order.
+    pub repos: Vec<RepoResult>,
+    /// Directories discovery couldn't list, with the error. Repos under them
+    /// are missing from `repos`.
+    pub errors: Vec<(PathBuf, String)>,
+    pub timings: Timings,
+}
+
+/// Stops a scan early from another thread. Repos not started yet are
+/// skipped, and walks in progress stop at the next commit; both come back
+/// as `Outcome::Cancelled`.
+#[derive(Clone, Debug, Default)]
+pub struct Cancel(Arc<AtomicBool>);
+
+impl Cancel {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// What a scan is about to do, once discovery is done.
+#[derive(Debug)]
+pub struct Plan<'a> {
+    pub roots: &'a [PathBuf],
+    /// The repos to scan, in path order.
+    pub repos: &'a [PathBuf],
+    pub id: &'a Identity,
+    pub since: i64,
+    pub until: Option<i64>,
+}
+
+/// What a scan is doing, as it happens.
+#[derive(Debug)]
+pub enum Event<'a> {
+    /// Discovery is done, and no repo has been scanned yet.
+    Found(Plan<'a>),
+    /// A worker started on a repo.
+    Started { path: &'a Path, name: &'a str },
+    /// A repo is done for good: after its retry, if it needed one. Comes
+    /// once per repo.
+    Finished(&'a RepoResult),
+}
+
+/// Called from the worker threads as the scan goes.
+pub type EventHook<'a> = &'a (dyn Fn(Event) + Sync);
+
+/// Finds the repos `args` points at and scans them over its window.
+pub fn scan_report(args: &ScanArgs) -> Result<ScanReport, String> {
+    scan_report_with(args, &Cancel::default(), &|_| {})
+}
+
+/// `scan_report` that reports each repo to `on_event` as it's done, and
+/// stops early once `cancel` is. A cancelled scan still returns what it
+/// got that far. The events are the progress, so none is drawn on stderr.
+pub fn scan_report_with(
+    args: &ScanArgs,
+    cancel: &Cancel,
+    on_event: EventHook,
+) -> Result<ScanReport, String> {
+    let mut args = args.clone();
+    args.cancel = cancel.clone();
+    args.quiet = true;
+    scan_report_in(&args, args.window(), Some(on_event))
+}
+
+/// `scan_report` over `window`. Progress is shown on stderr unless
+/// `args.quiet`.
+pub(crate) fn scan_report_in(
+    args: &ScanArgs,
+    window: Window,
+    on_event: Option<EventHook>,
+) -> Result<ScanReport, String> {
+    if args.trust_all {
+        trust_all();
+    }
+    let started = Instant::now();
+    let Discovery {
+        roots,
+        repos,
+        unreadable,
+    } = discover(args)?;
+    let discovery = started.elapsed();
+    let emit = |e: Event| {
+        if let Some(hook) = on_event {
+            hook(e);
+        }
+    };
+    let id = args.identity();
+    let (since, until) = resolve_window(window)?;
+    emit(Event::Found(Plan {
+        roots: &roots,
+        repos: &repos,
+        id: &id,
+        since,
+        until,
+    }));
+
+    let progress = progress::Progress::new(repos.len(), args.quiet);
+    let attempts: Vec<(RepoResult, Option<String>)> = repos
+        .par_iter()
+        .map(|r| {
+            let mut result = RepoResult {
+                path: r.clone(),
+                name: repo_name(&roots, r),
+                commits: Vec::new(),
+                outcome: Outcome::Cancelled,
+                elapsed: Duration::ZERO,
+            };
+            if args.cancel.is_cancelled() {
+                emit(Event::Finished(&result));
+                return (result, None);
+            }
+            progress.start(&result.name);
+            emit(Event::Started {
+                path: r,
+                name: &result.name,
+            });
+            let started = Instant::now();
+            let (commits, err) = scan_repo(r, since, until, &id, args);
+            result.commits = commits;
+            result.elapsed = started.elapsed();
+            progress.finish(&result.name);
+            let retry = match err {
+                None => {
+                    result.outcome = Outcome::Scanned;
+                    None
+                }
+                Some(e) if e.starts_with(CANCELLED) => None,
+                Some(e) if e.starts_with(UNBORN) => {
+                    result.outcome = Outcome::Unborn(e);
+                    None
+                }
+                Some(e) if e.starts_with(TIMED_OUT) => {
+                    result.outcome = Outcome::Failed(e);
+                    None
+                }
+                Some(e) => Some(e),
+            };
+            if retry.is_none() {
+                emit(Event::Finished(&result));
+            }
+            (result, retry)
+        })
+        .collect();
+    progress.clear();
+
+    // Transient failures (a lock held by an IDE, a flaky fetch) have usually
+    // cleared by the time every other repo is done, so give each one more go
+    // instead of silently dropping it from the results.
+    let mut results = Vec::with_capacity(attempts.len());
+    for (mut result, retry) in attempts {
+        if let Some(first_err) = retry {
+            let started = Instant::now();
+            let (again, err) = match args.cancel.is_cancelled() {
+                true => (Vec::new(), Some(CANCELLED.to_string())),
+                false => scan_repo(&result.path, since, until, &id, args),
+            };
+            result.elapsed += started.elapsed();
+            result.outcome = match err {
+                None => Outcome::Retried(first_err),
+                Some(e) if e.starts_with(CANCELLED) => Outcome::Cancelled,
+                Some(e) => Outcome::Failed(e),
+            };
+            if !again.is_empty() {
+                result.commits = again;
+            }
+            emit(Event::Finished(&result));
+        }
+        results.push(result);
+    }
+
+    Ok(ScanReport {
+        roots,
+        id,
+        since,
+        until,
+        repos: results,
+        errors: unreadable,
+        timings: Timings {
+            discovery,
+            scan: started.elapsed() - discovery,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use work::fixtures::FixtureRepo;
+
+    #[test]
+    fn reports_each_repo_on_its_own() {
+        let tmp = tempfile::tempdir().unwrap();
+        let api = FixtureRepo::init(&tmp.path().join("api")).unwrap();
+        api.commit(1_772_452_800, "Add login", &[("a.rs", "a\n")])
+            .unwrap();
+        api.commit(1_772_456_400, "Add logout", &[("a.rs", "a\nb\n")])
+            .unwrap();
+        FixtureRepo::init(&tmp.path().join("empty")).unwrap();
+
+        let root = tmp.path().to_str().unwrap();
+        let args = ScanArgs::parse_from([root, "--all", "--since", "2026-03-01", "-q"]).unwrap();
+        let report = scan_report(&args).unwrap();
+        let got: Vec<(&str, usize, bool)> = report
+            .repos
+            .iter()
+            .map(|r| {
+                (
+                    r.name.as_str(),
+                    r.commits.len(),
+                    r.outcome == Outcome::Scanned,
+                )
+            })
+            .collect();
+        assert_eq!(got, [("api", 2, true), ("empty", 0, false)]);
+        assert!(matches!(&report.repos[1].outcome, Outcome::Unborn(e) if e.contains("unborn")));
+        assert_eq!(report.repos[0].commits[0].summary, "Add logout");
+        assert!(report.errors.is_empty());
+
+        assert!(ScanArgs::parse_from([root, "--days", "soon"]).is_err());
+    }
+
+    #[test]
+    fn applies_trust_all_for_library_callers() {
+        let tmp = tempfile::tempdir().unwrap();
+        FixtureRepo::init(&tmp.path().join("api")).unwrap();
+        let root = tmp.path().to_str().unwrap();
+        let args = ScanArgs::parse_from([root, "--trust-all", "-q"]).unwrap();
+        scan_report(&args).unwrap();
+        assert!(crate::TRUST_ALL.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn reports_progress_and_stops_when_cancelled() {
+        let tmp = tempfile::tempdir().unwrap();
+        for name in ["a", "b", "c"] {
+            let fx = FixtureRepo::init(&tmp.path().join(name)).unwrap();
+            fx.commit(1_772_452_800, name, &[("f", name)]).unwrap();
+        }
+        let args = ScanArgs::parse_from([
+            tmp.path().to_str().unwrap(),
+            "--all",
+            "--since",
+            "2026-03-01",
+        ])
+        .unwrap();
+
+        let events = std::sync::Mutex::new(Vec::new());
+        let hook = |e: Event| {
+            let e = match e {
+                Event::Found(plan) => format!("found {}", plan.repos.len()),
+                Event::Started { name, .. } => format!("start {name}"),
+                Event::Finished(r) => format!("done {} {}", r.name, r.commits.len()),
+            };
+            events.lock().unwrap().push(e);
+        };
+        let report = scan_report_with(&args, &Cancel::default(), &hook).unwrap();
+        assert!(report.repos.iter().all(|r| r.outcome == Outcome::Scanned));
+        let mut events = events.into_inner().unwrap();
+        assert_eq!(events[0], "found 3");
+        events.sort();
+        assert_eq!(
+            events,
+            [
+                "done a 1", "done b 1", "done c 1", "found 3", "start a", "start b", "start c"
+            ]
+        );
+
+        let cancel = Cancel::default();
+        cancel.cancel();
+        let report = scan_report_with(&args, &cancel, &|_| {}).unwrap();
+        assert_eq!(report.repos.len(), 3);
+        assert!(
+            report
+                .repos
+                .iter()
+                .all(|r| r.outcome == Outcome::Cancelled && r.commits.is_empty())
+        );
+    }
+}
@@ -0,0 +1,103 @@
+use git2::Oid;
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+
+/// Hashes kept per view. Plenty for any window people look at, and keeps
+/// the file from growing forever in a long-running watch loop.
+const KEEP: usize = 10_000;
+
+/// The commits earlier `--new-only` runs of one view have shown.
+pub struct Seen {
+    path: PathBuf,
+    /// Oldest first, so trimming drops the longest-seen.
+    order: Vec<Oid>,
+    set: HashSet<Oid>,
+}
+
+/// `$XDG_STATE_HOME/work/seen/<view>`, falling back to `~/.local/state`.
+/// Runs outside a named view share `default`.
+pub fn path(view: Option<&str>) -> Result<PathBuf, String> {
+    let state = match std::env::var_os("XDG_STATE_HOME").filter(|v| !v.is_empty()) {
+        Some(d) => PathBuf::from(d),
+        None => std::env::var_os("HOME")
+            .map(|h| PathBuf::from(h).join(".local/state"))
+            .ok_or("work: HOME is not set, so --new-only has nowhere to keep state")?,
+    };
+    let name: String = view
+        .unwrap_or("default")
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    Ok(state.join("work").join("seen").join(name))
+}
+
+impl Seen {
+    /// A missing file is a first run: nothing seen yet.
+    pub fn load(path: PathBuf) -> Result<Self, String> {
+        let text = match fs::read_to_string(&path) {
+            Ok(t) => t,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(format!("work: cannot read '{}': {e}", path.display())),
+        };
+        let order: Vec<Oid> = text
+            .lines()
+            .filter_map(|l| Oid::from_str(l.trim()).ok())
+            .collect();
+        let set = order.iter().copied().collect();
+        Ok(Seen { path, order, set })
+    }
+
+    pub fn contains(&self, oid: &Oid) -> bool {
+        self.set.contains(oid)
+    }
+
+    pub fn record(&mut self, oids: impl IntoIterator<Item = Oid>) {
+        for oid in oids {
+            if self.set.insert(oid) {
+                self.order.push(oid);
+            }
+        }
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("work: cannot create '{}': {e}", dir.display()))?;
+        }
+        let keep = &self.order[self.order.len().saturating_sub(KEEP)..];
+        let text: String = keep.iter().map(|o| format!("{o}\n")).collect();
+        fs::write(&self.path, text)
+            .map_err(|e| format!("work: cannot write '{}': {e}", self.path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remembers_across_runs() {
+        let tmp = tempfile::tempdir().unwrap();
+        let file = tmp.path().join("seen/team");
+        let a = Oid::from_str("1111111111111111111111111111111111111111").unwrap();
+        let b = Oid::from_str("2222222222222222222222222222222222222222").unwrap();
+
+        let mut seen = Seen::load(file.clone()).unwrap();
+        assert!(!seen.contains(&a));
+        seen.record([a, a]);
+        seen.save().unwrap();
+
+        let mut seen = Seen::load(file.clone()).unwrap();
+        assert!(seen.contains(&a) && !seen.contains(&b));
+        seen.record([b]);
+        seen.save().unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap().lines().count(), 2);
+    }
+}
@@ -0,0 +1,104 @@
+use crate::CommitLine;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Time assumed to have gone into the first commit of a session, since git
+/// only records when work ended, not when it started.
+pub const SESSION_LEAD_SECS: i64 = 30 * 60;
+
+#[derive(clap::Args, Debug)]
+pub struct SessionArgs {
+    /// Minutes between commits that start a new session
+    #[arg(long, default_value = "120")]
+    pub session_gap: i64,
+}
+
+impl SessionArgs {
+    pub fn gap_secs(&self) -> i64 {
+        self.session_gap.saturating_mul(60)
+    }
+}
+
+/// A run of commits in one repo with no gap longer than the session gap.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Session {
+    pub repo: PathBuf,
+    pub start: i64,
+    pub end: i64,
+    pub commits: usize,
+    pub insertions: usize,
+    pub deletions: usize,
+}
+
+/// Clusters commits per repo into sessions, oldest first. A new session
+/// starts whenever two consecutive commits are more than `gap` seconds apart.
+pub fn infer_sessions(commits: &[CommitLine], gap: i64) -> Vec<Session> {
+    let mut by_repo: BTreeMap<&PathBuf, Vec<&CommitLine>> = BTreeMap::new();
+    for c in commits {
+        by_repo.entry(&c.repo).or_default().push(c);
+    }
+
+    let mut out = Vec::new();
+    for (repo, mut cs) in by_repo {
+        cs.sort_by_key(|c| c.time);
+
+        let mut current: Option<Session> = None;
+        for c in cs {
+            match current.as_mut() {
+                Some(s) if c.time - s.end <= gap => {
+                    s.end = c.time;
+                    s.commits += 1;
+                    s.insertions = s.insertions.saturating_add(c.insertions);
+                    s.deletions = s.deletions.saturating_add(c.deletions);
+                }
+                _ => {
+                    out.extend(current.take());
+                    current = Some(Session {
+                        repo: repo.clone(),
+                        start: c.time.saturating_sub(SESSION_LEAD_SECS),
+                        end: c.time,
+                        commits: 1,
+                        insertions: c.insertions,
+                        deletions: c.deletions,
+                    });
+                }
+            }
+        }
+        out.extend(current);
+    }
+
+    out.sort_by_key(|s| s.start);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(repo: &str, time: i64) -> CommitLine {
+        CommitLine {
+            insertions: 1,
+            ..CommitLine::stub(repo, time)
+        }
+    }
+
+    #[test]
+    fn splits_on_gap_and_repo() {
+        let hour = 60 * 60;
+        let commits = vec![
+            line("a", 10 * hour),
+            line("a", 11 * hour),
+            line("b", 11 * hour),
+            line("a", 15 * hour),
+        ];
+
+        let got = infer_sessions(&commits, 2 * hour);
+        assert_eq!(got.len(), 3);
+        assert_eq!(got[0].repo, PathBuf::from("a"));
+        assert_eq!(got[0].start, 10 * hour - SESSION_LEAD_SECS);
+        assert_eq!(got[0].end, 11 * hour);
+        assert_eq!(got[0].commits, 2);
+        assert_eq!(got[1].repo, PathBuf::from("b"));
+        assert_eq!(got[2].commits, 1);
+    }
+}
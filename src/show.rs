@@ -0,0 +1,245 @@
+use crate::open::find;
+use crate::{ScanArgs, format_time_local, open_repo, paint, repo_name};
+use git2::{Delta, Diff, DiffFormat, Repository};
+use std::path::PathBuf;
+
+#[derive(clap::Args, Debug)]
+pub struct ShowArgs {
+    /// Commit hash, or a unique prefix of one like the log's short hashes
+    #[arg(value_name = "HASH")]
+    pub hash: String,
+
+    #[command(flatten)]
+    pub scan: ScanArgs,
+
+    /// Also print the patch, colored like `git show`
+    #[arg(short = 'p', long)]
+    pub patch: bool,
+}
+
+/// The commit's changes against its first parent, like `git show
+/// --first-parent`, with renames found.
+fn commit_diff<'r>(repo: &'r Repository, commit: &git2::Commit) -> Result<Diff<'r>, git2::Error> {
+    let tree = commit.tree()?;
+    let parent = match commit.parent(0) {
+        Ok(p) => Some(p.tree()?),
+        Err(_) => None,
+    };
+    let mut diff = repo.diff_tree_to_tree(parent.as_ref(), Some(&tree), None)?;
+    diff.find_similar(None)?;
+    Ok(diff)
+}
+
+/// The letter `git show --name-status` uses.
+fn status_letter(status: Delta) -> char {
+    match status {
+        Delta::Added => 'A',
+        Delta::Deleted => 'D',
+        Delta::Renamed => 'R',
+        Delta::Copied => 'C',
+        Delta::Typechange => 'T',
+        _ => 'M',
+    }
+}
+
+/// `A src/new.rs  +12 -0` per file, then the totals.
+fn file_list(diff: &Diff) -> String {
+    let mut rows = Vec::new();
+    let (mut insertions, mut deletions) = (0, 0);
+    for (idx, delta) in diff.deltas().enumerate() {
+        let old = delta.old_file().path().map(|p| p.display().to_string());
+        let new = delta.new_file().path().map(|p| p.display().to_string());
+        let path = match (delta.status(), old, new) {
+            (Delta::Renamed | Delta::Copied, Some(old), Some(new)) => format!("{old} → {new}"),
+            (_, old, new) => new.or(old).unwrap_or_default(),
+        };
+        let lines = match git2::Patch::from_diff(diff, idx) {
+            Ok(Some(patch)) if !delta.flags().is_binary() => {
+                patch.line_stats().ok().map(|(_, i, d)| (i, d))
+            }
+            _ => None,
+        };
+        if let Some((i, d)) = lines {
+            insertions += i;
+            deletions += d;
+        }
+        rows.push((status_letter(delta.status()), path, lines));
+    }
+
+    let width = rows
+        .iter()
+        .map(|(_, p, _)| p.chars().count())
+        .max()
+        .unwrap_or(0);
+    let mut out = String::new();
+    for (status, path, lines) in &rows {
+        let lines = match lines {
+            Some((i, d)) => format!(
+                "{} {}",
+                paint("32", format!("+{i}")),
+                paint("31", format!("-{d}"))
+            ),
+            None => paint("2", "binary"),
+        };
+        let pad = width - path.chars().count();
+        out += &format!(" {status} {path}{:pad$}  {lines}\n", "");
+    }
+    out += &format!(
+        "{} file{} changed, {} {}\n",
+        rows.len(),
+        if rows.len() == 1 { "" } else { "s" },
+        paint("32", format!("+{insertions}")),
+        paint("31", format!("-{deletions}"))
+    );
+    out
+}
+
+/// The diff as a patch, in git's colors.
+fn patch_text(diff: &Diff) -> String {
+    let mut out = String::new();
+    let _ = diff.print(DiffFormat::Patch, |_, _, line| {
+        let text = String::from_utf8_lossy(line.content());
+        let text = text.trim_end_matches('\n');
+        let painted = match line.origin() {
+            '+' => paint("32", format!("+{text}")),
+            '-' => paint("31", format!("-{text}")),
+            ' ' => format!(" {text}"),
+            'H' => paint("36", text),
+            // A file's header comes as one block of lines.
+            'F' => text
+                .lines()
+                .map(|l| paint("1", l))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            _ => text.to_string(),
+        };
+        out += &painted;
+        out.push('\n');
+        true
+    });
+    out
+}
+
+/// Hash, repo, author (and committer, when it's someone else or another
+/// time), parents of a merge, the full message and the files changed.
+fn describe(
+    repo: &Repository,
+    commit: &git2::Commit,
+    name: &str,
+    patch: bool,
+) -> Result<String, String> {
+    let mut out = format!("{}\n", paint("33", format!("commit {}", commit.id())));
+    out += &format!("Repo:    {name}\n");
+    if commit.parent_count() > 1 {
+        let parents: Vec<String> = commit
+            .parent_ids()
+            .map(|id| id.to_string()[..7].to_string())
+            .collect();
+        out += &format!("Merge:   {}\n", parents.join(" "));
+    }
+    let (author, committer) = (commit.author(), commit.committer());
+    let who = |s: &git2::Signature| {
+        format!(
+            "{} <{}>, {}",
+            String::from_utf8_lossy(s.name_bytes()),
+            String::from_utf8_lossy(s.email_bytes()),
+            format_time_local(s.when().seconds())
+        )
+    };
+    out += &format!("Author:  {}\n", who(&author));
+    if who(&committer) != who(&author) {
+        out += &format!("Commit:  {}\n", who(&committer));
+    }
+    out.push('\n');
+    let message = String::from_utf8_lossy(commit.message_bytes());
+    for line in message.trim_end().lines() {
+        out += format!("    {line}").trim_end();
+        out.push('\n');
+    }
+
+    let diff =
+        commit_diff(repo, commit).map_err(|e| format!("work: cannot diff {}: {e}", commit.id()))?;
+    out.push('\n');
+    out += &file_list(&diff);
+    if patch {
+        out.push('\n');
+        out += &patch_text(&diff);
+    }
+    Ok(out)
+}
+
+/// `work show <hash>`: finds the commit in the discovered repos and prints
+/// it whole, without having to know which repo it was made in.
+pub fn show(args: ShowArgs) -> Result<(), String> {
+    let found = find(&args.scan, &args.hash)?;
+    let path: &PathBuf = &found.repos[0];
+    let repo = open_repo(path).map_err(|e| format!("work: cannot open {}: {e}", path.display()))?;
+    let commit = repo
+        .find_commit(found.oid)
+        .map_err(|e| format!("work: cannot read {}: {e}", found.oid))?;
+
+    let mut name = repo_name(&found.roots, path);
+    // Clones of one repo all have it; the first is as good as any.
+    if found.repos.len() > 1 {
+        let others = found.repos.len() - 1;
+        name += &format!(
+            " (and {others} other clone{})",
+            if others == 1 { "" } else { "s" }
+        );
+    }
+    print!("{}", describe(&repo, &commit, &name, args.patch)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use work::fixtures::FixtureRepo;
+
+    #[test]
+    fn describes_a_commit_with_files_and_patch() {
+        crate::COLOR.store(false, std::sync::atomic::Ordering::Relaxed);
+        let tmp = tempfile::tempdir().unwrap();
+        let fx = FixtureRepo::init(&tmp.path().join("api")).unwrap();
+        fx.commit(1_772_452_800, "Add readme", &[("README.md", "hi\n")])
+            .unwrap();
+        let oid = fx
+            .commit(
+                1_772_456_400,
+                "Add upload queue\n\nRetries with backoff.\n",
+                &[
+                    ("README.md", "hi\nthere\n"),
+                    ("src/queue.rs", "fn q() {}\n"),
+                ],
+            )
+            .unwrap();
+        let repo = fx.repo();
+        let commit = repo.find_commit(oid).unwrap();
+
+        let text = describe(repo, &commit, "api", true).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], format!("commit {oid}"));
+        assert_eq!(lines[1], "Repo:    api");
+        assert!(lines[2].starts_with("Author:  Fixture Author <fixture@example.com>, 2026-03-0"));
+        assert_eq!(
+            lines[3..8],
+            [
+                "",
+                "    Add upload queue",
+                "",
+                "    Retries with backoff.",
+                ""
+            ]
+        );
+        assert_eq!(lines[8], " M README.md     +1 -0");
+        assert_eq!(lines[9], " A src/queue.rs  +1 -0");
+        assert_eq!(lines[10], "2 files changed, +2 -0");
+        assert!(text.contains("diff --git a/README.md b/README.md\n"));
+        assert!(text.contains("@@ -1 +1,2 @@\n hi\n+there\n"));
+        assert!(
+            !describe(repo, &commit, "api", false)
+                .unwrap()
+                .contains("diff --git")
+        );
+    }
+}
@@ -0,0 +1,99 @@
+use crate::{CommitLine, git_command, open_repo};
+use std::collections::HashMap;
+use std::io::Write as _;
+use std::path::Path;
+use std::process::Stdio;
+
+/// Whether a commit is signed, and whether the signature checks out.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Signature {
+    /// A good signature by a trusted key: one in the allowed signers file
+    /// for SSH, or one with full or ultimate trust for gpg.
+    Good,
+    /// Signed, but the signature is bad or couldn't be vouched for: an
+    /// untrusted, expired or revoked key, or nothing to check it with.
+    Bad,
+    Unsigned,
+}
+
+impl Signature {
+    pub fn mark(self) -> &'static str {
+        match self {
+            Signature::Good => "✓",
+            Signature::Bad => "✗",
+            Signature::Unsigned => "-",
+        }
+    }
+
+    /// For `--raw` and `--plain`.
+    pub fn word(self) -> &'static str {
+        match self {
+            Signature::Good => "good",
+            Signature::Bad => "bad",
+            Signature::Unsigned => "unsigned",
+        }
+    }
+
+    /// From git's `%G?`, for a commit known to carry a signature. Only `G`
+    /// counts: `U` is a valid signature by a key nobody vouched for, which
+    /// for SSH means anyone's key that isn't in the allowed signers file.
+    fn from_status(code: &str) -> Self {
+        match code {
+            "G" => Signature::Good,
+            _ => Signature::Bad,
+        }
+    }
+}
+
+/// `--show-signatures`: fills in each commit's `signature`. libgit2 finds
+/// the signed commits without running anything, but can't check them, so
+/// those go to one `git log --no-walk` per repo, which verifies them with
+/// the gpg, `gpg.ssh.allowedSignersFile` and trust settings git uses.
+pub fn verify(repo_path: &Path, commits: &mut [CommitLine]) {
+    let Ok(repo) = open_repo(repo_path) else {
+        return;
+    };
+    let mut signed = Vec::new();
+    for c in commits.iter_mut() {
+        c.signature = Some(match repo.extract_signature(&c.oid, None) {
+            Ok(_) => {
+                signed.push(c.oid);
+                Signature::Bad
+            }
+            Err(_) => Signature::Unsigned,
+        });
+    }
+    if signed.is_empty() {
+        return;
+    }
+
+    let statuses = git_statuses(repo_path, &signed).unwrap_or_default();
+    for c in commits.iter_mut() {
+        if let Some(code) = statuses.get(&c.oid.to_string()) {
+            c.signature = Some(Signature::from_status(code));
+        }
+    }
+}
+
+/// `%G?` for each commit in `oids`, keyed by full hash. `None` if git
+/// couldn't be run, which leaves the signed commits unverified.
+fn git_statuses(repo_path: &Path, oids: &[git2::Oid]) -> Option<HashMap<String, String>> {
+    let mut child = git_command()
+        .args(["log", "--no-walk=unsorted", "--stdin", "--format=%H %G?"])
+        .current_dir(repo_path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+    let input: String = oids.iter().map(|o| format!("{o}\n")).collect();
+    child.stdin.take()?.write_all(input.as_bytes()).ok()?;
+    let output = child.wait_with_output().ok()?;
+    Some(
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|l| l.split_once(' '))
+            .map(|(hash, code)| (hash.to_string(), code.to_string()))
+            .collect(),
+    )
+}
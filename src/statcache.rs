@@ -0,0 +1,226 @@
+use crate::DiffStats;
+use git2::{ObjectType, Oid, Repository};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Lookups in every repo's cache this run, for the usage log.
+pub static HITS: AtomicUsize = AtomicUsize::new(0);
+pub static MISSES: AtomicUsize = AtomicUsize::new(0);
+
+/// Commits kept per repo, most recently used last. About 1 MB at most.
+const KEEP: usize = 20_000;
+
+/// `$XDG_CACHE_HOME/work/stats`, falling back to `~/.cache`.
+pub fn dir() -> Option<PathBuf> {
+    let cache = match std::env::var_os("XDG_CACHE_HOME").filter(|v| !v.is_empty()) {
+        Some(d) => PathBuf::from(d),
+        None => PathBuf::from(std::env::var_os("HOME")?).join(".cache"),
+    };
+    Some(cache.join("work").join("stats"))
+}
+
+/// One repo's diff stats from earlier runs. A commit's stats never change,
+/// but what they count does: the stat pathspecs, `--ignore-whitespace` and
+/// the repo's top-level attributes (`linguist-generated`) go into a
+/// fingerprint, and a file with a different one starts over. Failing to
+/// read or write the cache only costs the speedup, so errors are ignored.
+pub struct StatCache {
+    path: PathBuf,
+    fingerprint: Oid,
+    /// Oldest first, so trimming drops the longest-unused.
+    order: Vec<Oid>,
+    stats: HashMap<Oid, DiffStats>,
+    used: Vec<Oid>,
+    used_set: HashSet<Oid>,
+    added: bool,
+}
+
+fn hash(bytes: &[u8]) -> Oid {
+    Oid::hash_object(ObjectType::Blob, bytes).unwrap_or_else(|_| Oid::zero())
+}
+
+/// Bump when the line format changes, so old files are started over.
+const FORMAT: &str = "v3";
+
+fn fingerprint(repo: &Repository, pathspecs: &[String], ignore_whitespace: bool) -> Oid {
+    let ws = if ignore_whitespace { "-w" } else { "" };
+    let mut key = format!("{FORMAT}{ws}\n{}", pathspecs.join("\n")).into_bytes();
+    let attrs = repo
+        .workdir()
+        .map(|w| w.join(".gitattributes"))
+        .into_iter()
+        .chain([repo.path().join("info/attributes")]);
+    for file in attrs {
+        key.push(0);
+        key.extend(fs::read(file).unwrap_or_default());
+    }
+    hash(&key)
+}
+
+/// `<oid> <files> <insertions> <deletions> <lfs files> <lfs bytes> <mode
+/// files> <mode insertions> <mode deletions>`
+fn parse_line(line: &str) -> Option<(Oid, DiffStats)> {
+    let mut parts = line.split(' ');
+    let oid = Oid::from_str(parts.next()?).ok()?;
+    let (files, insertions, deletions, lfs_files) = {
+        let mut num = || parts.next()?.parse::<usize>().ok();
+        (num()?, num()?, num()?, num()?)
+    };
+    let lfs_bytes = parts.next()?.parse().ok()?;
+    let mut num = || parts.next()?.parse::<usize>().ok();
+    let (mode_files, mode_insertions, mode_deletions) = (num()?, num()?, num()?);
+    Some((
+        oid,
+        DiffStats {
+            files,
+            insertions,
+            deletions,
+            lfs_files,
+            lfs_bytes,
+            mode_files,
+            mode_insertions,
+            mode_deletions,
+        },
+    ))
+}
+
+impl StatCache {
+    pub fn open(
+        dir: &Path,
+        repo: &Repository,
+        pathspecs: &[String],
+        ignore_whitespace: bool,
+    ) -> Self {
+        let name = hash(repo.path().as_os_str().as_encoded_bytes());
+        let mut cache = StatCache {
+            path: dir.join(name.to_string()),
+            fingerprint: fingerprint(repo, pathspecs, ignore_whitespace),
+            order: Vec::new(),
+            stats: HashMap::new(),
+            used: Vec::new(),
+            used_set: HashSet::new(),
+            added: false,
+        };
+        let Ok(text) = fs::read_to_string(&cache.path) else {
+            return cache;
+        };
+        let mut lines = text.lines();
+        if lines.next() != Some(&format!("fingerprint {}", cache.fingerprint)) {
+            return cache;
+        }
+        for (oid, stats) in lines.filter_map(parse_line) {
+            if cache.stats.insert(oid, stats).is_none() {
+                cache.order.push(oid);
+            }
+        }
+        cache
+    }
+
+    pub fn get(&mut self, oid: Oid) -> Option<DiffStats> {
+        let Some(stats) = self.stats.get(&oid).copied() else {
+            MISSES.fetch_add(1, Ordering::Relaxed);
+            return None;
+        };
+        HITS.fetch_add(1, Ordering::Relaxed);
+        if self.used_set.insert(oid) {
+            self.used.push(oid);
+        }
+        Some(stats)
+    }
+
+    pub fn insert(&mut self, oid: Oid, stats: DiffStats) {
+        self.stats.insert(oid, stats);
+        if self.used_set.insert(oid) {
+            self.used.push(oid);
+        }
+        self.added = true;
+    }
+
+    /// Writes the file back if this run diffed anything new. Written to a
+    /// temporary file and renamed, so a concurrent run never reads half.
+    fn save(&self) {
+        if !self.added {
+            return;
+        }
+        let order: Vec<Oid> = self
+            .order
+            .iter()
+            .filter(|o| !self.used_set.contains(o))
+            .chain(&self.used)
+            .copied()
+            .collect();
+        let mut text = format!("fingerprint {}\n", self.fingerprint);
+        for oid in &order[order.len().saturating_sub(KEEP)..] {
+            let s = self.stats[oid];
+            text.push_str(&format!(
+                "{oid} {} {} {} {} {} {} {} {}\n",
+                s.files,
+                s.insertions,
+                s.deletions,
+                s.lfs_files,
+                s.lfs_bytes,
+                s.mode_files,
+                s.mode_insertions,
+                s.mode_deletions
+            ));
+        }
+        let tmp = self.path.with_extension(std::process::id().to_string());
+        let _ = fs::create_dir_all(self.path.parent().unwrap_or(Path::new(".")));
+        if fs::write(&tmp, text).is_ok() && fs::rename(&tmp, &self.path).is_err() {
+            let _ = fs::remove_file(&tmp);
+        }
+    }
+}
+
+/// Saving on drop keeps what a walk diffed before it broke off.
+impl Drop for StatCache {
+    fn drop(&mut self) {
+        self.save();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reuses_stats_until_the_pathspecs_change() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = Repository::init(tmp.path().join("app")).unwrap();
+        let dir = tmp.path().join("cache");
+        let (a, b) = (
+            Oid::hash_object(ObjectType::Blob, b"a").unwrap(),
+            Oid::zero(),
+        );
+        let specs = vec!["!*.lock".to_string()];
+        let stats = DiffStats {
+            files: 2,
+            insertions: 10,
+            deletions: 3,
+            lfs_files: 1,
+            lfs_bytes: -2048,
+            mode_files: 1,
+            mode_insertions: 1,
+            mode_deletions: 1,
+        };
+
+        let mut cache = StatCache::open(&dir, &repo, &specs, false);
+        assert_eq!(cache.get(a), None);
+        cache.insert(a, stats);
+        drop(cache);
+
+        let mut cache = StatCache::open(&dir, &repo, &specs, false);
+        assert_eq!(cache.get(a), Some(stats));
+        assert_eq!(cache.get(b), None);
+        drop(cache);
+
+        let mut cache = StatCache::open(&dir, &repo, &["docs".to_string()], false);
+        assert_eq!(cache.get(a), None);
+        drop(cache);
+
+        let mut cache = StatCache::open(&dir, &repo, &specs, true);
+        assert_eq!(cache.get(a), None);
+    }
+}
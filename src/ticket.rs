@@ -0,0 +1,247 @@
+use crate::render::Renderer;
+use crate::{Args, CommitLine, Scan, paint, raw_field, zoned};
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::io::{self, Write};
+use std::path::Path;
+
+/// Jira-style keys (`PROJ-123`) and issue numbers (`#42`).
+pub const DEFAULT_PATTERN: &str = r"\b[A-Z][A-Z0-9]*-\d+\b|#\d+\b";
+
+/// `--by-ticket`'s pattern.
+pub fn parse_pattern(s: &str) -> Result<Regex, String> {
+    Regex::new(s).map_err(|e| format!("invalid regex '{s}': {e}"))
+}
+
+/// The tickets a summary mentions, each once: a capture group's text if
+/// the pattern has one, the whole match if not. `#42` only means something
+/// within its repo, so those are prefixed with the repo's name.
+fn tickets(re: &Regex, summary: &str, repo: &str) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    for caps in re.captures_iter(summary) {
+        let Some(m) = caps.get(1).or_else(|| caps.get(0)) else {
+            continue;
+        };
+        let key = match m.as_str() {
+            k if k.starts_with('#') => format!("{repo}{k}"),
+            k => k.to_string(),
+        };
+        if !out.contains(&key) {
+            out.push(key);
+        }
+    }
+    out
+}
+
+/// One ticket's share of the window. `ticket` is `None` for the commits
+/// that don't mention one.
+#[derive(Debug)]
+struct TicketRow {
+    ticket: Option<String>,
+    commits: usize,
+    repos: usize,
+    /// Local days with a commit for it.
+    days: usize,
+    insertions: usize,
+    deletions: usize,
+}
+
+/// Busiest ticket first, commits without one last. A commit naming two
+/// tickets counts toward both.
+fn group<'a>(scan: &Scan, commits: &[&'a CommitLine], re: &Regex) -> Vec<TicketRow> {
+    #[derive(Default)]
+    struct Acc<'a> {
+        commits: usize,
+        repos: HashSet<&'a Path>,
+        days: HashSet<String>,
+        insertions: usize,
+        deletions: usize,
+    }
+
+    let mut by_ticket: HashMap<Option<String>, Acc<'a>> = HashMap::new();
+    for c in commits {
+        let found = tickets(re, &c.summary, &scan.name(&c.repo));
+        let keys: Vec<Option<String>> = match found.is_empty() {
+            true => vec![None],
+            false => found.into_iter().map(Some).collect(),
+        };
+        for key in keys {
+            add(by_ticket.entry(key).or_default(), c);
+        }
+    }
+
+    fn add<'a>(acc: &mut Acc<'a>, c: &'a CommitLine) {
+        acc.commits += 1;
+        acc.repos.insert(&c.repo);
+        if let Some(day) = zoned(c.time) {
+            acc.days.insert(day.format("%Y-%m-%d").to_string());
+        }
+        acc.insertions = acc.insertions.saturating_add(c.insertions);
+        acc.deletions = acc.deletions.saturating_add(c.deletions);
+    }
+
+    let mut rows: Vec<TicketRow> = by_ticket
+        .into_iter()
+        .map(|(ticket, acc)| TicketRow {
+            ticket,
+            commits: acc.commits,
+            repos: acc.repos.len(),
+            days: acc.days.len(),
+            insertions: acc.insertions,
+            deletions: acc.deletions,
+        })
+        .collect();
+    rows.sort_by(|a, b| {
+        a.ticket
+            .is_none()
+            .cmp(&b.ticket.is_none())
+            .then_with(|| b.commits.cmp(&a.commits))
+            .then_with(|| a.ticket.cmp(&b.ticket))
+    });
+    rows
+}
+
+fn name(ticket: &Option<String>) -> &str {
+    ticket.as_deref().unwrap_or("(no ticket)")
+}
+
+fn plural(n: usize, word: &str) -> String {
+    format!("{n} {word}{}", if n == 1 { "" } else { "s" })
+}
+
+/// `--by-ticket`: commits, repos, days and LoC per ticket named in commit
+/// summaries, for ticket-based timesheets. Given every commit in the
+/// window.
+pub struct Tickets<'a> {
+    pub args: &'a Args,
+    pub pattern: &'a Regex,
+}
+
+impl Renderer for Tickets<'_> {
+    fn render(&self, out: &mut dyn Write, scan: &Scan, commits: &[&CommitLine]) -> io::Result<()> {
+        let args = self.args;
+        let rows = group(scan, commits, self.pattern);
+
+        if args.raw {
+            // ticket\tcommits\trepos\tdays\t+ins\t-del
+            let sep = if args.null { "\0" } else { "\t" };
+            for r in &rows {
+                let ticket = r
+                    .ticket
+                    .as_deref()
+                    .map_or("-".to_string(), |t| raw_field(t, args.null));
+                let fields = [
+                    ticket,
+                    r.commits.to_string(),
+                    r.repos.to_string(),
+                    r.days.to_string(),
+                    format!("+{}", r.insertions),
+                    format!("-{}", r.deletions),
+                ];
+                writeln!(out, "{}", fields.join(sep))?;
+            }
+            return Ok(());
+        }
+
+        let width = rows
+            .iter()
+            .map(|r| name(&r.ticket).chars().count())
+            .max()
+            .unwrap_or(0);
+        let commits_width = rows
+            .iter()
+            .map(|r| plural(r.commits, "commit").len())
+            .max()
+            .unwrap_or(1);
+        for r in &rows {
+            writeln!(
+                out,
+                "{}  {:>commits_width$}  {:>8}  {:>7}  {} {}",
+                paint("1", format!("{:<width$}", name(&r.ticket))),
+                plural(r.commits, "commit"),
+                plural(r.repos, "repo"),
+                plural(r.days, "day"),
+                paint("32", format!("+{}", r.insertions)),
+                paint("31", format!("-{}", r.deletions)),
+            )?;
+        }
+        let without = rows
+            .iter()
+            .find(|r| r.ticket.is_none())
+            .map_or(0, |r| r.commits);
+        writeln!(
+            out,
+            "\n{} of {} commits mention a ticket ({})",
+            commits.len() - without,
+            commits.len(),
+            args.scan.window().label()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[test]
+    fn groups_commits_by_ticket() {
+        let re = parse_pattern(DEFAULT_PATTERN).unwrap();
+        assert_eq!(
+            tickets(&re, "PROJ-12: fix login (see #7, PROJ-12)", "api"),
+            ["PROJ-12", "api#7"]
+        );
+        assert!(tickets(&re, "Bump deps to v1-2", "api").is_empty());
+        let custom = parse_pattern(r"\[(\w+-\d+)\]").unwrap();
+        assert_eq!(tickets(&custom, "[OPS-3] rotate keys", "api"), ["OPS-3"]);
+        assert!(parse_pattern("(oops").is_err());
+
+        let line = |repo: &str, time: i64, summary: &str| CommitLine {
+            summary: summary.to_string(),
+            insertions: 10,
+            deletions: 1,
+            ..CommitLine::stub(repo, time)
+        };
+        let day = 24 * 60 * 60;
+        let scan = Scan::stub(
+            &["/code"],
+            vec![
+                line("/code/api", 1_772_452_800, "PROJ-1 Add login"),
+                line("/code/web", 1_772_452_800 + day, "PROJ-1 Login form"),
+                line(
+                    "/code/api",
+                    1_772_452_800,
+                    "PROJ-2 and PROJ-1: shared session",
+                ),
+                line("/code/api", 1_772_452_800, "Fix typo"),
+            ],
+        );
+        let commits: Vec<&CommitLine> = scan.commits.iter().collect();
+        let rows = group(&scan, &commits, &re);
+        let got: Vec<(&str, usize, usize, usize, usize)> = rows
+            .iter()
+            .map(|r| (name(&r.ticket), r.commits, r.repos, r.days, r.insertions))
+            .collect();
+        assert_eq!(
+            got,
+            [
+                ("PROJ-1", 3, 2, 2, 30),
+                ("PROJ-2", 1, 1, 1, 10),
+                ("(no ticket)", 1, 1, 1, 10)
+            ]
+        );
+
+        let args = Args::parse_from(["work", "--by-ticket", "--raw", "x"]);
+        let mut out = Vec::new();
+        Tickets {
+            args: &args,
+            pattern: &re,
+        }
+        .render(&mut out, &scan, &commits)
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "PROJ-1\t3\t2\t2\t+30\t-3\nPROJ-2\t1\t1\t1\t+10\t-1\n-\t1\t1\t1\t+10\t-1\n"
+        );
+    }
+}
@@ -0,0 +1,151 @@
+use crate::session::{SessionArgs, infer_sessions};
+use crate::{Scan, ScanArgs, finish_export, scan};
+use serde_json::Value;
+use std::path::Path;
+use std::process::Command;
+
+#[derive(clap::Args, Debug)]
+pub struct TimewArgs {
+    #[command(flatten)]
+    pub scan: ScanArgs,
+
+    #[command(flatten)]
+    pub sessions: SessionArgs,
+
+    /// Extra tag for every interval (repeatable)
+    #[arg(long = "tag", value_name = "TAG")]
+    pub tags: Vec<String>,
+
+    /// Print the `timew` commands instead of running them
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Exit with status 1 if any repo couldn't be scanned
+    #[arg(long)]
+    pub strict: bool,
+}
+
+/// timewarrior's own compact UTC format (what `timew export` prints).
+fn timew_time(ts: i64) -> String {
+    use chrono::{TimeZone, Utc};
+    Utc.timestamp_opt(ts, 0)
+        .single()
+        .map(|d| d.format("%Y%m%dT%H%M%SZ").to_string())
+        .unwrap_or_else(|| ts.to_string())
+}
+
+/// Tags for a session: the repo's name and, for nested repos, its top-level
+/// directory, which usually names the client or org (`acme/api` is tagged
+/// `acme/api` and `acme`).
+fn session_tags(name: &str, extra: &[String]) -> Vec<String> {
+    let mut tags = vec![name.to_string()];
+    let mut parts = Path::new(name).components();
+    if let (Some(first), Some(_)) = (parts.next(), parts.next()) {
+        tags.push(first.as_os_str().to_string_lossy().into_owned());
+    }
+    tags.extend(extra.iter().cloned());
+    tags
+}
+
+fn timew(args: &[String]) -> Result<std::process::Output, String> {
+    Command::new("timew")
+        .args(args)
+        .output()
+        .map_err(|e| format!("timewarrior: cannot run timew: {e}"))
+}
+
+/// Backfills timewarrior with one `timew track` interval per inferred
+/// session. Intervals already tracked by an earlier sync (same start, same
+/// repo tag) are skipped, or extended when the session has grown since.
+pub fn sync(tw: TimewArgs) -> Result<(), String> {
+    let scan = scan(&tw.scan)?;
+    let tracked = track(&tw, &scan);
+    finish_export(tw.strict, &scan, tracked)
+}
+
+fn track(tw: &TimewArgs, scan: &Scan) -> Result<(), String> {
+    let sessions = infer_sessions(&scan.commits, tw.sessions.gap_secs());
+    if sessions.is_empty() {
+        println!("No sessions to track");
+        return Ok(());
+    }
+
+    let first = sessions.iter().map(|s| s.start).min().unwrap_or(scan.since);
+    let existing: Vec<Value> = if tw.dry_run {
+        Vec::new()
+    } else {
+        let out = timew(&["export".into(), timew_time(first), "-".into(), "now".into()])?;
+        if !out.status.success() {
+            return Err(format!(
+                "timewarrior: timew export failed: {}",
+                String::from_utf8_lossy(&out.stderr).trim()
+            ));
+        }
+        serde_json::from_slice(&out.stdout)
+            .map_err(|e| format!("timewarrior: unexpected export output: {e}"))?
+    };
+
+    let (mut tracked, mut skipped, mut failed) = (0, 0, 0);
+    for s in &sessions {
+        let tags = session_tags(&scan.name(&s.repo), &tw.tags);
+        let start = timew_time(s.start);
+        let end = timew_time(s.end);
+
+        let previous = existing.iter().find(|iv| {
+            iv["start"].as_str() == Some(start.as_str())
+                && iv["tags"]
+                    .as_array()
+                    .is_some_and(|t| t.iter().any(|t| t.as_str() == Some(tags[0].as_str())))
+        });
+        // Timestamps are fixed-width UTC, so string order is time order.
+        let from = match previous.and_then(|iv| iv["end"].as_str()) {
+            Some(prev_end) if prev_end >= end.as_str() => {
+                skipped += 1;
+                continue;
+            }
+            Some(prev_end) => prev_end.to_string(),
+            None => start,
+        };
+
+        let mut cmd = vec!["track".to_string(), from, "-".into(), end];
+        cmd.extend(tags);
+
+        if tw.dry_run {
+            println!("timew {}", cmd.join(" "));
+            tracked += 1;
+            continue;
+        }
+
+        let out = timew(&cmd)?;
+        if out.status.success() {
+            tracked += 1;
+        } else {
+            // Usually an overlap with something tracked by hand; leave it be.
+            failed += 1;
+            eprintln!(
+                "timewarrior: {}: {}",
+                cmd.join(" "),
+                String::from_utf8_lossy(&out.stderr).trim()
+            );
+        }
+    }
+
+    if !tw.dry_run {
+        println!("Tracked {tracked} sessions ({skipped} already tracked, {failed} rejected)");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tags_sessions_by_repo_and_client() {
+        assert_eq!(session_tags("app", &[]), vec!["app"]);
+        assert_eq!(
+            session_tags("acme/api", &["billable".to_string()]),
+            vec!["acme/api", "acme", "billable"]
+        );
+    }
+}
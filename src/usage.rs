@@ -0,0 +1,280 @@
+use std::fs;
+use std::io::Write as _;
+use std::path::PathBuf;
+
+/// Scans kept. A few months of heavy use, and still a small file.
+const KEEP: usize = 2_000;
+
+#[derive(clap::Args, Debug)]
+pub struct StatsArgs {
+    /// Report on this tool's own recorded scans, with tuning suggestions
+    #[arg(long = "self", required = true)]
+    pub self_report: bool,
+}
+
+/// One recorded scan. Counts and timings only: no paths, names or commits.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Run {
+    pub time: i64,
+    pub repos: usize,
+    pub commits: usize,
+    pub millis: u64,
+    /// `-L`, and how deep under its root the deepest repo found was.
+    pub depth: usize,
+    pub deepest: usize,
+    /// Whether the stat cache was on, and its lookups.
+    pub cache: bool,
+    pub cache_hits: usize,
+    pub cache_misses: usize,
+    pub threads: usize,
+}
+
+/// `$XDG_STATE_HOME/work/usage.tsv`, falling back to `~/.local/state`.
+pub fn path() -> Option<PathBuf> {
+    let state = match std::env::var_os("XDG_STATE_HOME").filter(|v| !v.is_empty()) {
+        Some(d) => PathBuf::from(d),
+        None => PathBuf::from(std::env::var_os("HOME")?).join(".local/state"),
+    };
+    Some(state.join("work").join("usage.tsv"))
+}
+
+fn format_line(r: &Run) -> String {
+    format!(
+        "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\n",
+        r.time,
+        r.repos,
+        r.commits,
+        r.millis,
+        r.depth,
+        r.deepest,
+        u8::from(r.cache),
+        r.cache_hits,
+        r.cache_misses,
+        r.threads
+    )
+}
+
+fn parse_line(line: &str) -> Option<Run> {
+    let mut f = line.split('\t');
+    let mut num = || f.next()?.parse::<u64>().ok();
+    let [
+        time,
+        repos,
+        commits,
+        millis,
+        depth,
+        deepest,
+        cache,
+        hits,
+        misses,
+        threads,
+    ] = [(); 10].map(|_| num());
+    Some(Run {
+        time: time? as i64,
+        repos: repos? as usize,
+        commits: commits? as usize,
+        millis: millis?,
+        depth: depth? as usize,
+        deepest: deepest? as usize,
+        cache: cache? == 1,
+        cache_hits: hits? as usize,
+        cache_misses: misses? as usize,
+        threads: threads? as usize,
+    })
+}
+
+/// Appends a scan to the log (`record_usage = true` in the config file).
+/// Failing to write only loses the record, so errors are ignored.
+pub fn record(run: &Run) {
+    let Some(path) = path() else {
+        return;
+    };
+    let _ = fs::create_dir_all(path.parent().unwrap_or(&path));
+    let text = fs::read_to_string(&path).unwrap_or_default();
+    if text.lines().count() >= KEEP * 2 {
+        // Trim now and then rather than on every run.
+        let lines: Vec<&str> = text.lines().collect();
+        let mut kept = lines[lines.len() - KEEP..].join("\n");
+        kept.push('\n');
+        kept.push_str(&format_line(run));
+        let _ = fs::write(&path, kept);
+        return;
+    }
+    if let Ok(mut file) = fs::OpenOptions::new().create(true).append(true).open(&path) {
+        let _ = file.write_all(format_line(run).as_bytes());
+    }
+}
+
+/// The value `p` of the way up `sorted` (0.5 for the median).
+fn percentile<T: Copy + Default>(sorted: &[T], p: f64) -> T {
+    match sorted.len() {
+        0 => T::default(),
+        n => sorted[((n - 1) as f64 * p).round() as usize],
+    }
+}
+
+fn seconds(millis: u64) -> String {
+    format!("{:.1}s", millis as f64 / 1000.0)
+}
+
+/// What the recorded scans suggest changing, if anything.
+fn recommendations(runs: &[Run]) -> Vec<String> {
+    let mut out = Vec::new();
+    let Some(last) = runs.last() else {
+        return out;
+    };
+
+    let mut millis: Vec<u64> = runs.iter().map(|r| r.millis).collect();
+    millis.sort_unstable();
+    let slow = percentile(&millis, 0.5);
+
+    // Depth: only runs at the current -L say anything about it.
+    let at_depth: Vec<&Run> = runs.iter().filter(|r| r.depth == last.depth).collect();
+    let deepest = at_depth.iter().map(|r| r.deepest).max().unwrap_or(0);
+    let at_limit = at_depth.iter().filter(|r| r.deepest >= r.depth).count();
+    if at_limit * 4 >= at_depth.len() && at_limit > 0 {
+        out.push(format!(
+            "Repos often turn up right at the depth limit (-L {}), so some may be deeper still: try -L {}.",
+            last.depth,
+            last.depth + 1
+        ));
+    } else if at_depth.len() >= 5 && deepest + 1 < last.depth && deepest > 0 {
+        out.push(format!(
+            "No repo has been deeper than {deepest} under its root; -L {deepest} finds the same repos with less searching."
+        ));
+    }
+
+    // Cache: most scans without it, or a cache that keeps starting over.
+    let uncached = runs.iter().filter(|r| !r.cache).count();
+    let (hits, misses) = runs
+        .iter()
+        .fold((0, 0), |(h, m), r| (h + r.cache_hits, m + r.cache_misses));
+    if uncached * 2 > runs.len() {
+        out.push(
+            "Most scans ran without the stat cache (--no-stat-cache); leaving it on saves re-diffing the same commits every run."
+                .to_string(),
+        );
+    } else if runs.len() >= 10 && hits + misses > 0 && hits * 2 < hits + misses {
+        out.push(format!(
+            "Only {}% of diff stats came from the cache. Changing --stat-exclude, --path or -w between runs starts a repo's cache over.",
+            hits * 100 / (hits + misses)
+        ));
+    }
+
+    if slow > 3_000 {
+        let mut repos: Vec<usize> = runs.iter().map(|r| r.repos).collect();
+        repos.sort_unstable();
+        out.push(format!(
+            "A typical scan takes {} over {} repos: `work maintain` speeds up repos with bloated object stores, and `ignore` in the config file or --exclude-repo skips ones you never commit to. Scans use {} threads; set RAYON_NUM_THREADS to change that.",
+            seconds(slow),
+            percentile(&repos, 0.5),
+            last.threads
+        ));
+    }
+    out
+}
+
+/// `work stats --self`: typical repo counts, scan times and cache hit
+/// rates from the usage log, and what they suggest.
+pub fn stats(_args: StatsArgs) -> Result<(), String> {
+    let path = path().ok_or("work: HOME is not set, so there is no usage log")?;
+    let text = fs::read_to_string(&path).unwrap_or_default();
+    let runs: Vec<Run> = text.lines().filter_map(parse_line).collect();
+    if runs.is_empty() {
+        return Err(format!(
+            "work: no scans recorded yet. Usage is only recorded if you opt in with `record_usage = true` in the config file; it stays in {}",
+            path.display()
+        ));
+    }
+
+    let sorted = |f: fn(&Run) -> usize| {
+        let mut v: Vec<usize> = runs.iter().map(f).collect();
+        v.sort_unstable();
+        v
+    };
+    let (repos, commits) = (sorted(|r| r.repos), sorted(|r| r.commits));
+    let mut millis: Vec<u64> = runs.iter().map(|r| r.millis).collect();
+    millis.sort_unstable();
+    let first = crate::format_time_local(runs[0].time);
+
+    println!("{} scans recorded since {first}\n", runs.len());
+    println!(
+        "Repos per scan    median {}, 90th percentile {}",
+        percentile(&repos, 0.5),
+        percentile(&repos, 0.9)
+    );
+    println!(
+        "Commits per scan  median {}, 90th percentile {}",
+        percentile(&commits, 0.5),
+        percentile(&commits, 0.9)
+    );
+    println!(
+        "Scan time         median {}, 90th percentile {}",
+        seconds(percentile(&millis, 0.5)),
+        seconds(percentile(&millis, 0.9))
+    );
+    let (hits, misses) = runs
+        .iter()
+        .fold((0, 0), |(h, m), r| (h + r.cache_hits, m + r.cache_misses));
+    match hits + misses {
+        0 => println!("Stat cache        no lookups"),
+        n => println!(
+            "Stat cache        {}% hits ({hits} of {n} lookups)",
+            hits * 100 / n
+        ),
+    }
+
+    let advice = recommendations(&runs);
+    if advice.is_empty() {
+        println!("\nNothing to tune: the defaults suit how you use work.");
+    } else {
+        println!();
+        for a in advice {
+            println!("- {a}");
+        }
+    }
+    println!("\nRecorded in {}", path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(millis: u64, depth: usize, deepest: usize, hits: usize, misses: usize) -> Run {
+        Run {
+            repos: 40,
+            millis,
+            depth,
+            deepest,
+            cache: true,
+            cache_hits: hits,
+            cache_misses: misses,
+            threads: 8,
+            ..Run::default()
+        }
+    }
+
+    #[test]
+    fn round_trips_and_recommends() {
+        let r = run(1200, 3, 2, 5, 1);
+        assert_eq!(parse_line(format_line(&r).trim_end()), Some(r));
+        assert_eq!(parse_line("1\t2\tthree"), None);
+
+        // Fast, cached, repos well within -L: nothing to say.
+        let happy = vec![run(300, 3, 2, 90, 10); 12];
+        assert!(recommendations(&happy).is_empty());
+
+        // Repos at the limit, a cache that keeps missing, slow scans.
+        let advice = recommendations(&vec![run(5_000, 3, 3, 1, 9); 12]);
+        assert_eq!(advice.len(), 3, "{advice:?}");
+        assert!(advice[0].contains("try -L 4"));
+        assert!(advice[1].contains("Only 10%"));
+        assert!(advice[2].contains("5.0s over 40 repos"));
+
+        // Much shallower than -L.
+        let advice = recommendations(&vec![run(300, 6, 2, 9, 1); 5]);
+        assert_eq!(advice.len(), 1);
+        assert!(advice[0].contains("-L 2 finds the same repos"));
+    }
+}
@@ -0,0 +1,198 @@
+use crate::{
+    Args, Scan, format_clock_local, now, open_repo, paint, print_log, report_warnings, scan,
+    sort_commits,
+};
+use git2::Oid;
+use notify::{RecursiveMode, Watcher};
+use std::collections::HashSet;
+use std::io::{IsTerminal, Write as _};
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::time::Duration;
+
+/// How long to wait for a burst of ref updates (a rebase, a `git pull`) to
+/// settle before re-scanning.
+const SETTLE: Duration = Duration::from_millis(300);
+
+#[derive(clap::Args, Debug)]
+pub struct WatchArgs {
+    #[command(flatten)]
+    pub log: Args,
+
+    /// Seconds between re-scans; new commits show up sooner, when their repo's refs change
+    #[arg(long, value_name = "SECS", default_value = "60", value_parser = clap::value_parser!(u64).range(1..))]
+    pub interval: u64,
+
+    /// Don't watch refs for changes, just re-scan every --interval (for network filesystems, where notifications don't arrive)
+    #[arg(long)]
+    pub poll: bool,
+}
+
+/// What the board shows: redrawn only when this changes.
+fn fingerprint(scan: &Scan) -> (i64, Vec<Oid>) {
+    (scan.since, scan.commits.iter().map(|c| c.oid).collect())
+}
+
+/// Whether an event is a ref moving: a commit or checkout rewrites a file
+/// under `refs/heads`, or `HEAD` itself when it's detached. The rest of the
+/// git dir (the index, above all) changes far more often.
+fn is_ref_change(event: &notify::Event) -> bool {
+    !event.kind.is_access()
+        && event.paths.iter().any(|p| {
+            p.file_name().is_some_and(|n| n == "HEAD")
+                || p.components().any(|c| c.as_os_str() == "refs")
+        })
+}
+
+/// Watches the refs of repos not watched yet.
+fn watch_refs(watcher: &mut impl Watcher, watched: &mut HashSet<PathBuf>, repos: &[PathBuf]) {
+    for path in repos {
+        if watched.contains(path) {
+            continue;
+        }
+        let Ok(repo) = open_repo(path) else {
+            continue;
+        };
+        // Worktrees share their refs with the main repo, but have a HEAD each.
+        let _ = watcher.watch(
+            &repo.commondir().join("refs/heads"),
+            RecursiveMode::Recursive,
+        );
+        // HEAD is replaced rather than written, so watch the dir it's in.
+        let _ = watcher.watch(repo.path(), RecursiveMode::NonRecursive);
+        watched.insert(path.clone());
+    }
+}
+
+/// `work watch`: the log (today's, unless a window is given), redrawn
+/// whenever a commit lands in one of the repos, for a pane that stays open.
+/// The window moves along with the clock, so at midnight the board starts
+/// over.
+pub fn watch(mut w: WatchArgs) -> Result<(), String> {
+    let args = &mut w.log;
+    if args.events || args.alfred || args.stream || args.jsonl || args.whoami || args.new_only {
+        return Err(
+            "work: watch redraws the log, so --events, --alfred, --stream, --jsonl, --whoami and --new-only don't apply"
+                .into(),
+        );
+    }
+    args.scan.today_by_default();
+    args.prepare_scan();
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = match w.poll {
+        true => None,
+        false => match notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            if event.is_ok_and(|e| is_ref_change(&e)) {
+                let _ = tx.send(());
+            }
+        }) {
+            Ok(watcher) => Some(watcher),
+            Err(e) => {
+                eprintln!(
+                    "work: can't watch for changes ({e}); re-scanning every {}s",
+                    w.interval
+                );
+                None
+            }
+        },
+    };
+    let mut watched = HashSet::new();
+    let clear = std::io::stdout().is_terminal();
+    let mut shown = None;
+
+    loop {
+        // The first scan has to work; after that, a failed one (a mount
+        // gone for a moment) is reported and the board kept.
+        let mut scan = match scan(&args.scan) {
+            Ok(scan) => scan,
+            Err(e) if shown.is_some() => {
+                eprintln!("{e}");
+                wait(&rx, w.interval);
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+        let key = fingerprint(&scan);
+        if shown.as_ref() != Some(&key) {
+            sort_commits(&mut scan.commits, args.sort, args.reverse);
+            if clear {
+                print!("\x1b[2J\x1b[H");
+            } else if shown.is_some() {
+                println!();
+            }
+            if let Err(e) = print_log(args, &scan) {
+                // An empty day is a board too, not a reason to stop.
+                println!("{e}");
+            }
+            let time = format_clock_local(now().timestamp());
+            println!(
+                "{}",
+                paint(
+                    "2",
+                    format!("Watching {} repos, updated {time}", scan.repos.len())
+                )
+            );
+            let _ = std::io::stdout().flush();
+            report_warnings(&scan);
+            shown = Some(key);
+        }
+        if let Some(watcher) = watcher.as_mut() {
+            watch_refs(watcher, &mut watched, &scan.repos);
+        }
+        wait(&rx, w.interval);
+    }
+}
+
+/// Until a ref changes (and the changes settle) or `interval` seconds pass.
+fn wait(rx: &mpsc::Receiver<()>, interval: u64) {
+    let interval = Duration::from_secs(interval);
+    match rx.recv_timeout(interval) {
+        Ok(()) => while rx.recv_timeout(SETTLE).is_ok() {},
+        Err(mpsc::RecvTimeoutError::Timeout) => {}
+        // Not watching (--poll, or the watcher couldn't start).
+        Err(mpsc::RecvTimeoutError::Disconnected) => std::thread::sleep(interval),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+    use notify::event::{CreateKind, DataChange, EventKind, ModifyKind};
+
+    #[test]
+    fn wakes_for_ref_changes_only() {
+        let event = |kind, path: &str| notify::Event::new(kind).add_path(PathBuf::from(path));
+        let write = EventKind::Modify(ModifyKind::Data(DataChange::Any));
+        assert!(is_ref_change(&event(
+            write,
+            "/code/api/.git/refs/heads/main"
+        )));
+        assert!(is_ref_change(&event(
+            EventKind::Create(CreateKind::File),
+            "/code/api/.git/HEAD"
+        )));
+        assert!(!is_ref_change(&event(write, "/code/api/.git/index")));
+        assert!(!is_ref_change(&event(
+            EventKind::Access(notify::event::AccessKind::Any),
+            "/code/api/.git/HEAD"
+        )));
+
+        let cli = crate::Cli::try_parse_from([
+            "work",
+            "watch",
+            "--interval",
+            "5",
+            "--columns",
+            "time,summary",
+            "a",
+        ])
+        .unwrap();
+        let Some(crate::Cmd::Watch(w)) = cli.command else {
+            panic!("not watch");
+        };
+        assert_eq!((w.interval, w.log.columns.len()), (5, 2));
+        assert!(crate::Cli::try_parse_from(["work", "watch", "--interval", "0", "a"]).is_err());
+    }
+}
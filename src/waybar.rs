@@ -0,0 +1,152 @@
+use crate::{Scan, ScanArgs, format_clock_local, scan};
+use serde_json::{Value, json};
+
+#[derive(clap::Args, Debug)]
+pub struct WaybarArgs {
+    #[command(flatten)]
+    pub scan: ScanArgs,
+
+    /// Max number of commits in the tooltip
+    #[arg(short, long, default_value = "15")]
+    pub limit: usize,
+}
+
+/// CSS class for the module, so bar themes can color by activity.
+fn activity_class(commits: usize) -> &'static str {
+    match commits {
+        0 => "idle",
+        1..=2 => "low",
+        3..=9 => "medium",
+        _ => "high",
+    }
+}
+
+/// Waybar renders tooltips as Pango markup.
+fn escape_markup(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Prints the single-line JSON a Waybar `custom` module with
+/// `"return-type": "json"` expects. Like the menubar plugin, failures are
+/// reported in the payload rather than on stderr.
+pub fn waybar(mut args: WaybarArgs) -> Result<(), String> {
+    args.scan.today_by_default();
+    // The module shows no sizes and runs on an interval, so a commit the
+    // stat cache hasn't seen yet isn't worth diffing here.
+    args.scan.cached_stats_only = true;
+
+    let payload = match scan(&args.scan) {
+        Ok(scan) => payload(&scan, args.limit),
+        Err(e) => json!({
+            "text": "⎇ !",
+            "tooltip": escape_markup(&e),
+            "class": "error",
+            "alt": "error",
+        }),
+    };
+    println!("{payload}");
+    Ok(())
+}
+
+fn payload(scan: &Scan, limit: usize) -> Value {
+    let n = scan.commits.len();
+    let mut tooltip: Vec<String> = scan
+        .commits
+        .iter()
+        .take(limit)
+        .map(|c| {
+            format!(
+                "{}  <b>{}</b>  {}",
+                format_clock_local(c.time),
+                escape_markup(&scan.rel(&c.repo).display().to_string()),
+                escape_markup(&c.summary)
+            )
+        })
+        .collect();
+    if n > limit {
+        tooltip.push(format!("… and {} more", n - limit));
+    }
+    if tooltip.is_empty() {
+        tooltip.push("No commits yet".to_string());
+    }
+
+    let class = activity_class(n);
+    json!({
+        "text": format!("⎇ {n}"),
+        "tooltip": tooltip.join("\n"),
+        "class": class,
+        "alt": class,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CommitLine;
+    use crate::fixtures::FixtureRepo;
+    use clap::Parser;
+
+    fn commits(n: usize) -> Scan {
+        let commits = (0..n)
+            .map(|i| CommitLine::stub("/code/api", 1_772_452_800 - i as i64 * 60))
+            .collect();
+        Scan::stub(&["/code"], commits)
+    }
+
+    #[test]
+    fn classes_by_commit_count() {
+        for (n, class) in [
+            (0, "idle"),
+            (1, "low"),
+            (2, "low"),
+            (3, "medium"),
+            (9, "medium"),
+            (10, "high"),
+        ] {
+            let p = payload(&commits(n), 15);
+            assert_eq!(p["class"], class, "{n} commits");
+            assert_eq!(p["alt"], class);
+            assert_eq!(p["text"], format!("⎇ {n}"));
+        }
+        assert_eq!(payload(&commits(0), 15)["tooltip"], "No commits yet");
+    }
+
+    #[test]
+    fn escapes_and_trims_the_tooltip() {
+        let mut scan = commits(4);
+        scan.commits[0].repo = "/code/a&b".into();
+        scan.commits[0].summary = "Use <T> & friends".to_string();
+
+        let tooltip = payload(&scan, 2)["tooltip"].as_str().unwrap().to_string();
+        assert_eq!(
+            tooltip,
+            format!(
+                "{}  <b>a&amp;b</b>  Use &lt;T&gt; &amp; friends\n{}  <b>api</b>  \n… and 2 more",
+                format_clock_local(1_772_452_800),
+                format_clock_local(1_772_452_740)
+            )
+        );
+    }
+
+    #[test]
+    fn cached_stats_only_diffs_nothing() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = FixtureRepo::init(&tmp.path().join("api")).unwrap();
+        let hour_ago = chrono::Local::now().timestamp() - 3600;
+        repo.commit(hour_ago, "first", &[("a", "1\n2\n")]).unwrap();
+
+        let args = crate::Args::parse_from(["work", "--all", tmp.path().to_str().unwrap()]);
+        let mut scan_args = args.scan;
+        scan_args.cached_stats_only = true;
+        let got = scan(&scan_args).unwrap();
+        assert_eq!(got.commits.len(), 1);
+        assert!(got.commits[0].no_stats);
+
+        // A size filter needs the diff anyway.
+        scan_args.min_changes = Some(1);
+        let got = scan(&scan_args).unwrap();
+        assert_eq!(got.commits[0].insertions, 2);
+    }
+}
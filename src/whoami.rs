@@ -0,0 +1,235 @@
+use crate::{
+    Args, CommitLine, Identity, ScanArgs, matches_identity, open_repo, paint, report_warnings, scan,
+};
+use git2::ConfigLevel;
+use std::collections::HashSet;
+use std::path::Path;
+
+/// One author identity seen in the window, and why it might be you.
+#[derive(Debug, PartialEq, Eq)]
+struct Candidate {
+    name: String,
+    email: String,
+    commits: usize,
+    repos: usize,
+    /// Already counted as yours by the current identity.
+    matched: bool,
+    reasons: Vec<String>,
+}
+
+/// The OS account name, lowercased, if it's long enough to mean something:
+/// `me` or `a` would match half the team.
+fn os_user() -> Option<String> {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .ok()
+        .map(|u| u.trim().to_lowercase())
+        .filter(|u| u.len() >= 3 && u != "root")
+}
+
+/// `user.email` set in repos' own `.git/config`, which says more about who
+/// works in them than the global one does. A repo that won't open failed
+/// its scan too, and is among the scan's warnings with why.
+fn local_emails(repos: &[impl AsRef<Path>]) -> HashSet<String> {
+    repos
+        .iter()
+        .filter_map(|r| {
+            let cfg = open_repo(r.as_ref()).ok()?.config().ok()?;
+            cfg.open_level(ConfigLevel::Local)
+                .ok()?
+                .get_string("user.email")
+                .ok()
+        })
+        .map(|e| e.to_lowercase())
+        .collect()
+}
+
+/// Every identity with at least one reason to be you, most likely first.
+fn candidates(
+    commits: &[CommitLine],
+    id: &Identity,
+    user: Option<&str>,
+    local: &HashSet<String>,
+) -> Vec<Candidate> {
+    // Keyed by email: the same address under a misspelled name is still you.
+    let mut by_email: Vec<(String, String, usize, HashSet<&Path>)> = Vec::new();
+    for c in commits {
+        let email = c.author_email.to_lowercase();
+        match by_email.iter_mut().find(|(e, ..)| *e == email) {
+            Some((_, _, n, repos)) => {
+                *n += 1;
+                repos.insert(&c.repo);
+            }
+            None => by_email.push((
+                email,
+                c.author_name.clone(),
+                1,
+                HashSet::from([c.repo.as_path()]),
+            )),
+        }
+    }
+
+    let mut out: Vec<(usize, Candidate)> = Vec::new();
+    for (email, name, commits, repos) in by_email {
+        let mut score = 0;
+        let mut reasons = Vec::new();
+        if id
+            .email
+            .iter()
+            .chain(&id.other_emails)
+            .any(|e| e.eq_ignore_ascii_case(&email))
+        {
+            score += 4;
+            reasons.push("your user.email".to_string());
+        }
+        if id.name.as_deref() == Some(name.as_str()) {
+            score += 2;
+            reasons.push("your user.name".to_string());
+        }
+        if local.contains(&email) {
+            score += 3;
+            reasons.push("user.email in a repo's own config".to_string());
+        }
+        if let Some(user) = user {
+            let local_part = email.split('@').next().unwrap_or("");
+            let squashed: String = name.to_lowercase().split_whitespace().collect();
+            if local_part.contains(user) || squashed.contains(user) {
+                score += 2;
+                reasons.push(format!("looks like OS user '{user}'"));
+            }
+        }
+        if score == 0 {
+            continue;
+        }
+        out.push((
+            score,
+            Candidate {
+                // The log counts a repo's own identity as yours, too.
+                matched: matches_identity(id, Some(&name), Some(&email)) || local.contains(&email),
+                name,
+                email,
+                commits,
+                repos: repos.len(),
+                reasons,
+            },
+        ));
+    }
+    out.sort_by(|(sa, a), (sb, b)| {
+        sb.cmp(sa)
+            .then_with(|| b.commits.cmp(&a.commits))
+            .then_with(|| a.email.cmp(&b.email))
+    });
+    out.into_iter().map(|(_, c)| c).collect()
+}
+
+/// `--whoami`: scans everyone's commits in the window and lists the author
+/// identities that look like yours, so the ones your git identity misses
+/// (an old laptop's email, a noreply address) can be added to a profile.
+pub fn whoami(args: &Args) -> Result<(), String> {
+    let all = ScanArgs {
+        all: true,
+        ..args.scan.clone()
+    };
+    let scan = scan(&all)?;
+    let user = os_user();
+    let local = local_emails(&scan.repos);
+    let found = candidates(&scan.commits, &scan.id, user.as_deref(), &local);
+    if found.is_empty() {
+        report_warnings(&scan);
+        return Err(format!(
+            "work: no identity in {} looks like yours (try a longer window, e.g. --days 365)",
+            args.scan.window().describe()
+        ));
+    }
+
+    if args.raw {
+        // name\temail\tcommits\trepos\tmatched|unmatched\treasons
+        for c in &found {
+            println!(
+                "{}\t{}\t{}\t{}\t{}\t{}",
+                c.name,
+                c.email,
+                c.commits,
+                c.repos,
+                if c.matched { "matched" } else { "unmatched" },
+                c.reasons.join(", ")
+            );
+        }
+    } else {
+        let width = found
+            .iter()
+            .map(|c| c.name.chars().count() + c.email.len() + 3)
+            .max()
+            .unwrap_or(0);
+        for c in &found {
+            let who = format!("{} <{}>", c.name, c.email);
+            let mark = if c.matched {
+                paint("32", "✓")
+            } else {
+                paint("33", "?")
+            };
+            println!(
+                "{mark} {}  {:>5} commits in {:>3} repos  {}",
+                paint("1", format!("{who:<width$}")),
+                c.commits,
+                c.repos,
+                paint("2", c.reasons.join(", "))
+            );
+        }
+        let missing = found.iter().filter(|c| !c.matched).count();
+        if missing > 0 {
+            println!(
+                "\n{missing} marked ? aren't counted as yours by your git identity. \
+                 `name` or `email` in a [profile.NAME] of the config file picks who counts, \
+                 with --profile NAME; `email` can be a list of all of them."
+            );
+        }
+    }
+    report_warnings(&scan);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn by(repo: &str, name: &str, email: &str) -> CommitLine {
+        CommitLine {
+            author_name: name.to_string(),
+            author_email: email.to_string(),
+            ..CommitLine::stub(repo, 0)
+        }
+    }
+
+    #[test]
+    fn ranks_identities_that_look_like_me() {
+        let id = Identity {
+            name: None,
+            email: Some("kaf@corp.io".to_string()),
+            other_emails: vec!["kaf@home.dev".to_string()],
+        };
+        let commits = vec![
+            by("api", "Kaf", "kaf@corp.io"),
+            by("ui", "Kaf", "KAF@corp.io"),
+            by("blog", "kaf", "12345+kaf@users.noreply.github.com"),
+            by("old", "K. Af", "k@laptop.local"),
+            by("blog", "Kaf", "kaf@home.dev"),
+            by("api", "Ana", "ana@corp.io"),
+        ];
+        let local = HashSet::from(["k@laptop.local".to_string()]);
+        let got = candidates(&commits, &id, Some("kaf"), &local);
+        let got: Vec<(&str, usize, bool)> = got
+            .iter()
+            .map(|c| (c.email.as_str(), c.commits, c.matched))
+            .collect();
+        assert_eq!(
+            got,
+            [
+                ("kaf@corp.io", 2, true),
+                ("kaf@home.dev", 1, true),
+                ("k@laptop.local", 1, true),
+                ("12345+kaf@users.noreply.github.com", 1, false),
+            ]
+        );
+    }
+}
@@ -0,0 +1,412 @@
+//! The time windows `work` scans: the last N days, calendar shortcuts like
+//! "this week" and "last month", ISO weeks and explicit date ranges. Each
+//! resolves to a pair of Unix timestamps against a clock and a timezone, so
+//! the same window means the same instants everywhere it's used.
+
+use chrono::{
+    DateTime, Datelike, Days, LocalResult, Months, NaiveDate, NaiveTime, TimeDelta, TimeZone, Utc,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Window {
+    /// The last `n` days, counted back from now to the second.
+    Days(i64),
+    /// Since midnight.
+    Today,
+    /// The previous day, midnight to midnight.
+    Yesterday,
+    /// Since Monday 00:00.
+    Week,
+    /// The previous Monday-to-Sunday week.
+    LastWeek,
+    /// One ISO week, given by its Monday.
+    IsoWeek(NaiveDate),
+    /// Since the 1st of the month.
+    Month,
+    /// The previous calendar month.
+    LastMonth,
+    /// Since the start of the calendar quarter (January, April, July, October).
+    Quarter,
+    /// The previous calendar quarter.
+    LastQuarter,
+    /// Whole days from the first date up to, but not including, the second.
+    Range(NaiveDate, NaiveDate),
+    /// From midnight on a date until now.
+    Since(NaiveDate),
+}
+
+/// The range a commit timestamp has to be in to be taken at face value:
+/// 1970 up to 2100. git stores whatever it's given, so history imported
+/// from older tools can carry negative epochs, and a broken clock or
+/// importer can produce dates like 2106 (u32 overflow) that would sit at
+/// the top of every open-ended window forever.
+pub const PLAUSIBLE: std::ops::Range<i64> = 0..4_102_444_800;
+
+pub fn is_plausible(ts: i64) -> bool {
+    PLAUSIBLE.contains(&ts)
+}
+
+/// Unix time of 00:00 on `date` in `tz`, i.e. when that day starts.
+///
+/// Some zones move their clocks at midnight, so 00:00 may not exist or may
+/// happen twice. A day that skips midnight starts at its first valid
+/// instant (01:00 in Havana on a spring-forward Sunday); a day that repeats
+/// it starts at the first of the two. Either way there's an answer, so a
+/// window never fails over a transition.
+pub fn midnight<Tz: TimeZone>(tz: &Tz, date: NaiveDate) -> i64 {
+    let start = date.and_time(NaiveTime::MIN);
+    match tz.from_local_datetime(&start) {
+        LocalResult::Single(dt) | LocalResult::Ambiguous(dt, _) => return dt.timestamp(),
+        LocalResult::None => {}
+    }
+    // Inside a gap: the first local minute after it is the transition
+    // itself. Real gaps are at most a day long (Samoa skipped 2011-12-30).
+    for minutes in 1..=26 * 60 {
+        let t = start + TimeDelta::minutes(minutes);
+        if let Some(dt) = tz.from_local_datetime(&t).earliest() {
+            return dt.timestamp();
+        }
+    }
+    start.and_utc().timestamp()
+}
+
+fn monday_of(date: NaiveDate) -> NaiveDate {
+    date - Days::new(date.weekday().num_days_from_monday().into())
+}
+
+fn quarter_start(date: NaiveDate) -> NaiveDate {
+    let month = (date.month() - 1) / 3 * 3 + 1;
+    NaiveDate::from_ymd_opt(date.year(), month, 1).unwrap_or(date)
+}
+
+/// Parses `2026-W12` (ISO 8601 week date) into that week's Monday.
+pub fn parse_iso_week(s: &str) -> Result<NaiveDate, String> {
+    let err = || format!("expected an ISO week like 2026-W12, got '{s}'");
+    let (year, week) = s.split_once("-W").ok_or_else(err)?;
+    let year: i32 = year.parse().map_err(|_| err())?;
+    let week: u32 = week.parse().map_err(|_| err())?;
+    NaiveDate::from_isoywd_opt(year, week, chrono::Weekday::Mon)
+        .ok_or_else(|| format!("{year} has no ISO week {week}"))
+}
+
+/// Parses a window written out in one word, for options that take a second
+/// window: `today`, `yesterday`, `week`, `last-week`, `month`, `last-month`, `quarter`,
+/// `last-quarter`, `30d`, `2026-W12`, `2026-03-01..2026-03-15` (both days
+/// included) or `2026-03-01..` (until now).
+pub fn parse(s: &str) -> Result<Window, String> {
+    let date = |d: &str| {
+        NaiveDate::parse_from_str(d, "%Y-%m-%d")
+            .map_err(|_| format!("expected a date like 2026-03-01, got '{d}'"))
+    };
+    Ok(match s {
+        "today" => Window::Today,
+        "yesterday" => Window::Yesterday,
+        "week" => Window::Week,
+        "last-week" => Window::LastWeek,
+        "month" => Window::Month,
+        "last-month" => Window::LastMonth,
+        "quarter" => Window::Quarter,
+        "last-quarter" => Window::LastQuarter,
+        _ if s.contains("-W") => Window::IsoWeek(parse_iso_week(s)?),
+        _ => match s.split_once("..") {
+            Some((from, "")) => Window::Since(date(from)?),
+            Some((from, to)) => {
+                let (from, to) = (date(from)?, date(to)?);
+                if to < from {
+                    return Err(format!("'{s}' ends before it starts"));
+                }
+                Window::Range(from, to.succ_opt().unwrap_or(to))
+            }
+            None => match s.strip_suffix('d').map(str::parse::<i64>) {
+                Some(Ok(n)) if n > 0 => Window::Days(n),
+                _ => {
+                    return Err(format!(
+                        "unknown window '{s}' (try last-month, 30d, 2026-W12 or 2026-03-01..2026-03-15)"
+                    ));
+                }
+            },
+        },
+    })
+}
+
+/// `2026-W12` for the week starting on `monday`.
+pub fn iso_week_label(monday: NaiveDate) -> String {
+    let w = monday.iso_week();
+    format!("{}-W{:02}", w.year(), w.week())
+}
+
+impl Window {
+    /// The window's start and, for windows that end before now, its end,
+    /// as Unix timestamps. Calendar boundaries are midnights in `tz`.
+    pub fn resolve<Tz: TimeZone>(
+        &self,
+        now: DateTime<Utc>,
+        tz: &Tz,
+    ) -> Result<(i64, Option<i64>), String> {
+        let today = now.with_timezone(tz).date_naive();
+        let month_start = today.with_day(1).unwrap_or(today);
+        let out_of_range = || format!("work: {} is out of range", self.describe());
+        let days_before =
+            |d: NaiveDate, n: u64| d.checked_sub_days(Days::new(n)).ok_or_else(out_of_range);
+        let months_before = |d: NaiveDate, n: u32| {
+            d.checked_sub_months(Months::new(n))
+                .ok_or_else(out_of_range)
+        };
+
+        let (start, end) = match *self {
+            Window::Days(n) => {
+                let since = now
+                    .timestamp()
+                    .saturating_sub(n.saturating_mul(24 * 60 * 60));
+                return Ok((since, None));
+            }
+            Window::Today => (today, None),
+            Window::Yesterday => (days_before(today, 1)?, Some(today)),
+            Window::Week => (monday_of(today), None),
+            Window::LastWeek => {
+                let monday = monday_of(today);
+                (days_before(monday, 7)?, Some(monday))
+            }
+            Window::IsoWeek(monday) => (
+                monday,
+                Some(
+                    monday
+                        .checked_add_days(Days::new(7))
+                        .ok_or_else(out_of_range)?,
+                ),
+            ),
+            Window::Month => (month_start, None),
+            Window::LastMonth => (months_before(month_start, 1)?, Some(month_start)),
+            Window::Quarter => (quarter_start(today), None),
+            Window::LastQuarter => {
+                let start = quarter_start(today);
+                (months_before(start, 3)?, Some(start))
+            }
+            Window::Range(from, to) => (from, Some(to)),
+            Window::Since(from) => (from, None),
+        };
+        Ok((midnight(tz, start), end.map(|d| midnight(tz, d))))
+    }
+
+    /// For sentences: "No commits found in the last 7 days".
+    pub fn describe(&self) -> String {
+        match self {
+            Window::Days(n) => format!("the last {n} days"),
+            _ => self.label(),
+        }
+    }
+
+    /// For the summary line: "12 commits shown (last 7 days)".
+    pub fn label(&self) -> String {
+        match *self {
+            Window::Days(n) => format!("last {n} days"),
+            Window::Today => "today".to_string(),
+            Window::Yesterday => "yesterday".to_string(),
+            Window::Week => "this week".to_string(),
+            Window::LastWeek => "last week".to_string(),
+            Window::IsoWeek(monday) => format!("week {}", iso_week_label(monday)),
+            Window::Month => "this month".to_string(),
+            Window::LastMonth => "last month".to_string(),
+            Window::Quarter => "this quarter".to_string(),
+            Window::LastQuarter => "last quarter".to_string(),
+            Window::Range(from, to) => match to.pred_opt() {
+                Some(last) if last != from => format!("{from} to {last}"),
+                _ => from.to_string(),
+            },
+            Window::Since(from) => format!("since {from}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+    use proptest::prelude::*;
+
+    fn at(tz: &FixedOffset, y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+        tz.with_ymd_and_hms(y, m, d, h, min, 0)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn ts(tz: &FixedOffset, y: i32, m: u32, d: u32) -> i64 {
+        at(tz, y, m, d, 0, 0).timestamp()
+    }
+
+    #[test]
+    fn resolves_calendar_shortcuts() {
+        let tz = FixedOffset::east_opt(-5 * 3600).unwrap();
+        // Thursday afternoon, which is already Friday in UTC.
+        let now = at(&tz, 2026, 3, 19, 22, 30);
+
+        let r = |w: Window| w.resolve(now, &tz).unwrap();
+        assert_eq!(r(Window::Today), (ts(&tz, 2026, 3, 19), None));
+        assert_eq!(
+            r(Window::Yesterday),
+            (ts(&tz, 2026, 3, 18), Some(ts(&tz, 2026, 3, 19)))
+        );
+        assert_eq!(r(Window::Week), (ts(&tz, 2026, 3, 16), None));
+        assert_eq!(
+            r(Window::LastWeek),
+            (ts(&tz, 2026, 3, 9), Some(ts(&tz, 2026, 3, 16)))
+        );
+        assert_eq!(r(Window::Month), (ts(&tz, 2026, 3, 1), None));
+        assert_eq!(
+            r(Window::LastMonth),
+            (ts(&tz, 2026, 2, 1), Some(ts(&tz, 2026, 3, 1)))
+        );
+        assert_eq!(r(Window::Quarter), (ts(&tz, 2026, 1, 1), None));
+        assert_eq!(
+            r(Window::LastQuarter),
+            (ts(&tz, 2025, 10, 1), Some(ts(&tz, 2026, 1, 1)))
+        );
+        assert_eq!(
+            r(Window::Days(7)),
+            (now.timestamp() - 7 * 24 * 60 * 60, None)
+        );
+
+        // Year boundary.
+        let now = at(&tz, 2026, 1, 10, 14, 30);
+        assert_eq!(
+            Window::LastMonth.resolve(now, &tz).unwrap(),
+            (ts(&tz, 2025, 12, 1), Some(ts(&tz, 2026, 1, 1)))
+        );
+    }
+
+    #[test]
+    fn parses_iso_weeks() {
+        let monday = parse_iso_week("2026-W12").unwrap();
+        assert_eq!(monday, NaiveDate::from_ymd_opt(2026, 3, 16).unwrap());
+        assert_eq!(iso_week_label(monday), "2026-W12");
+        // ISO week 1 of 2026 starts in 2025.
+        assert_eq!(
+            parse_iso_week("2026-W01").unwrap(),
+            NaiveDate::from_ymd_opt(2025, 12, 29).unwrap()
+        );
+        assert!(parse_iso_week("2026-W54").is_err());
+        assert!(parse_iso_week("2026-12").is_err());
+    }
+
+    #[test]
+    fn parses_windows() {
+        let date = |m, d| NaiveDate::from_ymd_opt(2026, m, d).unwrap();
+        assert_eq!(parse("last-month"), Ok(Window::LastMonth));
+        assert_eq!(parse("yesterday"), Ok(Window::Yesterday));
+        assert_eq!(parse("30d"), Ok(Window::Days(30)));
+        assert_eq!(parse("2026-W12"), Ok(Window::IsoWeek(date(3, 16))));
+        assert_eq!(
+            parse("2026-03-01..2026-03-15"),
+            Ok(Window::Range(date(3, 1), date(3, 16)))
+        );
+        assert_eq!(parse("2026-03-01.."), Ok(Window::Since(date(3, 1))));
+        assert_eq!(
+            Window::Range(date(3, 1), date(3, 16)).label(),
+            "2026-03-01 to 2026-03-15"
+        );
+        for bad in [
+            "yesterday-ish",
+            "0d",
+            "2026-03-15..2026-03-01",
+            "2026-13-01..",
+        ] {
+            assert!(parse(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn days_start_at_the_first_instant_around_dst() {
+        use chrono_tz::America::Havana;
+        let date = |m, d| NaiveDate::from_ymd_opt(2026, m, d).unwrap();
+        let utc = |m, d, h| {
+            Utc.with_ymd_and_hms(2026, m, d, h, 0, 0)
+                .unwrap()
+                .timestamp()
+        };
+
+        // Clocks jump from 00:00 to 01:00 CDT (UTC-4): the day starts at 01:00.
+        assert_eq!(midnight(&Havana, date(3, 8)), utc(3, 8, 5));
+        // Clocks fall back from 01:00 CDT to 00:00 CST: the first 00:00 wins.
+        assert_eq!(midnight(&Havana, date(11, 1)), utc(11, 1, 4));
+
+        // A window computed on either day still resolves.
+        let now = Utc.with_ymd_and_hms(2026, 3, 8, 12, 0, 0).unwrap();
+        assert_eq!(
+            Window::Today.resolve(now, &Havana).unwrap(),
+            (utc(3, 8, 5), None)
+        );
+    }
+
+    proptest! {
+        /// Across real DST rules, every day has a start and the days tile
+        /// time: each one starts after the previous and lasts 23 to 25 hours.
+        #[test]
+        fn days_tile_time_in_dst_zones(
+            days in 0u64..60_000,
+            zone in 0usize..5,
+        ) {
+            let tz = [
+                chrono_tz::America::Havana,
+                chrono_tz::America::Sao_Paulo,
+                chrono_tz::Europe::London,
+                chrono_tz::Australia::Lord_Howe,
+                chrono_tz::Asia::Beirut,
+            ][zone];
+            let date = NaiveDate::from_ymd_opt(1970, 1, 1).unwrap() + Days::new(days);
+            let start = midnight(&tz, date);
+            let next = midnight(&tz, date + Days::new(1));
+            prop_assert!((23 * 3600..=25 * 3600).contains(&(next - start)));
+            let local = DateTime::from_timestamp(start, 0).unwrap().with_timezone(&tz);
+            prop_assert_eq!(local.date_naive(), date);
+        }
+
+        /// Every calendar window starts at a local midnight no later than
+        /// now, and the "last" windows end exactly where the current ones
+        /// begin.
+        #[test]
+        fn calendar_windows_hold_for_any_instant(
+            secs in 0i64..4_102_444_800,
+            offset_min in -12 * 60i32..=14 * 60,
+        ) {
+            let tz = FixedOffset::east_opt(offset_min * 60).unwrap();
+            let now = DateTime::from_timestamp(secs, 0).unwrap();
+            let local = now.with_timezone(&tz);
+
+            for w in [Window::Today, Window::Week, Window::Month, Window::Quarter] {
+                let (since, until) = w.resolve(now, &tz).unwrap();
+                let start = DateTime::from_timestamp(since, 0).unwrap().with_timezone(&tz);
+                prop_assert!(since <= secs);
+                prop_assert_eq!(until, None);
+                prop_assert_eq!(start.time(), chrono::NaiveTime::MIN);
+                match w {
+                    Window::Today => prop_assert_eq!(start.date_naive(), local.date_naive()),
+                    Window::Week => {
+                        prop_assert_eq!(start.weekday(), chrono::Weekday::Mon);
+                        prop_assert!(secs - since < 7 * 24 * 60 * 60);
+                    }
+                    Window::Month => {
+                        prop_assert_eq!(start.day(), 1);
+                        prop_assert_eq!(start.month(), local.month());
+                    }
+                    _ => {
+                        prop_assert_eq!(start.day(), 1);
+                        prop_assert_eq!(start.month() % 3, 1);
+                        prop_assert!(local.month() - start.month() < 3);
+                    }
+                }
+            }
+
+            for (last, current) in [
+                (Window::Yesterday, Window::Today),
+            (Window::LastWeek, Window::Week),
+                (Window::LastMonth, Window::Month),
+                (Window::LastQuarter, Window::Quarter),
+            ] {
+                let (since, until) = last.resolve(now, &tz).unwrap();
+                let (current_since, _) = current.resolve(now, &tz).unwrap();
+                prop_assert_eq!(until, Some(current_since));
+                prop_assert!(since < current_since);
+            }
+        }
+    }
+}
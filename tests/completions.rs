@@ -0,0 +1,48 @@
+//! Completion runs the real binary the way the shell scripts do, with
+//! `COMPLETE=<shell>`, so view and profile names come from a config file.
+
+use std::process::Command;
+
+fn complete(config: &std::path::Path, words: &[&str]) -> String {
+    let out = Command::new(env!("CARGO_BIN_EXE_work"))
+        .arg("--")
+        .args(words)
+        .env("COMPLETE", "fish")
+        .env("WORK_CONFIG", config)
+        .output()
+        .unwrap();
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    // Just the values; an empty word offers the flags after them.
+    String::from_utf8(out.stdout)
+        .unwrap()
+        .lines()
+        .filter(|l| !l.starts_with('-'))
+        .map(|l| format!("{l}\n"))
+        .collect()
+}
+
+#[test]
+fn completes_view_and_profile_names() {
+    let tmp = tempfile::tempdir().unwrap();
+    let config = tmp.path().join("config.toml");
+    std::fs::write(
+        &config,
+        "[view]\nmonthly = { month = true }\nstandup = { yesterday = true }\n\n[profile.oss]\npaths = [\"~/oss\"]\n",
+    )
+    .unwrap();
+
+    assert_eq!(
+        complete(&config, &["work", "view", ""]),
+        "monthly\nstandup\n"
+    );
+    assert_eq!(complete(&config, &["work", "view", "st"]), "standup\n");
+    assert_eq!(complete(&config, &["work", "--profile", ""]), "oss\n");
+
+    // A config file that doesn't parse completes nothing, quietly.
+    std::fs::write(&config, "[view\n").unwrap();
+    assert_eq!(complete(&config, &["work", "view", ""]), "");
+}
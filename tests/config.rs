@@ -0,0 +1,35 @@
+//! The config file as every command reads it: through the real binary,
+//! with `WORK_CONFIG` pointing at a file in a temp dir.
+
+use std::process::Command;
+use work::fixtures::FixtureRepo;
+
+#[test]
+fn scans_with_an_unset_github_token() {
+    let tmp = tempfile::tempdir().unwrap();
+    let repo = FixtureRepo::init(&tmp.path().join("code/api")).unwrap();
+    let hour_ago = chrono::Local::now().timestamp() - 3600;
+    repo.commit(hour_ago, "Add routes", &[("a", "1\n")])
+        .unwrap();
+    // What `work config init` writes, with the token line uncommented.
+    let config = tmp.path().join("config.toml");
+    std::fs::write(&config, "[github]\ntoken = \"${GITHUB_TOKEN}\"\n").unwrap();
+
+    let out = Command::new(env!("CARGO_BIN_EXE_work"))
+        .args(["--all", "--raw", "--no-stat-cache"])
+        .arg(tmp.path().join("code"))
+        .env("WORK_CONFIG", &config)
+        .env_remove("GITHUB_TOKEN")
+        .output()
+        .unwrap();
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert!(
+        String::from_utf8(out.stdout)
+            .unwrap()
+            .contains("Add routes")
+    );
+}
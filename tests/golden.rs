@@ -0,0 +1,75 @@
+//! Golden-file tests: run the real binary in `--deterministic` mode against
+//! fixture repos and compare its output byte for byte with `tests/golden/`.
+//! Set `UPDATE_GOLDEN=1` to rewrite the expected files after an intended
+//! output change.
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use work::fixtures::FixtureRepo;
+
+/// 2026-03-02 12:00 UTC, a Monday.
+const NOW: &str = "2026-03-02T12:00:00Z";
+const NOW_TS: i64 = 1_772_452_800;
+
+fn work(args: &[&str]) -> String {
+    let out = Command::new(env!("CARGO_BIN_EXE_work"))
+        .args(args)
+        .env("WORK_NOW", NOW)
+        .output()
+        .unwrap();
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    String::from_utf8(out.stdout).unwrap()
+}
+
+fn assert_golden(name: &str, actual: &str) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(name);
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::write(&path, actual).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(&path)
+        .unwrap_or_else(|_| panic!("missing {} (run with UPDATE_GOLDEN=1)", path.display()));
+    assert_eq!(actual, expected, "output differs from {}", path.display());
+}
+
+#[test]
+fn log_matches_golden() {
+    let tmp = tempfile::tempdir().unwrap();
+    let hour = 60 * 60;
+
+    let api = FixtureRepo::init(&tmp.path().join("api")).unwrap();
+    api.commit(
+        NOW_TS - 50 * hour,
+        "Add routes",
+        &[("src/routes.rs", "a\nb\nc\n")],
+    )
+    .unwrap();
+    api.commit(
+        NOW_TS - 3 * hour,
+        "Fix login",
+        &[("src/routes.rs", "a\nB\nc\n")],
+    )
+    .unwrap();
+
+    let web = FixtureRepo::init(&tmp.path().join("apps/web")).unwrap();
+    web.commit(
+        NOW_TS - 26 * hour,
+        "Login form",
+        &[("index.html", "<form>\n"), ("app.js", "go()\n")],
+    )
+    .unwrap();
+
+    let root = tmp.path().to_str().unwrap();
+    assert_golden("log.txt", &work(&["--deterministic", "--all", root]));
+    assert_golden(
+        "log-raw.txt",
+        &work(&["--deterministic", "--all", "-r", root]),
+    );
+}